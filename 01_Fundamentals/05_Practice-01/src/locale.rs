@@ -0,0 +1,131 @@
+//! Module with the English and Spanish texts of the program.
+//! The language comes from `--lang en|es`, or from `LC_ALL`, `LC_MESSAGES` or `LANG`.
+//! Spanish also uses a comma as the decimal separator, e.g. `36,6 °C`.
+use std::env;
+
+/// Enum to represent the languages of the program
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    En,
+    Es,
+}
+
+/// Enum to represent every text printed by the program
+#[derive(Debug, Clone, Copy)]
+pub enum Message {
+    Title,
+    SelectType,
+    CelsiusToFahrenheit,
+    CelsiusToKelvin,
+    FahrenheitToCelsius,
+    FahrenheitToKelvin,
+    KelvinToCelsius,
+    KelvinToFahrenheit,
+    Exit,
+    EnterChoice,
+    InvalidNumber,
+    Goodbye,
+    EnterCelsius,
+    EnterFahrenheit,
+    EnterKelvin,
+    InvalidOption,
+    /// Printed when the attempts to answer a question run out
+    TooManyAttempts,
+    /// The word between a temperature and its conversion
+    Is,
+}
+
+impl Locale {
+    /// Pick the language from the `--lang` argument or the environment
+    ///
+    /// # Returns
+    /// * The language to use, English if nothing matches.
+    pub fn detect() -> Locale {
+        let args: Vec<String> = env::args().collect();
+        let from_args = args
+            .iter()
+            .position(|arg| arg == "--lang")
+            .and_then(|index| args.get(index + 1))
+            .cloned();
+        let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty());
+        match from_args.or(from_env) {
+            Some(tag) if tag.to_lowercase().starts_with("es") => Locale::Es,
+            _ => Locale::En,
+        }
+    }
+
+    /// Returns the text of a message in this language
+    pub fn text(&self, message: Message) -> &'static str {
+        match (self, message) {
+            (Locale::En, Message::Title) => "------ Temperature Conversion ------",
+            (Locale::En, Message::SelectType) => "------ Select Conversion Type ------",
+            (Locale::En, Message::CelsiusToFahrenheit) => "1: Celsius to Fahrenheit",
+            (Locale::En, Message::CelsiusToKelvin) => "2: Celsius to Kelvin",
+            (Locale::En, Message::FahrenheitToCelsius) => "3: Fahrenheit to Celsius",
+            (Locale::En, Message::FahrenheitToKelvin) => "4: Fahrenheit to Kelvin",
+            (Locale::En, Message::KelvinToCelsius) => "5: Kelvin to Celsius",
+            (Locale::En, Message::KelvinToFahrenheit) => "6: Kelvin to Fahrenheit",
+            (Locale::En, Message::Exit) => "0: Exit",
+            (Locale::En, Message::EnterChoice) => "Enter your choice:",
+            (Locale::En, Message::InvalidNumber) => "Please enter a valid number!",
+            (Locale::En, Message::Goodbye) => "------ Exiting Program. Goodbye! :) ------",
+            (Locale::En, Message::EnterCelsius) => "Enter temperature in Celsius:",
+            (Locale::En, Message::EnterFahrenheit) => "Enter temperature in Fahrenheit:",
+            (Locale::En, Message::EnterKelvin) => "Enter temperature in Kelvin:",
+            (Locale::En, Message::InvalidOption) => "Please enter a valid option from the menu!",
+            (Locale::En, Message::TooManyAttempts) => "Too many invalid answers in a row.",
+            (Locale::En, Message::Is) => "is",
+            (Locale::Es, Message::Title) => "------ Conversión de temperatura ------",
+            (Locale::Es, Message::SelectType) => "------ Elige el tipo de conversión ------",
+            (Locale::Es, Message::CelsiusToFahrenheit) => "1: Celsius a Fahrenheit",
+            (Locale::Es, Message::CelsiusToKelvin) => "2: Celsius a Kelvin",
+            (Locale::Es, Message::FahrenheitToCelsius) => "3: Fahrenheit a Celsius",
+            (Locale::Es, Message::FahrenheitToKelvin) => "4: Fahrenheit a Kelvin",
+            (Locale::Es, Message::KelvinToCelsius) => "5: Kelvin a Celsius",
+            (Locale::Es, Message::KelvinToFahrenheit) => "6: Kelvin a Fahrenheit",
+            (Locale::Es, Message::Exit) => "0: Salir",
+            (Locale::Es, Message::EnterChoice) => "Elige una opción:",
+            (Locale::Es, Message::InvalidNumber) => "¡Introduce un número válido!",
+            (Locale::Es, Message::Goodbye) => "------ Saliendo del programa. ¡Adiós! :) ------",
+            (Locale::Es, Message::EnterCelsius) => "Introduce la temperatura en Celsius:",
+            (Locale::Es, Message::EnterFahrenheit) => "Introduce la temperatura en Fahrenheit:",
+            (Locale::Es, Message::EnterKelvin) => "Introduce la temperatura en Kelvin:",
+            (Locale::Es, Message::InvalidOption) => "¡Elige una opción válida del menú!",
+            (Locale::Es, Message::TooManyAttempts) => "Demasiadas respuestas no válidas seguidas.",
+            (Locale::Es, Message::Is) => "equivalen a",
+        }
+    }
+
+    /// Write a number with the decimal separator of this language
+    ///
+    /// # Arguments
+    /// * `value` - The number to write.
+    ///
+    /// # Returns
+    /// * The number as text, e.g. `36.6` or `36,6`.
+    pub fn format_number(&self, value: f64) -> String {
+        match self {
+            Locale::En => value.to_string(),
+            Locale::Es => value.to_string().replace('.', ","),
+        }
+    }
+
+    /// Read a number typed in this language
+    ///
+    /// In Spanish both `36,6` and `36.6` are accepted.
+    ///
+    /// # Arguments
+    /// * `input` - The text typed by the user.
+    ///
+    /// # Returns
+    /// * The number, or `None` if the text is not a number.
+    pub fn parse_number(&self, input: &str) -> Option<f64> {
+        match self {
+            Locale::En => input.trim().parse().ok(),
+            Locale::Es => input.trim().replace(',', ".").parse().ok(),
+        }
+    }
+}
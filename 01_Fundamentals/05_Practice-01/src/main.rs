@@ -2,189 +2,175 @@
 //!
 //! This program demonstrates basic Rust syntax and structure.
 //! Converts temperatures between Celsius, Fahrenheit, and Kelvin.
+//! The menu speaks English or Spanish, see the `locale` module.
+mod locale;
+
+use locale::{Locale, Message};
 use std::io::{self, Write}; //  Use this to handle user input and clear the console
-//Global constants for temperature conversion
-const SCALE_FACTOR: f64 = 1.8;
-const FAHRENHEIT_OFFSET: f64 = 32.0;
-const KELVIN_OFFSET: f64 = 273.15;
+// The constants and formulas are shared with Practice 2 through the `temperature_core` crate
+use temperature_core::{
+    celsius_to_fahrenheit, celsius_to_kelvin, fahrenheit_to_celsius, fahrenheit_to_kelvin,
+    kelvin_to_celsius, kelvin_to_fahrenheit,
+};
 
-/// Converts Celsius to Fahrenheit.
-///
-/// # Arguments
-/// * `celsius` - Temperature in Celsius.
-///
-/// # Returns
-/// * Temperature in Fahrenheit.
-fn celsius_to_fahrenheit(celsius: f64) -> f64 {
-    // Formula to convert Celsius to Fahrenheit
-    (celsius * SCALE_FACTOR) + FAHRENHEIT_OFFSET
-}
+/// Invalid answers accepted in a row before going back to the menu (or exiting)
+const MAX_ATTEMPTS: u32 = 5;
 
-/// Converts Celsius to Kelvin.
-///
-/// # Arguments
-/// * `celsius` - Temperature in Celsius.
-///
-/// # Returns
-/// * Temperature in Kelvin.
-fn celsius_to_kelvin(celsius: f64) -> f64 {
-    // Formula to convert Celsius to Kelvin
-    celsius + KELVIN_OFFSET
+/// Clear the console screen
+fn clear_screen() {
+    print!("\x1B[2J\x1B[1;1H");
+    io::stdout().flush().unwrap();
 }
 
-/// Converts Fahrenheit to Celsius.
-///
-/// # Arguments
-/// * `fahrenheit` - Temperature in Fahrenheit.
+/// Read one line from the standard input
 ///
 /// # Returns
-/// * Temperature in Celsius.
-fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
-    // Formula to convert Fahrenheit to Celsius
-    (fahrenheit - FAHRENHEIT_OFFSET) / SCALE_FACTOR
+/// * The line, or `None` at the end of the input or on a read error.
+fn read_line() -> Option<String> {
+    let mut line: String = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
 }
 
-/// Converts Fahrenheit to Kelvin.
+/// Read the user input and check if it's a valid option
 ///
-/// # Arguments
-/// * `fahrenheit` - Temperature in Fahrenheit.
-///
-/// # Returns
-/// * Temperature in Kelvin.
-fn fahrenheit_to_kelvin(fahrenheit: f64) -> f64 {
-    // First convert Fahrenheit to Celsius, then Celsius to Kelvin
-    let celsius = fahrenheit_to_celsius(fahrenheit);
-    celsius_to_kelvin(celsius)
-}
-
-/// Converts Kelvin to Celsius.
+/// The question is asked again after an invalid answer, at most `MAX_ATTEMPTS` times.
 ///
 /// # Arguments
-/// * `kelvin` - Temperature in Kelvin.
+/// * `locale` - Language of the prompts.
 ///
 /// # Returns
-/// * Temperature in Celsius.
-fn kelvin_to_celsius(kelvin: f64) -> f64 {
-    // Formula to convert Kelvin to Celsius
-    kelvin - KELVIN_OFFSET
+/// * A u32 representing the user's choice, or `None` when the input ended or every attempt
+///   was invalid.
+fn read_user_choice(locale: Locale) -> Option<u32> {
+    for _ in 0..MAX_ATTEMPTS {
+        println!("{}", locale.text(Message::EnterChoice));
+        let choice: String = read_line()?;
+        match choice.trim().parse() {
+            Ok(num) => return Some(num),
+            Err(_) => println!("{}", locale.text(Message::InvalidNumber)),
+        }
+    }
+    println!("{}", locale.text(Message::TooManyAttempts));
+    None
 }
 
-/// Converts Kelvin to Fahrenheit.
+/// Read the temperature input from the user
 ///
-/// # Arguments
-/// * `kelvin` - Temperature in Kelvin.
+/// The input is read again after an invalid number, at most `MAX_ATTEMPTS` times.
 ///
-/// # Returns
-/// * Temperature in Fahrenheit.
-fn kelvin_to_fahrenheit(kelvin: f64) -> f64 {
-    // First convert Kelvin to Celsius, then Celsius to Fahrenheit
-    let celsius = kelvin_to_celsius(kelvin);
-    celsius_to_fahrenheit(celsius)
-}
-
-/// Clear the console screen
-fn clear_screen() {
-    print!("\x1B[2J\x1B[1;1H");
-    io::stdout().flush().unwrap();
-}
-
-/// Read the user input and check if it's a valid option
+/// # Arguments
+/// * `locale` - Language of the prompts and decimal separator of the input.
 ///
 /// # Returns
-/// * A u32 representing the user's choice.
-fn read_user_choice() -> u32 {
-    let mut choice: String = String::new();
-    println!("Enter your choice:");
-    io::stdin()
-        .read_line(&mut choice)
-        .expect("Failed to read line");
-    let choice: u32 = match choice.trim().parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("Please enter a valid number!");
-            return read_user_choice();
+/// * A f64 representing the temperature input by the user, or `None` when the input ended
+///   or every attempt was invalid.
+fn read_temperature_input(locale: Locale) -> Option<f64> {
+    for _ in 0..MAX_ATTEMPTS {
+        let temp_input: String = read_line()?;
+        match locale.parse_number(&temp_input) {
+            Some(num) => return Some(num),
+            None => println!("{}", locale.text(Message::InvalidNumber)),
         }
-    };
-    choice
+    }
+    println!("{}", locale.text(Message::TooManyAttempts));
+    None
 }
 
-/// Read the temperature input from the user
+/// Print a conversion result with the decimal separator of the language
 ///
-/// # Returns
-/// * A f64 representing the temperature input by the user.
-fn read_temperature_input() -> f64 {
-    let mut temp_input: String = String::new();
-    io::stdin()
-        .read_line(&mut temp_input)
-        .expect("Failed to read line");
-    let temperature: f64 = match temp_input.trim().parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("Please enter a valid number!");
-            return read_temperature_input();
-        }
-    };
-    temperature
+/// # Arguments
+/// * `locale` - Language of the sentence.
+/// * `value` - The temperature typed by the user, with its symbol.
+/// * `converted` - The converted temperature, with its symbol.
+fn print_result(locale: Locale, value: (f64, &str), converted: (f64, &str)) {
+    println!(
+        "{} {} {} {} {}",
+        locale.format_number(value.0),
+        value.1,
+        locale.text(Message::Is),
+        locale.format_number(converted.0),
+        converted.1
+    );
 }
 
 fn main() {
+    // Language of the menu, from `--lang` or the environment
+    let locale = Locale::detect();
     // Flag to control the main loop
     let mut is_finished: bool = false;
     clear_screen();
 
-    println!("------ Temperature Conversion ------");
+    println!("{}", locale.text(Message::Title));
 
     // Main loop to keep the program running until the user decides to exit
     while !is_finished {
-        println!("------ Select Conversion Type ------");
-        println!("1: Celsius to Fahrenheit");
-        println!("2: Celsius to Kelvin");
-        println!("3: Fahrenheit to Celsius");
-        println!("4: Fahrenheit to Kelvin");
-        println!("5: Kelvin to Celsius");
-        println!("6: Kelvin to Fahrenheit");
-        println!("0: Exit");
+        println!("{}", locale.text(Message::SelectType));
+        println!("{}", locale.text(Message::CelsiusToFahrenheit));
+        println!("{}", locale.text(Message::CelsiusToKelvin));
+        println!("{}", locale.text(Message::FahrenheitToCelsius));
+        println!("{}", locale.text(Message::FahrenheitToKelvin));
+        println!("{}", locale.text(Message::KelvinToCelsius));
+        println!("{}", locale.text(Message::KelvinToFahrenheit));
+        println!("{}", locale.text(Message::Exit));
 
-        // Read user choice
-        let user_choice: u32 = read_user_choice();
+        // Read user choice; without a valid one there is nothing left to do
+        let Some(user_choice) = read_user_choice(locale) else {
+            println!("{}", locale.text(Message::Goodbye));
+            break;
+        };
 
         // Handle user choice only with IF statements
         if user_choice == 0 {
             is_finished = true;
-            println!("------ Exiting Program. Goodbye! :) ------");
+            println!("{}", locale.text(Message::Goodbye));
         } else if user_choice == 1 {
-            println!("Enter temperature in Celsius:");
-            let celsius: f64 = read_temperature_input();
+            println!("{}", locale.text(Message::EnterCelsius));
+            let Some(celsius) = read_temperature_input(locale) else {
+                continue;
+            };
             let fahrenheit = celsius_to_fahrenheit(celsius);
-            println!("{celsius} °C is {fahrenheit} °F");
+            print_result(locale, (celsius, "°C"), (fahrenheit, "°F"));
         } else if user_choice == 2 {
-            println!("Enter temperature in Celsius:");
-            let celsius: f64 = read_temperature_input();
+            println!("{}", locale.text(Message::EnterCelsius));
+            let Some(celsius) = read_temperature_input(locale) else {
+                continue;
+            };
             let kelvin = celsius_to_kelvin(celsius);
-            println!("{celsius} °C is {kelvin} K");
+            print_result(locale, (celsius, "°C"), (kelvin, "K"));
         } else if user_choice == 3 {
-            println!("Enter temperature in Fahrenheit:");
-            let fahrenheit: f64 = read_temperature_input();
+            println!("{}", locale.text(Message::EnterFahrenheit));
+            let Some(fahrenheit) = read_temperature_input(locale) else {
+                continue;
+            };
             let celsius = fahrenheit_to_celsius(fahrenheit);
-            println!("{fahrenheit} °F is {celsius} °C");
+            print_result(locale, (fahrenheit, "°F"), (celsius, "°C"));
         } else if user_choice == 4 {
-            println!("Enter temperature in Fahrenheit:");
-            let fahrenheit: f64 = read_temperature_input();
+            println!("{}", locale.text(Message::EnterFahrenheit));
+            let Some(fahrenheit) = read_temperature_input(locale) else {
+                continue;
+            };
             let kelvin = fahrenheit_to_kelvin(fahrenheit);
-            println!("{fahrenheit} °F is {kelvin} K");
+            print_result(locale, (fahrenheit, "°F"), (kelvin, "K"));
         } else if user_choice == 5 {
-            println!("Enter temperature in Kelvin:");
-            let kelvin: f64 = read_temperature_input();
+            println!("{}", locale.text(Message::EnterKelvin));
+            let Some(kelvin) = read_temperature_input(locale) else {
+                continue;
+            };
             let celsius = kelvin_to_celsius(kelvin);
-            println!("{kelvin} K is {celsius} °C");
+            print_result(locale, (kelvin, "K"), (celsius, "°C"));
         } else if user_choice == 6 {
-            println!("Enter temperature in Kelvin:");
-            let kelvin: f64 = read_temperature_input();
+            println!("{}", locale.text(Message::EnterKelvin));
+            let Some(kelvin) = read_temperature_input(locale) else {
+                continue;
+            };
             let fahrenheit = kelvin_to_fahrenheit(kelvin);
-            println!("{kelvin} K is {fahrenheit} °F");
+            print_result(locale, (kelvin, "K"), (fahrenheit, "°F"));
         } else {
             clear_screen();
-            println!("Please enter a valid option from the menu!");
+            println!("{}", locale.text(Message::InvalidOption));
         }
     }
 }
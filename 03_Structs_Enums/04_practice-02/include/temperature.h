@@ -0,0 +1,45 @@
+/* Generated by `temperature::ffi::header`; run the `ffi` tests with UPDATE_HEADER=1
+ * to regenerate it. */
+#ifndef TEMPERATURE_H
+#define TEMPERATURE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Temperature scales, passed as `int`. */
+#define TEMPERATURE_SCALE_CELSIUS 0 /* C */
+#define TEMPERATURE_SCALE_FAHRENHEIT 1 /* F */
+#define TEMPERATURE_SCALE_KELVIN 2 /* K */
+#define TEMPERATURE_SCALE_RANKINE 3 /* R */
+#define TEMPERATURE_SCALE_REAUMUR 4 /* Re */
+#define TEMPERATURE_SCALE_DELISLE 5 /* De */
+#define TEMPERATURE_SCALE_NEWTON 6 /* N */
+#define TEMPERATURE_SCALE_ROMER 7 /* Ro */
+
+/* Status codes returned by the functions below. */
+typedef enum temperature_status {
+    TEMPERATURE_OK = 0, /* success */
+    TEMPERATURE_UNKNOWN_SCALE = 1, /* unknown temperature scale */
+    TEMPERATURE_NOT_FINITE = 2, /* the value or its result is not a finite number */
+    TEMPERATURE_BELOW_ABSOLUTE_ZERO = 3, /* the value is below absolute zero */
+    TEMPERATURE_NULL_POINTER = 4, /* the result pointer is NULL */
+} temperature_status;
+
+/* Convert `value` from one scale to another; `*result` is only written on success. */
+temperature_status temperature_convert(double value, int from, int to, double *result);
+
+/* Write the absolute zero of a scale into `*result`. */
+temperature_status temperature_absolute_zero(int scale, double *result);
+
+/* ASCII code of a scale such as "F", or NULL; the string is static. */
+const char *temperature_scale_code(int scale);
+
+/* Description of a status code, or NULL; the string is static. */
+const char *temperature_status_message(int status);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TEMPERATURE_H */
@@ -0,0 +1,350 @@
+//! Module with the threshold alerts of the converter.
+//! A rule such as `above 100F for 3` raises an alert once that many readings in a row are
+//! past the threshold. The alert then stays up until as many readings in a row are back
+//! on the other side by more than the hysteresis, so a reading that flaps around the
+//! threshold raises one alert instead of one per reading.
+//!
+//! Rules can be written in any scale; readings are compared in kelvins, so a rule in
+//! Fahrenheit works on a stream of Celsius readings:
+//!
+//! ```
+//! use temperature::TemperatureUnit;
+//! use temperature::alert::{AlertEventKind, AlertMonitor, AlertRule};
+//!
+//! let rule: AlertRule = "above 100F for 2 hysteresis 2F".parse().unwrap();
+//! let mut log = Vec::new();
+//! let mut monitor = AlertMonitor::new(vec![rule], Box::new(&mut log));
+//! let mut kinds = Vec::new();
+//! for (line, celsius) in [38.0, 38.5, 37.9, 38.6, 36.0, 36.5].into_iter().enumerate() {
+//!     let events = monitor.observe(line + 1, None, TemperatureUnit::Celsius(celsius)).unwrap();
+//!     kinds.extend(events.iter().map(|event| (event.line, event.kind)));
+//! }
+//! assert_eq!(kinds, [(2, AlertEventKind::Raised), (6, AlertEventKind::Cleared)]);
+//! ```
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::delta::TemperatureDelta;
+use crate::expression::{self, ExpressionError};
+use crate::json;
+use crate::output::OutputFormat;
+use crate::units::TemperatureUnit;
+
+/// Enum to represent the side of the threshold that raises the alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Above,
+    Below,
+}
+
+/// Struct to represent an alert rule, e.g. `above 100 °F for 3 readings`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertRule {
+    pub direction: Direction,
+    pub threshold: TemperatureUnit,
+    /// Readings in a row past the threshold that raise the alert, and back that clear it
+    pub readings: usize,
+    /// How far back past the threshold a reading must be to count towards clearing
+    pub hysteresis: TemperatureDelta,
+}
+
+impl AlertRule {
+    /// Constructor for a rule on a single reading, with one degree of the threshold's scale
+    /// as hysteresis
+    pub fn new(direction: Direction, threshold: TemperatureUnit) -> Self {
+        AlertRule {
+            direction,
+            threshold,
+            readings: 1,
+            hysteresis: TemperatureDelta::new(threshold.unit(), 1.0),
+        }
+    }
+
+    /// Returns true if the reading is past the threshold
+    fn is_past(&self, kelvin: f64) -> bool {
+        let threshold = self.threshold.to_kelvin();
+        match self.direction {
+            Direction::Above => kelvin > threshold,
+            Direction::Below => kelvin < threshold,
+        }
+    }
+
+    /// Returns true if the reading is back from the threshold by more than the hysteresis
+    fn is_back(&self, kelvin: f64) -> bool {
+        // Delisle degrees count downwards, so only the size of the hysteresis matters
+        let margin = self.hysteresis.to_kelvin().abs();
+        let threshold = self.threshold.to_kelvin();
+        match self.direction {
+            Direction::Above => kelvin < threshold - margin,
+            Direction::Below => kelvin > threshold + margin,
+        }
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Above => "above",
+            Direction::Below => "below",
+        };
+        write!(f, "{direction} {}", self.threshold)?;
+        if self.readings > 1 {
+            write!(f, " for {} readings", self.readings)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a rule such as `above 100F`, `below 0 °C for 3` or `above 310K for 2 hysteresis 0.5K`
+///
+/// `over` and `>` also mean above, `under` and `<` below; `for N readings` may drop the
+/// last word. Decimals are written with a dot.
+impl FromStr for AlertRule {
+    type Err = ParseAlertRuleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = |kind| ParseAlertRuleError {
+            input: input.to_string(),
+            kind,
+        };
+        let trimmed = input.trim().to_lowercase();
+        let directions = [
+            ("above", Direction::Above),
+            ("over", Direction::Above),
+            (">", Direction::Above),
+            ("below", Direction::Below),
+            ("under", Direction::Below),
+            ("<", Direction::Below),
+        ];
+        let (rest, direction) = directions
+            .iter()
+            .find_map(|(word, direction)| Some((trimmed.strip_prefix(word)?, *direction)))
+            .ok_or(error(AlertRuleErrorKind::MissingDirection))?;
+
+        // Split the rest into the threshold, the `for` part and the `hysteresis` part
+        let mut parts: [Vec<&str>; 3] = Default::default();
+        let mut part = 0;
+        for word in rest.split_whitespace() {
+            match word {
+                "for" => part = 1,
+                "hysteresis" => part = 2,
+                word => parts[part].push(word),
+            }
+        }
+        let threshold = expression::parse_temperature(&parts[0].join(" "))
+            .map_err(|e| error(AlertRuleErrorKind::Threshold(e)))?;
+        let mut rule = AlertRule::new(direction, threshold);
+
+        if !parts[1].is_empty() {
+            let count = parts[1].join(" ");
+            let readings = match parts[1].as_slice() {
+                [number] | [number, "reading" | "readings"] => number.parse().ok(),
+                _ => None,
+            };
+            rule.readings = readings
+                .filter(|readings| *readings > 0)
+                .ok_or(error(AlertRuleErrorKind::InvalidCount(count)))?;
+        }
+        if !parts[2].is_empty() {
+            let margin = expression::parse_temperature(&parts[2].join(" "))
+                .map_err(|e| error(AlertRuleErrorKind::Hysteresis(e)))?;
+            rule.hysteresis = TemperatureDelta::new(margin.unit(), margin.value());
+        }
+        Ok(rule)
+    }
+}
+
+/// Enum to represent why a rule could not be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertRuleErrorKind {
+    /// The rule does not start with `above` or `below`
+    MissingDirection,
+    /// The threshold is not a temperature with its unit
+    Threshold(ExpressionError),
+    /// The number after `for` is not a whole number above zero
+    InvalidCount(String),
+    /// The hysteresis is not a temperature difference with its unit
+    Hysteresis(ExpressionError),
+}
+
+/// Struct to represent a rule that could not be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseAlertRuleError {
+    pub input: String,
+    pub kind: AlertRuleErrorKind,
+}
+
+impl fmt::Display for ParseAlertRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid alert rule `{}`: ", self.input)?;
+        match &self.kind {
+            AlertRuleErrorKind::MissingDirection => write!(f, "start it with `above` or `below`"),
+            AlertRuleErrorKind::Threshold(error) => write!(f, "{error}"),
+            AlertRuleErrorKind::InvalidCount(count) => {
+                write!(f, "`{count}` is not a number of readings")
+            }
+            AlertRuleErrorKind::Hysteresis(error) => write!(f, "hysteresis: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseAlertRuleError {}
+
+/// Enum to represent whether an alert went up or down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertEventKind {
+    Raised,
+    Cleared,
+}
+
+/// Struct to represent an alert that went up or down at a reading
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertEvent {
+    pub kind: AlertEventKind,
+    pub rule: AlertRule,
+    /// 1-based line of the reading that raised or cleared the alert
+    pub line: usize,
+    pub timestamp: Option<String>,
+    /// The reading, in the scale of the rule
+    pub reading: TemperatureUnit,
+}
+
+impl AlertEvent {
+    /// Format the event as a line for people, e.g. `alert raised at line 7: ...`
+    pub fn to_text(&self, precision: usize) -> String {
+        let kind = match self.kind {
+            AlertEventKind::Raised => "raised",
+            AlertEventKind::Cleared => "cleared",
+        };
+        let timestamp = self
+            .timestamp
+            .as_ref()
+            .map_or(String::new(), |timestamp| format!(" ({timestamp})"));
+        format!(
+            "alert {kind} at line {}{timestamp}: {:.precision$}, {}",
+            self.line, self.reading, self.rule
+        )
+    }
+
+    /// Format the event as a single-line JSON object for an alert log
+    pub fn to_json(&self, precision: usize) -> String {
+        let kind = match self.kind {
+            AlertEventKind::Raised => "raised",
+            AlertEventKind::Cleared => "cleared",
+        };
+        let timestamp = self.timestamp.as_ref().map_or(String::new(), |timestamp| {
+            format!(r#""timestamp":"{}","#, json::escape(timestamp))
+        });
+        format!(
+            r#"{{"event":"{kind}","rule":"{}","line":{},{timestamp}"reading":{{"value":{:.precision$},"unit":"{}"}}}}"#,
+            json::escape(&self.rule.to_string()),
+            self.line,
+            self.reading.value(),
+            self.reading.unit().code()
+        )
+    }
+}
+
+/// Struct to represent how far a rule is from changing its alert
+#[derive(Debug, Clone, Copy, Default)]
+struct RuleState {
+    active: bool,
+    /// Readings in a row that count towards raising, or clearing while active
+    streak: usize,
+}
+
+/// Struct to represent the rules watching a stream of readings and where their events go
+pub struct AlertMonitor<'a> {
+    rules: Vec<(AlertRule, RuleState)>,
+    log: Box<dyn Write + 'a>,
+    format: OutputFormat,
+    precision: usize,
+}
+
+impl<'a> AlertMonitor<'a> {
+    /// Constructor for a monitor writing its events as text with two decimals
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The rules to check every reading against
+    /// * `log` - Where the events are written, e.g. stderr or a log file
+    pub fn new(rules: Vec<AlertRule>, log: Box<dyn Write + 'a>) -> Self {
+        AlertMonitor {
+            rules: rules
+                .into_iter()
+                .map(|rule| (rule, RuleState::default()))
+                .collect(),
+            log,
+            format: OutputFormat::Text,
+            precision: 2,
+        }
+    }
+
+    /// Builder method to write the events as text or as one JSON object per line
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Builder method to set the decimals of the readings in the events
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Check a reading against every rule and write the events it causes
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The 1-based line of the reading
+    /// * `timestamp` - The time of the reading, when the input had one
+    /// * `reading` - The reading, in any scale
+    ///
+    /// # Returns
+    ///
+    /// * The events, in the order of the rules, once they are written to the log
+    pub fn observe(
+        &mut self,
+        line: usize,
+        timestamp: Option<&str>,
+        reading: TemperatureUnit,
+    ) -> io::Result<Vec<AlertEvent>> {
+        let kelvin = reading.to_kelvin();
+        let mut events = Vec::new();
+        for (rule, state) in &mut self.rules {
+            let counts = if state.active {
+                rule.is_back(kelvin)
+            } else {
+                rule.is_past(kelvin)
+            };
+            state.streak = if counts { state.streak + 1 } else { 0 };
+            if state.streak < rule.readings {
+                continue;
+            }
+            state.streak = 0;
+            state.active = !state.active;
+            events.push(AlertEvent {
+                kind: if state.active {
+                    AlertEventKind::Raised
+                } else {
+                    AlertEventKind::Cleared
+                },
+                rule: *rule,
+                line,
+                timestamp: timestamp.map(str::to_string),
+                reading: reading.convert_to(rule.threshold.unit()),
+            });
+        }
+        for event in &events {
+            let text = match self.format {
+                OutputFormat::Text => event.to_text(self.precision),
+                OutputFormat::Json | OutputFormat::JsonLines => event.to_json(self.precision),
+            };
+            writeln!(self.log, "{text}")?;
+        }
+        self.log.flush()?;
+        Ok(events)
+    }
+}
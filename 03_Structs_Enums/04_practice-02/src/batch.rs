@@ -0,0 +1,343 @@
+//! Module with the batch conversion of CSV/TSV files.
+//! One column of every row is converted with a `ConversionRequest`; rows that cannot be
+//! converted are written back untouched and reported with their line number.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::alert::AlertMonitor;
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Enum to represent how the temperature column is found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnSelector {
+    /// Column position, starting at 1 like `cut -f`
+    Index(usize),
+    /// Column name as written in the header row
+    Name(String),
+}
+
+impl ColumnSelector {
+    /// Parse a selector: numbers are positions, anything else is a header name
+    pub fn parse(input: &str) -> Self {
+        match input.parse::<usize>() {
+            Ok(index) => ColumnSelector::Index(index),
+            Err(_) => ColumnSelector::Name(input.to_string()),
+        }
+    }
+}
+
+/// Enum to represent where the converted values are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnMode {
+    /// Keep the original column and add the converted one at the end
+    Append,
+    /// Overwrite the original column with the converted values
+    Replace,
+}
+
+/// Struct to represent the settings of a batch conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOptions {
+    pub column: ColumnSelector,
+    pub from: ConversionUnit,
+    pub to: ConversionUnit,
+    pub delimiter: char,
+    pub has_header: bool,
+    pub mode: ColumnMode,
+    pub precision: usize,
+}
+
+impl BatchOptions {
+    /// Constructor with the usual CSV defaults: comma separated, header row, appended column
+    pub fn new(column: ColumnSelector, from: ConversionUnit, to: ConversionUnit) -> Self {
+        BatchOptions {
+            column,
+            from,
+            to,
+            delimiter: ',',
+            has_header: true,
+            mode: ColumnMode::Append,
+            precision: 2,
+        }
+    }
+}
+
+/// Enum to represent why a single row could not be converted
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowErrorKind {
+    /// The row has fewer fields than the selected column
+    MissingColumn,
+    /// The field is not a number
+    InvalidValue(String),
+    /// The field is a number but not a possible temperature
+    InvalidTemperature(TemperatureError),
+}
+
+/// Struct to represent a row that was skipped, with its 1-based line number
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub kind: RowErrorKind,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            RowErrorKind::MissingColumn => {
+                write!(f, "line {}: the row has no value in that column", self.line)
+            }
+            RowErrorKind::InvalidValue(value) => {
+                write!(f, "line {}: `{value}` is not a temperature", self.line)
+            }
+            RowErrorKind::InvalidTemperature(error) => write!(f, "line {}: {error}", self.line),
+        }
+    }
+}
+
+/// Struct to represent the outcome of a batch conversion
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchReport {
+    pub converted: usize,
+    pub errors: Vec<RowError>,
+}
+
+/// Enum to represent errors that stop the whole batch
+#[derive(Debug)]
+pub enum BatchError {
+    /// Reading the input or writing the output failed
+    Io(io::Error),
+    /// The named column is not in the header row
+    ColumnNotFound(String),
+    /// A column name was given but the input has no header row
+    NameWithoutHeader(String),
+    /// Column positions start at 1
+    ZeroIndex,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Io(error) => write!(f, "{error}"),
+            BatchError::ColumnNotFound(name) => write!(f, "no column named `{name}`"),
+            BatchError::NameWithoutHeader(name) => {
+                write!(f, "cannot find column `{name}` without a header row")
+            }
+            BatchError::ZeroIndex => write!(f, "column positions start at 1"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<io::Error> for BatchError {
+    fn from(error: io::Error) -> Self {
+        BatchError::Io(error)
+    }
+}
+
+/// Convert one column of a delimited file
+///
+/// # Arguments
+///
+/// * `reader` - The CSV/TSV input
+/// * `writer` - Where the converted rows are written
+/// * `options` - Column, units and layout of the conversion
+///
+/// # Returns
+///
+/// * A report with the number of converted rows and the rows that were skipped
+pub fn convert_delimited<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    options: &BatchOptions,
+) -> Result<BatchReport, BatchError> {
+    convert_delimited_with_alerts(reader, writer, options, None)
+}
+
+/// Convert one column of a delimited file like `convert_delimited`, checking every value
+/// against alert rules
+///
+/// # Arguments
+///
+/// * `reader` - The CSV/TSV input
+/// * `writer` - Where the converted rows are written
+/// * `options` - Column, units and layout of the conversion
+/// * `alerts` - The rules and the log of their events, if any
+///
+/// # Returns
+///
+/// * A report with the number of converted rows and the rows that were skipped
+pub fn convert_delimited_with_alerts<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    options: &BatchOptions,
+    mut alerts: Option<&mut AlertMonitor<'_>>,
+) -> Result<BatchReport, BatchError> {
+    let mut report = BatchReport::default();
+    let mut lines = reader.lines().enumerate();
+    let mut column = match &options.column {
+        ColumnSelector::Index(0) => return Err(BatchError::ZeroIndex),
+        ColumnSelector::Index(index) => Some(index - 1),
+        ColumnSelector::Name(name) if !options.has_header => {
+            return Err(BatchError::NameWithoutHeader(name.clone()));
+        }
+        ColumnSelector::Name(_) => None,
+    };
+    // Rows shorter than this are padded, so an appended cell lands under its header
+    let mut width = column.map_or(0, |index| index + 1);
+
+    if options.has_header {
+        let Some((_, header)) = lines.next() else {
+            return Ok(report);
+        };
+        let mut fields = split_record(&header?, options.delimiter);
+        if let ColumnSelector::Name(name) = &options.column {
+            let index = fields
+                .iter()
+                .position(|field| field.trim() == name)
+                .ok_or_else(|| BatchError::ColumnNotFound(name.clone()))?;
+            column = Some(index);
+        }
+        let index = column.unwrap_or_default();
+        let label = fields.get(index).map(|field| field.trim()).unwrap_or("");
+        let label = format!("{label} ({})", options.to.symbol());
+        width = fields.len();
+        write_converted(&mut fields, index, width, label, options.mode);
+        writeln!(writer, "{}", join_record(&fields, options.delimiter))?;
+    }
+
+    let index = column.unwrap_or_default();
+    for (number, line) in lines {
+        let line = line?;
+        // Blank lines carry no reading, keep them as they are
+        if line.trim().is_empty() {
+            writeln!(writer, "{line}")?;
+            continue;
+        }
+        let mut fields = split_record(&line, options.delimiter);
+        match read_field(fields.get(index), options) {
+            Ok(temperature) => {
+                let request = ConversionRequest::new(temperature, options.to);
+                let converted = format!("{:.*}", options.precision, request.convert());
+                write_converted(&mut fields, index, width, converted, options.mode);
+                report.converted += 1;
+                if let Some(alerts) = alerts.as_deref_mut() {
+                    alerts.observe(number + 1, None, temperature)?;
+                }
+            }
+            Err(kind) => {
+                // Keep the row aligned with the others by leaving the new cell empty
+                if options.mode == ColumnMode::Append {
+                    write_converted(&mut fields, index, width, String::new(), options.mode);
+                }
+                report.errors.push(RowError {
+                    line: number + 1,
+                    kind,
+                });
+            }
+        }
+        writeln!(writer, "{}", join_record(&fields, options.delimiter))?;
+    }
+    writer.flush()?;
+    Ok(report)
+}
+
+/// Read the temperature of a single field, or explain why it cannot be converted
+fn read_field(
+    field: Option<&String>,
+    options: &BatchOptions,
+) -> Result<TemperatureUnit, RowErrorKind> {
+    let field = field.ok_or(RowErrorKind::MissingColumn)?.trim();
+    let value = field
+        .parse::<f64>()
+        .map_err(|_| RowErrorKind::InvalidValue(field.to_string()))?;
+    TemperatureUnit::try_new(options.from, value).map_err(RowErrorKind::InvalidTemperature)
+}
+
+/// Put a value in the row following the column mode
+///
+/// # Arguments
+///
+/// * `fields` - The fields of the row
+/// * `index` - The column that was converted
+/// * `width` - The number of columns before appending, e.g. of the header
+/// * `value` - The converted value or the header of the new column
+/// * `mode` - Whether the value is appended or replaces the column
+fn write_converted(
+    fields: &mut Vec<String>,
+    index: usize,
+    width: usize,
+    value: String,
+    mode: ColumnMode,
+) {
+    match mode {
+        ColumnMode::Append => {
+            if fields.len() < width {
+                fields.resize(width, String::new());
+            }
+            fields.push(value);
+        }
+        ColumnMode::Replace => {
+            if let Some(field) = fields.get_mut(index) {
+                *field = value;
+            }
+        }
+    }
+}
+
+/// Split a record into fields, honouring double-quoted fields
+///
+/// # Arguments
+///
+/// * `line` - One line of the input, without the line break
+/// * `delimiter` - The field separator, e.g. `,` or `\t`
+///
+/// # Returns
+///
+/// * The unquoted fields of the record
+pub fn split_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // A doubled quote inside a quoted field is a literal quote
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Join fields into a record, quoting the ones that need it
+///
+/// # Arguments
+///
+/// * `fields` - The fields of the record
+/// * `delimiter` - The field separator, e.g. `,` or `\t`
+///
+/// # Returns
+///
+/// * The record as a single line, without the line break
+pub fn join_record(fields: &[String], delimiter: char) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains(delimiter) || field.contains('"') {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&delimiter.to_string())
+}
@@ -0,0 +1,87 @@
+//! A small local HTTP API for the temperature converter.
+//!
+//! `converter-server [--bind ADDRESS] [--port PORT]` listens on `127.0.0.1:8080` by default
+//! and answers `GET /convert?value=100&from=C&to=F` and `POST /convert/batch` with JSON.
+//! The endpoints themselves live in the `temperature::server` module.
+use std::env;
+use std::net::TcpListener;
+use std::process::ExitCode;
+use temperature::server;
+
+/// Exit code used when the server cannot listen or stops accepting connections
+const SERVER_ERROR: u8 = 1;
+/// Exit code used when the command line arguments are wrong
+const USAGE_ERROR: u8 = 2;
+
+/// Help printed by `--help`
+const USAGE: &str = "\
+Usage: converter-server [--bind ADDRESS] [--port PORT]
+
+Endpoints:
+  GET  /convert?value=100&from=C&to=F
+  POST /convert/batch  with {\"from\":\"C\",\"to\":\"F\",\"values\":[0,37,100]}
+
+Options:
+  --bind ADDRESS   Address to listen on (default: 127.0.0.1)
+  --port PORT      Port to listen on, 0 picks a free one (default: 8080)
+  -h, --help       Show this help";
+
+/// Read the address to listen on from the arguments
+///
+/// # Returns
+///
+/// * `Ok(Some(address))`, `Ok(None)` when help was asked for, or the usage error
+fn parse_address(args: &[String]) -> Result<Option<String>, String> {
+    let (mut bind, mut port) = ("127.0.0.1".to_string(), 8080_u16);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--bind" => {
+                bind = args
+                    .next()
+                    .ok_or("`--bind` needs an address, e.g. `--bind 0.0.0.0`")?
+                    .clone();
+            }
+            "--port" => {
+                let value = args.next().ok_or("`--port` needs a number")?;
+                port = value
+                    .parse()
+                    .map_err(|_| format!("`{value}` is not a port number"))?;
+            }
+            other => return Err(format!("unknown argument `{other}`")),
+        }
+    }
+    Ok(Some(format!("{bind}:{port}")))
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let address = match parse_address(&args) {
+        Ok(Some(address)) => address,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("error: cannot listen on {address}: {error}");
+            return ExitCode::from(SERVER_ERROR);
+        }
+    };
+    match listener.local_addr() {
+        Ok(local) => eprintln!("Listening on http://{local}"),
+        Err(_) => eprintln!("Listening on http://{address}"),
+    }
+    if let Err(error) = server::serve(listener) {
+        eprintln!("error: {error}");
+        return ExitCode::from(SERVER_ERROR);
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,1190 @@
+//! Module with the non-interactive command line mode.
+//! It turns the program arguments into conversion requests, so the converter can be scripted.
+//!
+//! Both of these invocations are accepted:
+//!
+//! ```text
+//! converter 98.6 F --to C
+//! converter --from K --to F 0 100 273.15
+//! converter --batch readings.csv --column temp --from C --to F
+//! converter 98.6F in C
+//! converter --table --from C --start -40 --end 100 --step 10 --format md
+//! converter --from C --to F 0 37 100 --output jsonl
+//! converter --keep-history --history conversions.json
+//! converter --lang es 36,6 C --to F
+//! converter --units
+//! converter --line
+//! converter --definitions my-units.ini 4 GM --to C
+//! converter 5 km to mi
+//! converter --stats --from C --to F 20.5 21.3 19.8
+//! converter --stats --from C --to F --input readings.txt
+//! converter --exact --from C --to F 0.1 36.6
+//! converter --follow sensor.log --from C --to F --on-malformed skip
+//! converter --follow - --from C --to F --alert "above 100F for 3" --alert-log alerts.jsonl
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::alert::{AlertRule, ParseAlertRuleError};
+use crate::batch::{BatchOptions, ColumnMode, ColumnSelector};
+use crate::exact::{ExactError, ExactTemperature, ParseRationalError, Rational};
+use crate::expression::{self, ExpressionError};
+use crate::follow::{FollowOptions, MalformedLines, ParseMalformedLinesError};
+use crate::locale::{Locale, ParseLocaleError};
+use crate::output::{OutputFormat, ParseOutputFormatError};
+use crate::registry::{ConversionError, Quantity, Registry};
+use crate::request::ConversionRequest;
+use crate::table::{TableError, TableFormat, TableOptions};
+use crate::units::{ConversionUnit, ParseUnitError, TemperatureError, TemperatureUnit};
+
+/// Usage text printed by `--help` and after argument errors
+pub const USAGE: &str = "\
+Usage: converter [VALUE...] [UNIT] --to UNIT
+       converter EXPRESSION [--to UNIT]  (e.g. `98.6F in C`, `-40F->C`, `25c`)
+       converter --from UNIT --to UNIT VALUE...
+       converter --batch FILE --column COLUMN --from UNIT --to UNIT
+       converter --table --from UNIT --start VALUE --end VALUE --step VALUE
+       converter --stats --from UNIT [--to UNIT] [VALUE... | --input FILE]
+       converter --exact --from UNIT --to UNIT VALUE...   (no rounding, e.g. `1/3`)
+       converter --follow FILE [--from UNIT] --to UNIT   (convert readings as they arrive)
+       converter [--output FORMAT] [--history FILE] [--keep-history]   (interactive menu)
+       converter --line                  (line menu even in a terminal)
+       converter --units [--output FORMAT]   (menu for lengths, masses, volumes and speeds)
+       converter [--definitions FILE] VALUE UNIT --to UNIT   (any registered unit)
+       converter --definitions FILE      (unit menu, with the scales of FILE)
+
+Run without arguments to open the interactive menu. In a terminal it is full screen:
+arrow keys pick the scales and the result follows what you type.
+
+A temperature without a target, such as `25c`, is converted into `--to`; without it
+Celsius goes into Fahrenheit and every other scale into Celsius.
+
+Options:
+  -f, --from UNIT        Scale of the input values (or give it after the values)
+  -t, --to UNIT          Scale to convert into
+  -b, --batch FILE       Convert a column of a CSV/TSV file (`-` reads stdin)
+  -c, --column COLUMN    Column to convert, by header name or position from 1
+      --replace          Overwrite the column instead of appending a new one
+      --delimiter CHAR   Field separator (default `,`, or tab for .tsv files)
+      --tsv              Same as a tab delimiter
+      --no-header        The first row is data, not column names
+      --table            Print a conversion table for a range of values
+      --start VALUE      First value of the table
+      --end VALUE        Last value of the table
+      --step VALUE       Distance between two rows of the table
+      --format FORMAT    Table format: text, markdown, csv or html (default text)
+      --stats            Print min, max, mean, median, range and standard deviation
+      --input FILE       Readings for `--stats`, one or more per line (`-` or none: stdin)
+      --exact            Convert with fractions instead of floating point; values that are
+                         not finite decimals print as fractions such as `340/9`
+      --follow FILE      Convert every new line of FILE like `tail -f` (`-` reads stdin);
+                         a line is `[TIMESTAMP] VALUE [UNIT]`, `--from` is the default unit
+      --on-malformed HOW Lines without a reading: skip, warn or stop (default warn)
+      --alert RULE       With `--follow` or `--batch`, report readings past a threshold, e.g.
+                         `above 100F for 3` or `below 0C hysteresis 0.5C` (repeatable)
+      --alert-log FILE   Append the alerts to FILE as JSON Lines instead of printing them
+  -p, --precision N      Decimal places in the results (default 2, at most 17)
+  -o, --output FORMAT    Conversion output: text, json or jsonl (default text)
+      --history FILE     Save the menu history to FILE on exit (.json or CSV)
+      --keep-history     Keep the menu history between sessions in the data directory
+      --units            Open the menu of every registered unit, not only temperatures
+      --line             Use the numbered line menu instead of the full-screen one
+      --definitions FILE Load extra units from FILE (default units.ini in the config directory)
+  -l, --lang LANG        Language of the menu and results: en or es (default from LANG)
+  -h, --help             Print this help
+
+Units: C, F, K, R, Re, De, N, Ro (names such as `celsius` also work),
+       plus lengths, masses, volumes, speeds and the units of the definitions file";
+
+/// Decimal places printed when `--precision` is not given
+pub const DEFAULT_PRECISION: usize = 2;
+
+/// Most decimal places accepted by `--precision`; an `f64` never has more significant ones
+pub const MAX_PRECISION: usize = 17;
+
+/// Enum to represent what the command line asked for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Print the usage text
+    Help,
+    /// Open the interactive menu
+    Menu(MenuArgs),
+    /// Convert every value from one scale into another
+    Convert(CliArgs),
+    /// Convert every value between two units of the registry, e.g. user-defined ones
+    ConvertUnits(UnitArgs),
+    /// Convert a column of a CSV/TSV file
+    Batch(BatchArgs),
+    /// Print a conversion table
+    Table(TableOptions),
+    /// Print summary statistics of a series of readings
+    Stats(StatsArgs),
+    /// Convert every value exactly, with fractions
+    ConvertExact(ExactArgs),
+    /// Convert the readings of a file or stdin as they arrive
+    Follow(FollowArgs),
+}
+
+/// Struct to represent a parsed conversion invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliArgs {
+    from: ConversionUnit,
+    to: ConversionUnit,
+    values: Vec<f64>,
+    precision: usize,
+    output: OutputFormat,
+    locale: Locale,
+}
+
+impl CliArgs {
+    /// Returns the scale of the input values
+    pub fn from(&self) -> ConversionUnit {
+        self.from
+    }
+
+    /// Returns the scale to convert into
+    pub fn to(&self) -> ConversionUnit {
+        self.to
+    }
+
+    /// Returns the values to convert, in the order they were given
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the number of decimal places to print
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    /// Returns how the results are printed
+    pub fn output(&self) -> OutputFormat {
+        self.output
+    }
+
+    /// Returns the language of the text results
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Builds the arguments for a single parsed expression
+    fn from_request(
+        request: ConversionRequest,
+        precision: usize,
+        output: OutputFormat,
+        locale: Locale,
+    ) -> Self {
+        CliArgs {
+            from: request.from().unit(),
+            to: request.to(),
+            values: vec![request.from().value()],
+            precision,
+            output,
+            locale,
+        }
+    }
+
+    /// Builds one conversion request per input value
+    ///
+    /// Values that are not possible temperatures, or whose result does not fit in an `f64`,
+    /// give an error in their place, so the other values can still be converted.
+    pub fn requests(&self) -> Vec<Result<ConversionRequest, TemperatureError>> {
+        self.values
+            .iter()
+            .map(|value| {
+                let temperature = TemperatureUnit::try_new(self.from, *value)?;
+                let request = ConversionRequest::new(temperature, self.to);
+                request.try_convert()?;
+                Ok(request)
+            })
+            .collect()
+    }
+}
+
+/// Struct to represent a parsed statistics invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsArgs {
+    /// Scale of the readings
+    pub from: ConversionUnit,
+    /// Scale of the summary, the scale of the readings by default
+    pub to: ConversionUnit,
+    /// Readings given as arguments; when empty they are read from `input`
+    pub values: Vec<f64>,
+    /// File with the readings, or `None` for stdin
+    pub input: Option<PathBuf>,
+    pub precision: usize,
+    pub output: OutputFormat,
+    pub locale: Locale,
+}
+
+/// Struct to represent an exact conversion invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExactArgs {
+    pub from: ConversionUnit,
+    pub to: ConversionUnit,
+    /// The values exactly as typed, e.g. `0.1` is one tenth and not the nearest `f64`
+    pub values: Vec<Rational>,
+    /// Decimal places of the approximation printed next to fractions
+    pub precision: usize,
+    pub output: OutputFormat,
+    pub locale: Locale,
+}
+
+impl ExactArgs {
+    /// Convert every value, with an error in place of the values that cannot be converted
+    pub fn conversions(&self) -> Vec<Result<(ExactTemperature, ExactTemperature), ExactError>> {
+        self.values
+            .iter()
+            .map(|value| {
+                let temperature = ExactTemperature::try_new(self.from, *value)?;
+                Ok((temperature, temperature.convert_to(self.to)?))
+            })
+            .collect()
+    }
+}
+
+/// Struct to represent a conversion between two units of the registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitArgs {
+    /// Symbol of the unit of the input values
+    pub from: String,
+    /// Symbol of the unit to convert into
+    pub to: String,
+    pub values: Vec<f64>,
+    pub precision: usize,
+    pub output: OutputFormat,
+    pub locale: Locale,
+}
+
+impl UnitArgs {
+    /// Convert every value, with an error in place of the values that cannot be converted
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - The registry the arguments were parsed with
+    pub fn conversions<'a>(
+        &self,
+        registry: &'a Registry,
+    ) -> Vec<Result<(Quantity<'a>, Quantity<'a>), ConversionError>> {
+        self.values
+            .iter()
+            .map(|value| {
+                let from = registry
+                    .find(&self.from)
+                    .ok_or_else(|| ConversionError::UnknownUnit(self.from.clone()))?;
+                let to = registry
+                    .find(&self.to)
+                    .ok_or_else(|| ConversionError::UnknownUnit(self.to.clone()))?;
+                let quantity = Quantity {
+                    value: *value,
+                    unit: from,
+                };
+                Ok((quantity, quantity.convert_to(to)?))
+            })
+            .collect()
+    }
+}
+
+/// Struct to represent the settings of the interactive menu
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MenuArgs {
+    /// How the results are printed
+    pub output: OutputFormat,
+    /// File the history is saved to on exit
+    pub history: Option<PathBuf>,
+    /// Load and save the history under the user's data directory
+    pub keep_history: bool,
+    /// Language of the menu
+    pub locale: Locale,
+    /// Open the unit registry menu instead of the temperature menu
+    pub units: bool,
+    /// Use the line menu even when the full-screen interface could be drawn
+    pub line: bool,
+}
+
+/// Struct to represent a parsed batch invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchArgs {
+    /// File to read, or `None` to read stdin
+    pub input: Option<PathBuf>,
+    pub options: BatchOptions,
+    pub alerts: AlertArgs,
+}
+
+/// Struct to represent a parsed watch mode invocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowArgs {
+    /// File to follow, or `None` to read stdin until it ends
+    pub input: Option<PathBuf>,
+    pub options: FollowOptions,
+    pub alerts: AlertArgs,
+}
+
+/// Struct to represent the alert rules of a watch or batch invocation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertArgs {
+    pub rules: Vec<AlertRule>,
+    /// File the events are appended to as JSON Lines, instead of text on stderr
+    pub log: Option<PathBuf>,
+}
+
+/// Enum to represent the ways the arguments can be wrong
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    /// A flag such as `--to` was the last argument
+    MissingFlagValue(String),
+    /// The source scale was given twice
+    DuplicateFrom,
+    /// The target scale was given twice
+    DuplicateTo,
+    /// No source scale was given
+    MissingFrom,
+    /// No target scale was given
+    MissingTo,
+    /// No value to convert was given
+    MissingValues,
+    /// A flag that the converter does not know
+    UnknownFlag(String),
+    /// An argument that is neither a number nor a unit
+    UnknownUnit(ParseUnitError),
+    /// A batch option was given without `--batch`
+    BatchOnly(String),
+    /// `--batch` was given without `--column`
+    MissingColumn,
+    /// A table option was given without `--table`
+    TableOnly(String),
+    /// `--table` was given without one of `--start`, `--end` or `--step`
+    MissingTableValue(&'static str),
+    /// Two modes such as `--batch` and `--table` were both given
+    ConflictingModes(&'static str, &'static str),
+    /// `--input` was given without `--stats`
+    StatsOnly(String),
+    /// `--on-malformed` was given without `--follow`
+    FollowOnly(String),
+    /// The `--on-malformed` value is not skip, warn or stop
+    UnknownMalformedLines(ParseMalformedLinesError),
+    /// An alert option was given without `--follow` or `--batch`
+    AlertOnly(String),
+    /// An `--alert` rule could not be read
+    InvalidAlert(ParseAlertRuleError),
+    /// Values were given together with `--batch` or `--table`
+    UnexpectedValues(&'static str),
+    /// A flag expected a number, e.g. `--step ten`
+    InvalidNumber { flag: String, value: String },
+    /// `--precision` asked for more decimals than `MAX_PRECISION`
+    PrecisionTooLarge(usize),
+    /// A value of `--exact` is not an exact number, e.g. `nan`
+    InvalidExactNumber(ParseRationalError),
+    /// A fraction such as `1/3` was given without `--exact`
+    ExactOnly(String),
+    /// The table options are wrong, e.g. an unknown `--format`
+    Table(TableError),
+    /// The `--output` format is not text, json or jsonl
+    UnknownOutput(ParseOutputFormatError),
+    /// `--output` was given together with `--batch` or `--table`
+    OutputWithMode(&'static str),
+    /// A menu option such as `--history` was given together with values to convert
+    MenuOnly(String),
+    /// A history flag was given together with `--units` or `--definitions`, whose menu
+    /// keeps no history
+    HistoryWithUnits(String),
+    /// Both units are registered but cannot be converted into each other
+    UnitConversion(ConversionError),
+    /// The `--lang` language is not in the catalog
+    UnknownLanguage(ParseLocaleError),
+    /// The delimiter is not a single character
+    InvalidDelimiter(String),
+    /// The arguments look like an expression but it could not be parsed
+    Expression {
+        input: String,
+        error: ExpressionError,
+    },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::MissingFlagValue(flag) => write!(f, "`{flag}` needs a value after it"),
+            CliError::DuplicateFrom => write!(f, "the source unit was given more than once"),
+            CliError::DuplicateTo => write!(f, "the target unit was given more than once"),
+            CliError::MissingFrom => write!(f, "missing the unit of the input values"),
+            CliError::MissingTo => write!(f, "missing the target unit, use `--to UNIT`"),
+            CliError::MissingValues => write!(f, "missing the values to convert"),
+            CliError::UnknownFlag(flag) => write!(f, "unknown option `{flag}`"),
+            CliError::UnknownUnit(error) => write!(f, "{error}"),
+            CliError::BatchOnly(flag) => write!(f, "`{flag}` only works together with `--batch`"),
+            CliError::MissingColumn => write!(f, "`--batch` needs `--column COLUMN`"),
+            CliError::TableOnly(flag) => write!(f, "`{flag}` only works together with `--table`"),
+            CliError::MissingTableValue(flag) => write!(f, "`--table` needs `{flag} VALUE`"),
+            CliError::ConflictingModes(first, second) => {
+                write!(f, "`{first}` and `{second}` cannot be combined")
+            }
+            CliError::StatsOnly(flag) => write!(f, "`{flag}` only works together with `--stats`"),
+            CliError::FollowOnly(flag) => {
+                write!(f, "`{flag}` only works together with `--follow`")
+            }
+            CliError::UnknownMalformedLines(error) => write!(f, "{error}"),
+            CliError::AlertOnly(flag) => {
+                write!(
+                    f,
+                    "`{flag}` only works together with `--follow` or `--batch`"
+                )
+            }
+            CliError::InvalidAlert(error) => write!(f, "{error}"),
+            CliError::UnexpectedValues(mode) => write!(f, "values cannot be mixed with `{mode}`"),
+            CliError::InvalidNumber { flag, value } => {
+                write!(f, "`{flag}` expects a number, got `{value}`")
+            }
+            CliError::PrecisionTooLarge(precision) => write!(
+                f,
+                "`--precision` allows at most {MAX_PRECISION} decimal places, got {precision}"
+            ),
+            CliError::InvalidExactNumber(error) => write!(f, "{error}"),
+            CliError::ExactOnly(value) => {
+                write!(
+                    f,
+                    "fractions such as `{value}` only work together with `--exact`"
+                )
+            }
+            CliError::Table(error) => write!(f, "{error}"),
+            CliError::UnknownOutput(error) => write!(f, "{error}"),
+            CliError::OutputWithMode(mode) => {
+                write!(f, "`--output` cannot be combined with `{mode}`")
+            }
+            CliError::MenuOnly(flag) => {
+                write!(f, "`{flag}` only works in the interactive menu")
+            }
+            CliError::HistoryWithUnits(flag) => {
+                write!(
+                    f,
+                    "`{flag}` cannot be combined with `--units` or `--definitions`"
+                )
+            }
+            CliError::UnitConversion(error) => write!(f, "{error}"),
+            CliError::UnknownLanguage(error) => write!(f, "{error}"),
+            CliError::InvalidDelimiter(delimiter) => {
+                write!(f, "the delimiter `{delimiter}` must be a single character")
+            }
+            CliError::Expression { input, error } => {
+                write!(f, "invalid expression\n{}", error.highlight(input))
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<TableError> for CliError {
+    fn from(error: TableError) -> Self {
+        CliError::Table(error)
+    }
+}
+
+impl From<ParseOutputFormatError> for CliError {
+    fn from(error: ParseOutputFormatError) -> Self {
+        CliError::UnknownOutput(error)
+    }
+}
+
+impl From<ParseAlertRuleError> for CliError {
+    fn from(error: ParseAlertRuleError) -> Self {
+        CliError::InvalidAlert(error)
+    }
+}
+
+impl From<ParseMalformedLinesError> for CliError {
+    fn from(error: ParseMalformedLinesError) -> Self {
+        CliError::UnknownMalformedLines(error)
+    }
+}
+
+impl From<ParseLocaleError> for CliError {
+    fn from(error: ParseLocaleError) -> Self {
+        CliError::UnknownLanguage(error)
+    }
+}
+
+impl From<ParseUnitError> for CliError {
+    fn from(error: ParseUnitError) -> Self {
+        CliError::UnknownUnit(error)
+    }
+}
+
+/// Parse the program arguments, without the program name, in English
+///
+/// Arguments that are not valid flags are tried as a single expression,
+/// so `converter 300 kelvin in fahrenheit` works too, and so does `converter 25c`.
+///
+/// # Arguments
+///
+/// * `args` - The arguments given after the program name
+///
+/// # Returns
+///
+/// * The requested command, or the first problem found in the arguments
+pub fn parse_args<S: AsRef<str>>(args: &[S]) -> Result<Command, CliError> {
+    parse_args_with_locale(args, Locale::En)
+}
+
+/// Parse the program arguments, reading numbers in the given language unless `--lang` is used
+///
+/// # Arguments
+///
+/// * `args` - The arguments given after the program name
+/// * `default_locale` - Language used without `--lang`, usually `Locale::from_env()`
+///
+/// # Returns
+///
+/// * The requested command, or the first problem found in the arguments
+pub fn parse_args_with_locale<S: AsRef<str>>(
+    args: &[S],
+    default_locale: Locale,
+) -> Result<Command, CliError> {
+    let locale = find_locale(args)?.unwrap_or(default_locale);
+    parse_flags(args, locale).or_else(|flags_error| match parse_expression_args(args, locale)? {
+        Some(cli_args) => Ok(Command::Convert(cli_args)),
+        None => Err(flags_error),
+    })
+}
+
+/// Parse the arguments as one expression, with the flags that shape its result
+///
+/// `--to` gives the target of a temperature written without one, e.g. `25c --to K`.
+///
+/// # Returns
+///
+/// * `None` if the arguments are not an expression, e.g. they use the flags of another mode
+fn parse_expression_args<S: AsRef<str>>(
+    args: &[S],
+    locale: Locale,
+) -> Result<Option<CliArgs>, CliError> {
+    let mut to: Option<&str> = None;
+    let mut precision = DEFAULT_PRECISION;
+    let mut output = OutputFormat::Text;
+    let mut words: Vec<&str> = Vec::new();
+
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        match arg {
+            // Parsed once the words turn out to be a temperature
+            "-t" | "--to" => {
+                if to.replace(next_value(&mut args, arg)?).is_some() {
+                    return Err(CliError::DuplicateTo);
+                }
+            }
+            "-p" | "--precision" => {
+                let value = next_value(&mut args, arg)?;
+                precision = parse_precision(arg, value)?;
+            }
+            "-o" | "--output" => output = next_value(&mut args, arg)?.parse()?,
+            // Already read by `find_locale`
+            "-l" | "--lang" => {
+                next_value(&mut args, arg)?;
+            }
+            // Any other flag belongs to another mode; `-40F->C` is still a value
+            _ if arg.starts_with("--") || matches!(arg, "-f" | "-b" | "-c" | "-h") => {
+                return Ok(None);
+            }
+            _ => words.push(arg),
+        }
+    }
+
+    let input = words.join(" ");
+    let normalized = locale.normalize_decimals(&input);
+    let request = if expression::has_connector(&input) {
+        // Only blame the expression if the user clearly wrote one
+        let request = expression::parse_expression(&normalized)
+            .map_err(|error| CliError::Expression { input, error })?;
+        if to.is_some() {
+            return Err(CliError::DuplicateTo);
+        }
+        request
+    } else {
+        let Ok(temperature) = expression::parse_temperature(&normalized) else {
+            return Ok(None);
+        };
+        let to = match to {
+            Some(to) => to.parse()?,
+            None => default_target(temperature.unit()),
+        };
+        ConversionRequest::new(temperature, to)
+    };
+    Ok(Some(CliArgs::from_request(
+        request, precision, output, locale,
+    )))
+}
+
+/// Scale a temperature given without a target is converted into
+///
+/// # Arguments
+///
+/// * `from` - The scale of the temperature
+///
+/// # Returns
+///
+/// * Fahrenheit for Celsius, Celsius for every other scale
+fn default_target(from: ConversionUnit) -> ConversionUnit {
+    match from {
+        ConversionUnit::ToCelsius => ConversionUnit::ToFahrenheit,
+        _ => ConversionUnit::ToCelsius,
+    }
+}
+
+/// Parse the program arguments, falling back to the units of the registry
+///
+/// Temperatures keep the full command line of `parse_args_with_locale`; only arguments that
+/// it rejects are tried as `VALUE... UNIT --to UNIT` or `VALUE UNIT to UNIT` over the registry,
+/// which holds the lengths, masses, volumes, speeds and user-defined units.
+///
+/// # Arguments
+///
+/// * `args` - The arguments given after the program name
+/// * `default_locale` - Language used without `--lang`
+/// * `registry` - The known units, e.g. `Registry::builtin()` plus a definitions file
+///
+/// # Returns
+///
+/// * The requested command, or the first problem found in the arguments
+pub fn parse_args_with_registry<S: AsRef<str>>(
+    args: &[S],
+    default_locale: Locale,
+    registry: &Registry,
+) -> Result<Command, CliError> {
+    parse_args_with_locale(args, default_locale).or_else(|error| {
+        let locale = find_locale(args)?.unwrap_or(default_locale);
+        match parse_unit_args(args, locale, registry) {
+            Ok(Some(unit_args)) => Ok(Command::ConvertUnits(unit_args)),
+            Err(unit_error @ (CliError::UnitConversion(_) | CliError::PrecisionTooLarge(_))) => {
+                Err(unit_error)
+            }
+            // Anything else is better explained by the temperature parser
+            _ => Err(error),
+        }
+    })
+}
+
+/// Find the `--definitions` file before parsing, since it decides which units exist
+pub fn definitions_path<S: AsRef<str>>(args: &[S]) -> Result<Option<PathBuf>, CliError> {
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        if arg == "--definitions" {
+            return Ok(Some(PathBuf::from(next_value(&mut args, arg)?)));
+        }
+    }
+    Ok(None)
+}
+
+/// Parse the arguments as a conversion between two units of the registry
+///
+/// # Returns
+///
+/// * `None` if the arguments do not name two registered units
+fn parse_unit_args<S: AsRef<str>>(
+    args: &[S],
+    locale: Locale,
+    registry: &Registry,
+) -> Result<Option<UnitArgs>, CliError> {
+    let mut from: Option<String> = None;
+    let mut to: Option<String> = None;
+    let mut values: Vec<f64> = Vec::new();
+    let mut precision = DEFAULT_PRECISION;
+    let mut output = OutputFormat::Text;
+    // Every argument that is not a flag, for the `VALUE UNIT to UNIT` form
+    let mut words: Vec<&str> = Vec::new();
+    let mut unit_words: Vec<&str> = Vec::new();
+
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-f" | "--from" => from = Some(next_value(&mut args, arg)?.to_string()),
+            "-t" | "--to" => to = Some(next_value(&mut args, arg)?.to_string()),
+            "-p" | "--precision" => {
+                let value = next_value(&mut args, arg)?;
+                precision = parse_precision(arg, value)?;
+            }
+            "-o" | "--output" => output = next_value(&mut args, arg)?.parse()?,
+            "-l" | "--lang" | "--definitions" => {
+                next_value(&mut args, arg)?;
+            }
+            _ => {
+                if let Some(value) = locale.parse_number(arg) {
+                    values.push(value);
+                } else if arg.starts_with('-') && arg != "->" {
+                    return Ok(None);
+                } else {
+                    unit_words.push(arg);
+                }
+                words.push(arg);
+            }
+        }
+    }
+
+    let Some(to) = to else {
+        if from.is_some() {
+            return Ok(None);
+        }
+        let input = locale.normalize_decimals(&words.join(" "));
+        return match registry.parse_conversion(&input) {
+            Ok((quantity, converted)) => Ok(Some(UnitArgs {
+                from: quantity.unit.symbol.clone(),
+                to: converted.unit.symbol.clone(),
+                values: vec![quantity.value],
+                precision,
+                output,
+                locale,
+            })),
+            Err(ConversionError::UnknownUnit(_) | ConversionError::InvalidExpression(_)) => {
+                Ok(None)
+            }
+            Err(error) => Err(CliError::UnitConversion(error)),
+        };
+    };
+    let from = from.unwrap_or_else(|| unit_words.join(" "));
+    let (Some(from), Some(to)) = (registry.find(&from), registry.find(&to)) else {
+        return Ok(None);
+    };
+    if values.is_empty() {
+        return Ok(None);
+    }
+    if from.dimension != to.dimension {
+        return Err(CliError::UnitConversion(
+            ConversionError::IncompatibleDimensions {
+                from: from.dimension,
+                to: to.dimension,
+            },
+        ));
+    }
+    Ok(Some(UnitArgs {
+        from: from.symbol.clone(),
+        to: to.symbol.clone(),
+        values,
+        precision,
+        output,
+        locale,
+    }))
+}
+
+/// Find the `--lang` flag first, since values before it are read in its language
+fn find_locale<S: AsRef<str>>(args: &[S]) -> Result<Option<Locale>, CliError> {
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        if arg == "-l" || arg == "--lang" {
+            return Ok(Some(next_value(&mut args, arg)?.parse()?));
+        }
+    }
+    Ok(None)
+}
+
+/// Parse the arguments as values, units and flags
+fn parse_flags<S: AsRef<str>>(args: &[S], locale: Locale) -> Result<Command, CliError> {
+    let mut from: Option<ConversionUnit> = None;
+    let mut to: Option<ConversionUnit> = None;
+    let mut values: Vec<f64> = Vec::new();
+    let mut precision = DEFAULT_PRECISION;
+    let mut output: Option<OutputFormat> = None;
+    // Menu settings, only valid when nothing is converted from the arguments
+    let mut history: Option<PathBuf> = None;
+    let mut keep_history = false;
+    let mut units = false;
+    let mut line = false;
+    let mut definitions = false;
+    let mut menu_only_flag: Option<String> = None;
+    // Batch mode settings, only valid together with `--batch`
+    let mut batch: Option<String> = None;
+    let mut column: Option<String> = None;
+    let mut delimiter: Option<char> = None;
+    let mut mode = ColumnMode::Append;
+    let mut has_header = true;
+    let mut batch_only_flag: Option<String> = None;
+    // Table mode settings, only valid together with `--table`
+    let mut table = false;
+    let mut start: Option<f64> = None;
+    let mut end: Option<f64> = None;
+    let mut step: Option<f64> = None;
+    let mut format = TableFormat::Text;
+    let mut table_only_flag: Option<String> = None;
+    // Statistics settings, only valid together with `--stats`
+    let mut stats = false;
+    let mut stats_input: Option<String> = None;
+    // Watch mode settings, only valid together with `--follow`
+    let mut follow: Option<String> = None;
+    let mut on_malformed = MalformedLines::default();
+    let mut follow_only_flag: Option<String> = None;
+    // Alert settings, valid with `--follow` and `--batch`
+    let mut alerts = AlertArgs::default();
+    let mut alert_flag: Option<String> = None;
+    // Exact mode keeps the values as typed, fractions included
+    let mut exact = false;
+    let mut exact_values: Vec<String> = Vec::new();
+    let mut fraction: Option<String> = None;
+
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-f" | "--from" => {
+                let unit = next_value(&mut args, arg)?.parse()?;
+                set_once(&mut from, unit, CliError::DuplicateFrom)?;
+            }
+            "-t" | "--to" => {
+                let unit = next_value(&mut args, arg)?.parse()?;
+                set_once(&mut to, unit, CliError::DuplicateTo)?;
+            }
+            "-p" | "--precision" => {
+                let value = next_value(&mut args, arg)?;
+                precision = parse_precision(arg, value)?;
+            }
+            // Already read by `find_locale`
+            "-l" | "--lang" => {
+                next_value(&mut args, arg)?;
+            }
+            // Already loaded through `definitions_path`; on its own it opens the unit menu,
+            // the only one that lists the scales of the file
+            "--definitions" => {
+                next_value(&mut args, arg)?;
+                definitions = true;
+            }
+            "-o" | "--output" => output = Some(next_value(&mut args, arg)?.parse()?),
+            "--history" => {
+                history = Some(PathBuf::from(next_value(&mut args, arg)?));
+                menu_only_flag.get_or_insert(arg.to_string());
+            }
+            "--keep-history" => {
+                keep_history = true;
+                menu_only_flag.get_or_insert(arg.to_string());
+            }
+            "--units" => {
+                units = true;
+                menu_only_flag.get_or_insert(arg.to_string());
+            }
+            "--line" => {
+                line = true;
+                menu_only_flag.get_or_insert(arg.to_string());
+            }
+            "-b" | "--batch" => batch = Some(next_value(&mut args, arg)?.to_string()),
+            "-c" | "--column" => {
+                column = Some(next_value(&mut args, arg)?.to_string());
+                batch_only_flag.get_or_insert(arg.to_string());
+            }
+            "--delimiter" => {
+                let value = next_value(&mut args, arg)?;
+                delimiter = Some(parse_delimiter(value)?);
+                batch_only_flag.get_or_insert(arg.to_string());
+            }
+            "--tsv" => {
+                delimiter = Some('\t');
+                batch_only_flag.get_or_insert(arg.to_string());
+            }
+            "--replace" => {
+                mode = ColumnMode::Replace;
+                batch_only_flag.get_or_insert(arg.to_string());
+            }
+            "--no-header" => {
+                has_header = false;
+                batch_only_flag.get_or_insert(arg.to_string());
+            }
+            "--table" => table = true,
+            "--stats" => stats = true,
+            "--exact" => exact = true,
+            "--input" => stats_input = Some(next_value(&mut args, arg)?.to_string()),
+            "--follow" => follow = Some(next_value(&mut args, arg)?.to_string()),
+            "--alert" => {
+                let rule = locale.normalize_decimals(next_value(&mut args, arg)?);
+                alerts.rules.push(rule.parse()?);
+                alert_flag.get_or_insert(arg.to_string());
+            }
+            "--alert-log" => {
+                alerts.log = Some(PathBuf::from(next_value(&mut args, arg)?));
+                alert_flag.get_or_insert(arg.to_string());
+            }
+            "--on-malformed" => {
+                on_malformed = next_value(&mut args, arg)?.parse()?;
+                follow_only_flag.get_or_insert(arg.to_string());
+            }
+            "--start" | "--end" | "--step" => {
+                let value = next_number(&mut args, arg, locale)?;
+                match arg {
+                    "--start" => start = Some(value),
+                    "--end" => end = Some(value),
+                    _ => step = Some(value),
+                }
+                table_only_flag.get_or_insert(arg.to_string());
+            }
+            "--format" => {
+                format = next_value(&mut args, arg)?.parse()?;
+                table_only_flag.get_or_insert(arg.to_string());
+            }
+            _ => {
+                // Numbers are values (negative ones too), anything else must be the source unit
+                let normalized = locale.normalize_decimals(arg.trim());
+                if let Some(value) = locale.parse_number(arg) {
+                    values.push(value);
+                    exact_values.push(normalized);
+                } else if normalized.contains('/') && normalized.parse::<Rational>().is_ok() {
+                    fraction.get_or_insert(arg.to_string());
+                    exact_values.push(normalized);
+                } else if arg.starts_with('-') {
+                    return Err(CliError::UnknownFlag(arg.to_string()));
+                } else {
+                    set_once(&mut from, arg.parse()?, CliError::DuplicateFrom)?;
+                }
+            }
+        }
+    }
+
+    // Options of one mode make no sense in the others
+    if let (Some(flag), None) = (batch_only_flag, &batch) {
+        return Err(CliError::BatchOnly(flag));
+    }
+    if let (Some(flag), false) = (table_only_flag, table) {
+        return Err(CliError::TableOnly(flag));
+    }
+    if stats_input.is_some() && !stats {
+        return Err(CliError::StatsOnly("--input".to_string()));
+    }
+    if let (Some(flag), None) = (follow_only_flag, &follow) {
+        return Err(CliError::FollowOnly(flag));
+    }
+    if let Some(flag) = alert_flag
+        && follow.is_none()
+        && batch.is_none()
+    {
+        return Err(CliError::AlertOnly(flag));
+    }
+    if let (Some(value), false) = (fraction, exact) {
+        return Err(CliError::ExactOnly(value));
+    }
+    if exact {
+        if table {
+            return Err(CliError::ConflictingModes("--exact", "--table"));
+        }
+        if batch.is_some() {
+            return Err(CliError::ConflictingModes("--exact", "--batch"));
+        }
+        if stats {
+            return Err(CliError::ConflictingModes("--exact", "--stats"));
+        }
+    }
+    if stats {
+        if table {
+            return Err(CliError::ConflictingModes("--stats", "--table"));
+        }
+        if batch.is_some() {
+            return Err(CliError::ConflictingModes("--stats", "--batch"));
+        }
+    }
+    if follow.is_some() {
+        let modes = [
+            ("--batch", batch.is_some()),
+            ("--table", table),
+            ("--stats", stats),
+            ("--exact", exact),
+        ];
+        if let Some((mode, _)) = modes.into_iter().find(|(_, given)| *given) {
+            return Err(CliError::ConflictingModes("--follow", mode));
+        }
+    }
+    if output.is_some() {
+        if table {
+            return Err(CliError::OutputWithMode("--table"));
+        }
+        if batch.is_some() {
+            return Err(CliError::OutputWithMode("--batch"));
+        }
+    }
+    // Menu settings without anything to convert open the interactive menu
+    let is_menu = output.is_some() || menu_only_flag.is_some() || definitions;
+    let nothing_to_convert = from.is_none() && to.is_none() && exact_values.is_empty();
+    let other_mode = batch.is_some() || table || stats || follow.is_some();
+    if is_menu && nothing_to_convert && !other_mode {
+        let units = units || definitions;
+        if units {
+            if let Some(path) = &history {
+                return Err(CliError::HistoryWithUnits(format!(
+                    "--history {}",
+                    path.display()
+                )));
+            }
+            if keep_history {
+                return Err(CliError::HistoryWithUnits("--keep-history".to_string()));
+            }
+        }
+        return Ok(Command::Menu(MenuArgs {
+            output: output.unwrap_or_default(),
+            history,
+            keep_history,
+            locale,
+            units,
+            line,
+        }));
+    }
+    if let Some(flag) = menu_only_flag {
+        return Err(CliError::MenuOnly(flag));
+    }
+
+    // Readings of the stream may carry their own unit, so `--from` is only the default
+    if let Some(follow) = follow {
+        if !exact_values.is_empty() {
+            return Err(CliError::UnexpectedValues("--follow"));
+        }
+        let mut options = FollowOptions::new(from, to.ok_or(CliError::MissingTo)?);
+        options.precision = precision;
+        options.output = output.unwrap_or_default();
+        options.locale = locale;
+        options.on_malformed = on_malformed;
+        let input = (follow != "-").then(|| PathBuf::from(&follow));
+        return Ok(Command::Follow(FollowArgs {
+            input,
+            options,
+            alerts,
+        }));
+    }
+    let from = from.ok_or(CliError::MissingFrom)?;
+
+    if table {
+        if batch.is_some() {
+            return Err(CliError::ConflictingModes("--batch", "--table"));
+        }
+        if !values.is_empty() {
+            return Err(CliError::UnexpectedValues("--table"));
+        }
+        let mut options = TableOptions::new(
+            from,
+            start.ok_or(CliError::MissingTableValue("--start"))?,
+            end.ok_or(CliError::MissingTableValue("--end"))?,
+            step.ok_or(CliError::MissingTableValue("--step"))?,
+        );
+        // `--to` narrows the table down to a single target column
+        if let Some(to) = to {
+            options.targets = vec![to];
+        }
+        options.precision = precision;
+        options.format = format;
+        return Ok(Command::Table(options));
+    }
+
+    if stats {
+        if !values.is_empty() && stats_input.is_some() {
+            return Err(CliError::UnexpectedValues("--input"));
+        }
+        return Ok(Command::Stats(StatsArgs {
+            from,
+            to: to.unwrap_or(from),
+            values,
+            input: stats_input.filter(|input| input != "-").map(PathBuf::from),
+            precision,
+            output: output.unwrap_or_default(),
+            locale,
+        }));
+    }
+
+    let to = to.ok_or(CliError::MissingTo)?;
+    let Some(batch) = batch else {
+        if exact_values.is_empty() {
+            return Err(CliError::MissingValues);
+        }
+        if exact {
+            let values = exact_values
+                .iter()
+                .map(|value| value.parse())
+                .collect::<Result<_, _>>()
+                .map_err(CliError::InvalidExactNumber)?;
+            return Ok(Command::ConvertExact(ExactArgs {
+                from,
+                to,
+                values,
+                precision,
+                output: output.unwrap_or_default(),
+                locale,
+            }));
+        }
+        return Ok(Command::Convert(CliArgs {
+            from,
+            to,
+            values,
+            precision,
+            output: output.unwrap_or_default(),
+            locale,
+        }));
+    };
+
+    if !values.is_empty() {
+        return Err(CliError::UnexpectedValues("--batch"));
+    }
+    let column = column.ok_or(CliError::MissingColumn)?;
+    let input = (batch != "-").then(|| PathBuf::from(&batch));
+    let is_tsv = batch.to_lowercase().ends_with(".tsv");
+    let mut options = BatchOptions::new(ColumnSelector::parse(&column), from, to);
+    options.delimiter = delimiter.unwrap_or(if is_tsv { '\t' } else { ',' });
+    options.has_header = has_header;
+    options.mode = mode;
+    options.precision = precision;
+    Ok(Command::Batch(BatchArgs {
+        input,
+        options,
+        alerts,
+    }))
+}
+
+/// Read the number that must follow a flag such as `--step`, in the language's decimals
+fn next_number<'a>(
+    args: &mut impl Iterator<Item = &'a str>,
+    flag: &str,
+    locale: Locale,
+) -> Result<f64, CliError> {
+    let value = next_value(args, flag)?;
+    locale
+        .parse_number(value)
+        .ok_or_else(|| invalid_number(flag, value))
+}
+
+/// Read the decimal places of `--precision`, up to `MAX_PRECISION`
+fn parse_precision(flag: &str, value: &str) -> Result<usize, CliError> {
+    let precision = value.parse().map_err(|_| invalid_number(flag, value))?;
+    if precision > MAX_PRECISION {
+        return Err(CliError::PrecisionTooLarge(precision));
+    }
+    Ok(precision)
+}
+
+/// Build the error for a flag whose value is not a number
+fn invalid_number(flag: &str, value: &str) -> CliError {
+    CliError::InvalidNumber {
+        flag: flag.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Read the value that must follow a flag such as `--to`
+fn next_value<'a>(
+    args: &mut impl Iterator<Item = &'a str>,
+    flag: &str,
+) -> Result<&'a str, CliError> {
+    args.next()
+        .ok_or_else(|| CliError::MissingFlagValue(flag.to_string()))
+}
+
+/// Parse a delimiter, accepting `\t` and `tab` for tabs
+fn parse_delimiter(value: &str) -> Result<char, CliError> {
+    if value == "\\t" || value.eq_ignore_ascii_case("tab") {
+        return Ok('\t');
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(CliError::InvalidDelimiter(value.to_string())),
+    }
+}
+
+/// Store a unit, failing if it was already given
+fn set_once(
+    slot: &mut Option<ConversionUnit>,
+    unit: ConversionUnit,
+    duplicate: CliError,
+) -> Result<(), CliError> {
+    if slot.replace(unit).is_some() {
+        return Err(duplicate);
+    }
+    Ok(())
+}
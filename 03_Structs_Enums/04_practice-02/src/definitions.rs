@@ -0,0 +1,423 @@
+//! Module with user-defined units read from a definitions file.
+//! The file uses INI sections, which are also valid TOML: one section per unit, named after
+//! the unit, with its symbol, optional aliases, and the linear `factor` and `offset` that
+//! turn a reading into the base unit (`kelvin = value * factor + offset` for temperatures).
+//! Numbers may be written with the constants of `formulas`, so a scale can be defined the
+//! way the built-in ones are:
+//!
+//! ```text
+//! # Gas mark 1 is 275 °F and every mark adds 25 °F
+//! [gas mark]
+//! symbol = "GM"
+//! aliases = ["gasmark", "mark"]
+//! factor = 25 / SCALE_FACTOR
+//! offset = 250 / SCALE_FACTOR + KELVIN_OFFSET - FAHRENHEIT_OFFSET / SCALE_FACTOR
+//!
+//! [fathom]
+//! dimension = "length"
+//! symbol = "ftm"
+//! factor = 1.8288
+//! ```
+//!
+//! Every definition is checked before it is registered: the factor cannot be zero, and no
+//! symbol, name or alias may clash with a built-in unit or with another definition.
+//!
+//! ```
+//! use temperature::definitions;
+//! use temperature::registry::Registry;
+//!
+//! let mut registry = Registry::builtin();
+//! let text = "[gas mark]\nsymbol = GM\nfactor = 25 / SCALE_FACTOR\n\
+//!             offset = 250 / SCALE_FACTOR + KELVIN_OFFSET - FAHRENHEIT_OFFSET / SCALE_FACTOR\n";
+//! definitions::register(&mut registry, &definitions::parse(text).unwrap()).unwrap();
+//! let fahrenheit = registry.convert(4.0, "GM", "F").unwrap();
+//! assert!((fahrenheit - 350.0).abs() < 1e-9);
+//! ```
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::formulas::{FAHRENHEIT_OFFSET, KELVIN_BOILING_POINT, KELVIN_OFFSET, SCALE_FACTOR};
+use crate::registry::{Dimension, Registry, RegistryError, Unit};
+
+/// Constants that can be used by name in the numbers of a definition
+const CONSTANTS: [(&str, f64); 4] = [
+    ("SCALE_FACTOR", SCALE_FACTOR),
+    ("FAHRENHEIT_OFFSET", FAHRENHEIT_OFFSET),
+    ("KELVIN_OFFSET", KELVIN_OFFSET),
+    ("KELVIN_BOILING_POINT", KELVIN_BOILING_POINT),
+];
+
+/// Struct to represent one unit of the file, with the line its section starts on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub line: usize,
+    pub unit: Unit,
+}
+
+/// Enum to represent the mistakes a definitions file can have
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefinitionErrorKind {
+    /// A line that is neither a section, a `key = value` pair nor a comment
+    UnexpectedLine(String),
+    /// A `key = value` pair before the first section
+    OutsideSection,
+    /// A key that definitions do not have
+    UnknownKey(String),
+    /// The same key twice in one section
+    DuplicateKey(String),
+    /// A required key is missing from the section
+    MissingKey(&'static str),
+    /// A value that should be a number, e.g. `factor = fast`
+    InvalidNumber(String),
+    /// A `dimension` the registry does not know
+    UnknownDimension(String),
+    /// The unit is valid on its own but cannot be registered, e.g. a taken symbol
+    Registry(RegistryError),
+}
+
+/// Struct to represent a mistake in a definitions file and the line it is on
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefinitionError {
+    pub line: usize,
+    pub kind: DefinitionErrorKind,
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            DefinitionErrorKind::UnexpectedLine(line) => {
+                write!(f, "expected `[name]` or `key = value`, got `{line}`")
+            }
+            DefinitionErrorKind::OutsideSection => {
+                write!(f, "values must come after a `[name]` section")
+            }
+            DefinitionErrorKind::UnknownKey(key) => write!(
+                f,
+                "unknown key `{key}`, use symbol, aliases, dimension, factor or offset"
+            ),
+            DefinitionErrorKind::DuplicateKey(key) => write!(f, "`{key}` is given twice"),
+            DefinitionErrorKind::MissingKey(key) => write!(f, "the unit needs a `{key}`"),
+            DefinitionErrorKind::InvalidNumber(value) => write!(f, "`{value}` is not a number"),
+            DefinitionErrorKind::UnknownDimension(value) => write!(
+                f,
+                "unknown dimension `{value}`, use temperature, length, mass, volume or speed"
+            ),
+            DefinitionErrorKind::Registry(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+/// Enum to represent why a definitions file could not be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read
+    Io(io::Error),
+    /// The file has a mistake
+    Definition(DefinitionError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "{error}"),
+            LoadError::Definition(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Struct to represent the keys of a section while it is being read
+#[derive(Default)]
+struct Section {
+    name: String,
+    line: usize,
+    symbol: Option<String>,
+    aliases: Option<Vec<String>>,
+    dimension: Option<Dimension>,
+    factor: Option<f64>,
+    offset: Option<f64>,
+}
+
+impl Section {
+    /// Turn the finished section into a unit, checking the required keys
+    fn into_definition(self) -> Result<Definition, DefinitionError> {
+        let missing = |key| DefinitionError {
+            line: self.line,
+            kind: DefinitionErrorKind::MissingKey(key),
+        };
+        let symbol = self.symbol.clone().ok_or_else(|| missing("symbol"))?;
+        let factor = self.factor.ok_or_else(|| missing("factor"))?;
+        let aliases: Vec<&str> = self.aliases.iter().flatten().map(String::as_str).collect();
+        let unit = Unit::new(
+            &symbol,
+            &self.name,
+            self.dimension.unwrap_or(Dimension::Temperature),
+            factor,
+        )
+        .with_offset(self.offset.unwrap_or(0.0))
+        .with_aliases(&aliases);
+        Ok(Definition {
+            line: self.line,
+            unit,
+        })
+    }
+}
+
+/// Parse the text of a definitions file
+///
+/// Only the syntax is checked here; `register` checks the units themselves.
+///
+/// # Arguments
+///
+/// * `text` - The whole file
+///
+/// # Returns
+///
+/// * One definition per section, in file order, or the first mistake found
+pub fn parse(text: &str) -> Result<Vec<Definition>, DefinitionError> {
+    let mut definitions = Vec::new();
+    let mut section: Option<Section> = None;
+    for (index, raw) in text.lines().enumerate() {
+        let line = index + 1;
+        let error = |kind| DefinitionError { line, kind };
+        let content = strip_comment(raw).trim();
+        if content.is_empty() {
+            continue;
+        }
+        if let Some(name) = content
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            if let Some(finished) = section.take() {
+                definitions.push(finished.into_definition()?);
+            }
+            section = Some(Section {
+                name: unquote(name.trim()).to_string(),
+                line,
+                ..Section::default()
+            });
+            continue;
+        }
+        let Some((key, value)) = content.split_once('=') else {
+            return Err(error(DefinitionErrorKind::UnexpectedLine(
+                content.to_string(),
+            )));
+        };
+        let Some(current) = section.as_mut() else {
+            return Err(error(DefinitionErrorKind::OutsideSection));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let duplicate = || error(DefinitionErrorKind::DuplicateKey(key.to_string()));
+        match key {
+            "symbol" => set_once(&mut current.symbol, unquote(value).to_string(), duplicate)?,
+            "aliases" => set_once(&mut current.aliases, parse_list(value), duplicate)?,
+            "dimension" => {
+                let dimension = parse_dimension(value).ok_or_else(|| {
+                    error(DefinitionErrorKind::UnknownDimension(value.to_string()))
+                })?;
+                set_once(&mut current.dimension, dimension, duplicate)?
+            }
+            "factor" | "offset" => {
+                let number = evaluate(value)
+                    .ok_or_else(|| error(DefinitionErrorKind::InvalidNumber(value.to_string())))?;
+                let slot = if key == "factor" {
+                    &mut current.factor
+                } else {
+                    &mut current.offset
+                };
+                set_once(slot, number, duplicate)?
+            }
+            _ => return Err(error(DefinitionErrorKind::UnknownKey(key.to_string()))),
+        }
+    }
+    if let Some(finished) = section {
+        definitions.push(finished.into_definition()?);
+    }
+    Ok(definitions)
+}
+
+/// Register the definitions, stopping at the first one the registry rejects
+///
+/// # Arguments
+///
+/// * `registry` - Where the units are added, usually `Registry::builtin()`
+/// * `definitions` - The units read by `parse`
+///
+/// # Returns
+///
+/// * The rejection with the line of the definition, e.g. a zero factor or a taken symbol
+pub fn register(
+    registry: &mut Registry,
+    definitions: &[Definition],
+) -> Result<(), DefinitionError> {
+    for definition in definitions {
+        registry
+            .register(definition.unit.clone())
+            .map_err(|error| DefinitionError {
+                line: definition.line,
+                kind: DefinitionErrorKind::Registry(error),
+            })?;
+    }
+    Ok(())
+}
+
+/// Read a definitions file and add its units to the registry
+///
+/// # Arguments
+///
+/// * `registry` - Where the units are added
+/// * `path` - The definitions file
+/// * `required` - If false, a missing file adds nothing instead of failing
+pub fn load(registry: &mut Registry, path: &Path, required: bool) -> Result<(), LoadError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) if !required && error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(LoadError::Io(error)),
+    };
+    let definitions = parse(&text).map_err(LoadError::Definition)?;
+    register(registry, &definitions).map_err(LoadError::Definition)
+}
+
+/// Returns the definitions file loaded when `--definitions` is not given
+///
+/// `$XDG_CONFIG_HOME` wins everywhere; otherwise `%APPDATA%` on Windows,
+/// `~/Library/Application Support` on macOS and `~/.config` elsewhere.
+pub fn default_path() -> Option<PathBuf> {
+    let config_dir = if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        PathBuf::from(dir)
+    } else if cfg!(windows) {
+        PathBuf::from(env::var_os("APPDATA")?)
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from(env::var_os("HOME")?).join("Library/Application Support")
+    } else {
+        PathBuf::from(env::var_os("HOME")?).join(".config")
+    };
+    Some(config_dir.join("temperature-converter").join("units.ini"))
+}
+
+/// Store a value unless the key was already given
+fn set_once<T>(
+    slot: &mut Option<T>,
+    value: T,
+    duplicate: impl FnOnce() -> DefinitionError,
+) -> Result<(), DefinitionError> {
+    if slot.is_some() {
+        return Err(duplicate());
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Remove a `#` or `;` comment, unless it is inside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' | ';' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Remove the double quotes around a TOML string
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Parse `a, b` or the TOML array `["a", "b"]`
+fn parse_list(value: &str) -> Vec<String> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(value);
+    inner
+        .split(',')
+        .map(|item| unquote(item.trim()).to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Parse a dimension name, ignoring case and quotes
+fn parse_dimension(value: &str) -> Option<Dimension> {
+    let value = unquote(value);
+    Dimension::ALL
+        .into_iter()
+        .find(|dimension| dimension.name().eq_ignore_ascii_case(value))
+}
+
+/// Evaluate a number written with `+`, `-`, `*`, `/` and the names in `CONSTANTS`
+///
+/// `*` and `/` bind tighter than `+` and `-`; there are no parentheses.
+fn evaluate(value: &str) -> Option<f64> {
+    let value = unquote(value).trim();
+    let mut terms: Vec<(f64, &str)> = Vec::new();
+    let mut start = 0;
+    let mut sign = 1.0;
+    for (index, c) in value.char_indices() {
+        let before = value[..index].trim_end();
+        // A sign right after another operator, or the `-` of `1e-5`, is part of the number
+        let in_exponent = before.ends_with(['e', 'E'])
+            && before[..before.len() - 1].ends_with(|c: char| c.is_ascii_digit());
+        let is_operator = matches!(c, '+' | '-')
+            && !before.is_empty()
+            && !before.ends_with(['+', '-', '*', '/'])
+            && !in_exponent;
+        if is_operator {
+            terms.push((sign, &value[start..index]));
+            sign = if c == '-' { -1.0 } else { 1.0 };
+            start = index + 1;
+        }
+    }
+    terms.push((sign, &value[start..]));
+    terms
+        .into_iter()
+        .map(|(sign, term)| Some(sign * evaluate_product(term)?))
+        .sum()
+}
+
+/// Evaluate a product such as `25 / SCALE_FACTOR`
+fn evaluate_product(term: &str) -> Option<f64> {
+    let mut result = 1.0;
+    let mut operator = '*';
+    let mut rest = term;
+    loop {
+        let end = rest.find(['*', '/']).unwrap_or(rest.len());
+        let factor = evaluate_atom(rest[..end].trim())?;
+        result = if operator == '*' {
+            result * factor
+        } else {
+            result / factor
+        };
+        let Some(next) = rest[end..].chars().next() else {
+            return result.is_finite().then_some(result);
+        };
+        operator = next;
+        rest = &rest[end + 1..];
+    }
+}
+
+/// Evaluate a single number or constant name
+fn evaluate_atom(atom: &str) -> Option<f64> {
+    let (negative, atom) = match atom.strip_prefix('-') {
+        Some(rest) => (true, rest.trim()),
+        None => (false, atom),
+    };
+    let value = match CONSTANTS.iter().find(|(name, _)| *name == atom) {
+        Some((_, value)) => *value,
+        None => atom.parse().ok()?,
+    };
+    Some(if negative { -value } else { value })
+}
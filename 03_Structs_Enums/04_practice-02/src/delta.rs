@@ -0,0 +1,145 @@
+//! Module with the `TemperatureDelta` type.
+//! A delta is a difference between two temperatures, so converting it only changes the
+//! size of the degree: a rise of 10 °C is a rise of 18 °F, not 50 °F.
+//!
+//! ```
+//! use temperature::{ConversionUnit, TemperatureDelta, TemperatureUnit};
+//!
+//! let rise = TemperatureUnit::Celsius(30.0) - TemperatureUnit::Celsius(20.0);
+//! let in_fahrenheit = rise.convert_to(ConversionUnit::ToFahrenheit);
+//! assert!((in_fahrenheit.value() - 18.0).abs() < 1e-9);
+//!
+//! let warmer = TemperatureUnit::Fahrenheit(50.0) + TemperatureDelta::new(ConversionUnit::ToCelsius, 10.0);
+//! assert!((warmer.value() - 68.0).abs() < 1e-9);
+//! ```
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::units::{ConversionUnit, TemperatureUnit};
+
+/// Struct to represent a difference between two temperatures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureDelta {
+    value: f64,
+    unit: ConversionUnit,
+}
+
+impl TemperatureDelta {
+    /// Constructor for TemperatureDelta
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The scale whose degrees measure the difference
+    /// * `value` - The size of the difference, negative for a drop
+    pub fn new(unit: ConversionUnit, value: f64) -> Self {
+        TemperatureDelta { value, unit }
+    }
+
+    /// Returns the numeric value without its unit
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns the scale whose degrees measure this difference
+    pub fn unit(&self) -> ConversionUnit {
+        self.unit
+    }
+
+    /// Returns this difference measured in kelvins
+    pub fn to_kelvin(self) -> f64 {
+        self.value / self.unit.degrees_per_kelvin()
+    }
+
+    /// Converts this difference into another scale
+    ///
+    /// Only the size of the degree changes; offsets such as 32 °F never apply.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The target scale
+    ///
+    /// # Returns
+    ///
+    /// * A new TemperatureDelta measured in the `to` scale
+    pub fn convert_to(&self, to: ConversionUnit) -> TemperatureDelta {
+        // Same scale on both sides, skip the round trip so the value stays exact
+        if self.unit == to {
+            return *self;
+        }
+        TemperatureDelta::new(to, self.to_kelvin() * to.degrees_per_kelvin())
+    }
+}
+
+/// `absolute - absolute = delta`, measured in the scale of the left-hand side
+impl Sub for TemperatureUnit {
+    type Output = TemperatureDelta;
+
+    fn sub(self, rhs: TemperatureUnit) -> TemperatureDelta {
+        let unit = self.unit();
+        TemperatureDelta::new(unit, self.value() - rhs.convert_to(unit).value())
+    }
+}
+
+/// `absolute + delta = absolute`, in the scale of the absolute temperature
+///
+/// The result is not validated, so call `validate` if it may fall below absolute zero.
+impl Add<TemperatureDelta> for TemperatureUnit {
+    type Output = TemperatureUnit;
+
+    fn add(self, rhs: TemperatureDelta) -> TemperatureUnit {
+        let unit = self.unit();
+        TemperatureUnit::new(unit, self.value() + rhs.convert_to(unit).value())
+    }
+}
+
+/// `absolute - delta = absolute`, in the scale of the absolute temperature
+impl Sub<TemperatureDelta> for TemperatureUnit {
+    type Output = TemperatureUnit;
+
+    fn sub(self, rhs: TemperatureDelta) -> TemperatureUnit {
+        self + -rhs
+    }
+}
+
+/// `delta + delta = delta`, measured in the scale of the left-hand side
+impl Add for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    fn add(self, rhs: TemperatureDelta) -> TemperatureDelta {
+        TemperatureDelta::new(self.unit, self.value + rhs.convert_to(self.unit).value)
+    }
+}
+
+/// `delta - delta = delta`, measured in the scale of the left-hand side
+impl Sub for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    fn sub(self, rhs: TemperatureDelta) -> TemperatureDelta {
+        self + -rhs
+    }
+}
+
+impl Neg for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    fn neg(self) -> TemperatureDelta {
+        TemperatureDelta::new(self.unit, -self.value)
+    }
+}
+
+/// Scaling a difference, e.g. a rise per hour times a number of hours
+impl Mul<f64> for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    fn mul(self, rhs: f64) -> TemperatureDelta {
+        TemperatureDelta::new(self.unit, self.value * rhs)
+    }
+}
+
+impl Div<f64> for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    fn div(self, rhs: f64) -> TemperatureDelta {
+        TemperatureDelta::new(self.unit, self.value / rhs)
+    }
+}
@@ -0,0 +1,414 @@
+//! Module with the exact conversion mode, built on rational numbers.
+//! `SCALE_FACTOR = 1.8` and `0.1` have no exact `f64` value, so the plain formulas drift a
+//! little on every conversion. Here every constant is a fraction (`1.8` is `9/5`, `273.15`
+//! is `5463/20`), so conversions between any two scales are exact and round-trip to the
+//! very same value. Results that are not finite decimals print as fractions, e.g. `340/9`.
+//!
+//! ```
+//! use temperature::ConversionUnit;
+//! use temperature::exact::{ExactTemperature, Rational};
+//!
+//! let celsius = ExactTemperature::try_new(ConversionUnit::ToCelsius, "0.1".parse().unwrap())?;
+//! let fahrenheit = celsius.convert_to(ConversionUnit::ToFahrenheit)?;
+//! assert_eq!(fahrenheit.value().to_string(), "32.18");
+//! assert_eq!(fahrenheit.convert_to(ConversionUnit::ToCelsius)?, celsius);
+//!
+//! let body = ExactTemperature::try_new(ConversionUnit::ToFahrenheit, Rational::integer(100))?;
+//! assert_eq!(body.convert_to(ConversionUnit::ToCelsius)?.value().to_string(), "340/9");
+//! # Ok::<(), temperature::exact::ExactError>(())
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::units::{ConversionUnit, TemperatureUnit};
+
+/// Exact value of `formulas::SCALE_FACTOR`
+pub const SCALE_FACTOR: Rational = Rational::ratio(9, 5);
+/// Exact value of `formulas::FAHRENHEIT_OFFSET`
+pub const FAHRENHEIT_OFFSET: Rational = Rational::integer(32);
+/// Exact value of `formulas::KELVIN_OFFSET`
+pub const KELVIN_OFFSET: Rational = Rational::ratio(5463, 20);
+/// Exact value of `formulas::KELVIN_BOILING_POINT`
+pub const KELVIN_BOILING_POINT: Rational = Rational::ratio(7463, 20);
+/// Exact value of `formulas::REAUMUR_FACTOR`
+pub const REAUMUR_FACTOR: Rational = Rational::ratio(4, 5);
+/// Exact value of `formulas::DELISLE_FACTOR`
+pub const DELISLE_FACTOR: Rational = Rational::ratio(3, 2);
+/// Exact value of `formulas::NEWTON_FACTOR`
+pub const NEWTON_FACTOR: Rational = Rational::ratio(33, 100);
+/// Exact value of `formulas::ROMER_FACTOR`
+pub const ROMER_FACTOR: Rational = Rational::ratio(21, 40);
+/// Exact value of `formulas::ROMER_OFFSET`
+pub const ROMER_OFFSET: Rational = Rational::ratio(15, 2);
+
+/// Struct to represent a fraction in lowest terms with a positive denominator
+///
+/// Arithmetic is checked: a result that does not fit in `i128` is `None`, never a wrong value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    /// Zero, `0/1`
+    pub const ZERO: Rational = Rational::integer(0);
+
+    /// Constructor for a fraction, reduced to lowest terms
+    ///
+    /// # Returns
+    ///
+    /// * The fraction, or `None` for a zero denominator or parts equal to `i128::MIN`
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 || numerator == i128::MIN || denominator == i128::MIN {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        let sign = denominator.signum();
+        Some(Rational {
+            numerator: sign * numerator / divisor,
+            denominator: sign * denominator / divisor,
+        })
+    }
+
+    /// Constructor for a whole number
+    pub const fn integer(value: i128) -> Self {
+        Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    /// Constructor for the constants, which are written in lowest terms
+    const fn ratio(numerator: i128, denominator: i128) -> Self {
+        Rational {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Returns the numerator, which carries the sign
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    /// Returns the denominator, always positive
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    /// Returns true below zero
+    pub fn is_negative(&self) -> bool {
+        self.numerator < 0
+    }
+
+    /// Returns true if the value is a finite decimal such as `32.18`, not `1/3`
+    pub fn is_decimal(&self) -> bool {
+        let mut denominator = self.denominator;
+        for prime in [2, 5] {
+            while denominator % prime == 0 {
+                denominator /= prime;
+            }
+        }
+        denominator == 1
+    }
+
+    /// Returns the nearest `f64`
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Adds two fractions, or returns `None` on overflow
+    pub fn checked_add(self, other: Rational) -> Option<Rational> {
+        // Going through the least common multiple keeps the numbers small
+        let divisor = gcd(self.denominator, other.denominator);
+        let numerator = self
+            .numerator
+            .checked_mul(other.denominator / divisor)?
+            .checked_add(other.numerator.checked_mul(self.denominator / divisor)?)?;
+        Rational::new(
+            numerator,
+            self.denominator.checked_mul(other.denominator / divisor)?,
+        )
+    }
+
+    /// Subtracts two fractions, or returns `None` on overflow
+    pub fn checked_sub(self, other: Rational) -> Option<Rational> {
+        self.checked_add(Rational::new(-other.numerator, other.denominator)?)
+    }
+
+    /// Multiplies two fractions, or returns `None` on overflow
+    pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+        // Cancelling across first keeps the products small
+        let left = gcd(self.numerator, other.denominator);
+        let right = gcd(other.numerator, self.denominator);
+        Rational::new(
+            (self.numerator / left).checked_mul(other.numerator / right)?,
+            (self.denominator / right).checked_mul(other.denominator / left)?,
+        )
+    }
+
+    /// Divides two fractions, or returns `None` on overflow or division by zero
+    pub fn checked_div(self, other: Rational) -> Option<Rational> {
+        self.checked_mul(Rational::new(other.denominator, other.numerator)?)
+    }
+}
+
+/// Returns the greatest common divisor, `1` when both numbers are zero
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // Callers never pass `i128::MIN`, so the divisor fits back into `i128`
+    a.max(1) as i128
+}
+
+/// Print a finite decimal in full, e.g. `-0.125`, and any other value as `numerator/denominator`
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            return write!(f, "{}", self.numerator);
+        }
+        if self.is_decimal() {
+            // Scale the fraction to a power of ten with as few digits as possible
+            let mut places = 0;
+            let mut power: i128 = 1;
+            while power % self.denominator != 0 {
+                places += 1;
+                power = match power.checked_mul(10) {
+                    Some(power) => power,
+                    None => break,
+                };
+            }
+            if let Some(scaled) = self.numerator.checked_mul(power / self.denominator)
+                && power % self.denominator == 0
+            {
+                let sign = if scaled < 0 { "-" } else { "" };
+                let digits = scaled.unsigned_abs();
+                let power = power as u128;
+                return write!(f, "{sign}{}.{:0places$}", digits / power, digits % power);
+            }
+        }
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+/// Parse a decimal such as `-12.5`, `36.6` or `1e-3`, or a fraction such as `1/3`
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRationalError::Invalid {
+            input: input.to_string(),
+        };
+        let out_of_range = || ParseRationalError::OutOfRange {
+            input: input.to_string(),
+        };
+        // Report the whole input, whichever side of a fraction was wrong
+        let whole_input = |part_error: ParseRationalError| match part_error {
+            ParseRationalError::Invalid { .. } => error(),
+            ParseRationalError::OutOfRange { .. } => out_of_range(),
+        };
+        let text = input.trim();
+        if let Some((numerator, denominator)) = text.split_once('/') {
+            let numerator: Rational = numerator.parse().map_err(whole_input)?;
+            let denominator: Rational = denominator.parse().map_err(whole_input)?;
+            return numerator.checked_div(denominator).ok_or_else(error);
+        }
+        let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => {
+                let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(error());
+                }
+                (
+                    mantissa,
+                    exponent.parse::<i32>().map_err(|_| out_of_range())?,
+                )
+            }
+            None => (text, 0),
+        };
+        let (negative, digits) = match mantissa.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = whole.chars().chain(fraction.chars());
+        if whole.len() + fraction.len() == 0 || !all_digits.clone().all(|c| c.is_ascii_digit()) {
+            return Err(error());
+        }
+        let mut numerator: i128 = 0;
+        for digit in all_digits {
+            numerator = numerator
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(i128::from(digit as u8 - b'0')))
+                .ok_or_else(out_of_range)?;
+        }
+        if negative {
+            numerator = -numerator;
+        }
+        // `12.5e1` is 125 / 10^(1 - 1)
+        let shift = i64::from(exponent) - fraction.len() as i64;
+        let power = 10_i128
+            .checked_pow(u32::try_from(shift.unsigned_abs()).map_err(|_| out_of_range())?)
+            .ok_or_else(out_of_range)?;
+        let value = if shift >= 0 {
+            Rational::new(numerator.checked_mul(power).ok_or_else(out_of_range)?, 1)
+        } else {
+            Rational::new(numerator, power)
+        };
+        value.ok_or_else(error)
+    }
+}
+
+/// Enum to represent text that cannot be read as an exact number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRationalError {
+    /// Not a decimal or a fraction, e.g. `nan`
+    Invalid { input: String },
+    /// A decimal or fraction whose digits or exponent do not fit in 128 bits, e.g. `1e-400`
+    OutOfRange { input: String },
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRationalError::Invalid { input } => write!(
+                f,
+                "`{input}` is not an exact number, use a decimal such as `36.6` or a fraction such as `1/3`"
+            ),
+            ParseRationalError::OutOfRange { input } => write!(
+                f,
+                "`{input}` is out of range, exact numbers fit in 128 bits: up to 38 digits \
+                 and powers of ten from 1e-38 to 1e38"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+/// Enum to represent why an exact conversion has no result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExactError {
+    /// A numerator or a denominator no longer fits in 128 bits
+    Overflow,
+    /// The value is colder than 0 K
+    BelowAbsoluteZero {
+        value: Rational,
+        unit: ConversionUnit,
+    },
+}
+
+impl fmt::Display for ExactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExactError::Overflow => write!(f, "the exact result needs more than 128 bits"),
+            ExactError::BelowAbsoluteZero { value, unit } => write!(
+                f,
+                "{value} {} is below absolute zero ({:.2} {})",
+                unit.symbol(),
+                unit.absolute_zero(),
+                unit.symbol()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExactError {}
+
+/// Struct to represent a temperature whose value is an exact fraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExactTemperature {
+    value: Rational,
+    unit: ConversionUnit,
+}
+
+impl ExactTemperature {
+    /// Constructor for a temperature that is not checked against absolute zero
+    pub fn new(unit: ConversionUnit, value: Rational) -> Self {
+        ExactTemperature { value, unit }
+    }
+
+    /// Validated constructor for a temperature
+    ///
+    /// # Returns
+    ///
+    /// * The temperature, or an error if it is below absolute zero
+    pub fn try_new(unit: ConversionUnit, value: Rational) -> Result<Self, ExactError> {
+        let temperature = ExactTemperature::new(unit, value);
+        if temperature.to_kelvin()?.is_negative() {
+            return Err(ExactError::BelowAbsoluteZero { value, unit });
+        }
+        Ok(temperature)
+    }
+
+    /// Returns the exact value
+    pub fn value(&self) -> Rational {
+        self.value
+    }
+
+    /// Returns the scale of the value
+    pub fn unit(&self) -> ConversionUnit {
+        self.unit
+    }
+
+    /// Returns the exact value in Kelvin
+    pub fn to_kelvin(self) -> Result<Rational, ExactError> {
+        let (base, factor, offset) = scale(self.unit);
+        self.value
+            .checked_sub(offset)
+            .and_then(|value| value.checked_div(factor))
+            .and_then(|value| value.checked_add(base))
+            .ok_or(ExactError::Overflow)
+    }
+
+    /// Converts the temperature into another scale without rounding
+    pub fn convert_to(&self, to: ConversionUnit) -> Result<ExactTemperature, ExactError> {
+        let (base, factor, offset) = scale(to);
+        let value = self
+            .to_kelvin()?
+            .checked_sub(base)
+            .and_then(|value| value.checked_mul(factor))
+            .and_then(|value| value.checked_add(offset))
+            .ok_or(ExactError::Overflow)?;
+        Ok(ExactTemperature::new(to, value))
+    }
+
+    /// Returns the nearest `f64` temperature, for printing or the other modules
+    pub fn to_temperature(self) -> TemperatureUnit {
+        TemperatureUnit::new(self.unit, self.value.to_f64())
+    }
+}
+
+/// Print the exact value with its symbol, e.g. `340/9 °C`
+impl fmt::Display for ExactTemperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit.symbol())
+    }
+}
+
+/// Returns the line of a scale: `value = (kelvin - base) * factor + offset`
+///
+/// The lines follow the formulas of `formulas`, with their constants as fractions.
+fn scale(unit: ConversionUnit) -> (Rational, Rational, Rational) {
+    let one = Rational::integer(1);
+    let zero = Rational::ZERO;
+    match unit {
+        ConversionUnit::ToCelsius => (KELVIN_OFFSET, one, zero),
+        ConversionUnit::ToFahrenheit => (KELVIN_OFFSET, SCALE_FACTOR, FAHRENHEIT_OFFSET),
+        ConversionUnit::ToKelvin => (zero, one, zero),
+        ConversionUnit::ToRankine => (zero, SCALE_FACTOR, zero),
+        ConversionUnit::ToReaumur => (KELVIN_OFFSET, REAUMUR_FACTOR, zero),
+        // Delisle counts down from the boiling point
+        ConversionUnit::ToDelisle => (
+            KELVIN_BOILING_POINT,
+            Rational::ratio(-DELISLE_FACTOR.numerator, DELISLE_FACTOR.denominator),
+            zero,
+        ),
+        ConversionUnit::ToNewton => (KELVIN_OFFSET, NEWTON_FACTOR, zero),
+        ConversionUnit::ToRomer => (KELVIN_OFFSET, ROMER_FACTOR, ROMER_OFFSET),
+    }
+}
@@ -0,0 +1,251 @@
+//! Module with the parser for conversion expressions such as `98.6 °F to C`.
+//! Units can be written as symbols or names, and the target is introduced by
+//! `to`, `in`, `as`, `into`, `->` or `=>`.
+//!
+//! ```
+//! use temperature::expression::parse_expression;
+//! use temperature::{ConversionUnit, TemperatureUnit};
+//!
+//! let request = parse_expression("-40F->C").unwrap();
+//! assert_eq!(request.from(), TemperatureUnit::Fahrenheit(-40.0));
+//! assert_eq!(request.to(), ConversionUnit::ToCelsius);
+//! ```
+
+use std::fmt;
+
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Words and arrows that separate the temperature from the target unit
+const CONNECTORS: [&str; 6] = ["to", "in", "as", "into", "->", "=>"];
+
+/// Enum to represent what went wrong while parsing an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionErrorKind {
+    /// The expression does not start with a number
+    MissingValue,
+    /// The number could not be read, e.g. `1.2.3`
+    InvalidNumber(String),
+    /// The value has no unit, e.g. `98.6 to C`
+    MissingUnit,
+    /// A word that does not name any scale
+    UnknownUnit(String),
+    /// There is no `to`/`in`/`->` part
+    MissingTarget,
+    /// Something was left after a complete expression
+    UnexpectedInput(String),
+    /// The value is not a possible temperature, e.g. `-500 C`
+    InvalidTemperature(TemperatureError),
+}
+
+/// Struct to represent a parse error and the column where it happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionError {
+    /// Position of the offending text, counted in characters from 1
+    pub column: usize,
+    pub kind: ExpressionErrorKind,
+}
+
+impl ExpressionError {
+    /// Render the input with a caret under the offending column
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The expression that failed to parse
+    ///
+    /// # Returns
+    ///
+    /// * Two lines: the input and a `^` marker, followed by the message
+    pub fn highlight(&self, input: &str) -> String {
+        format!(
+            "{input}\n{}^ {}",
+            " ".repeat(self.column.saturating_sub(1)),
+            self
+        )
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ExpressionErrorKind::MissingValue => write!(f, "expected a number"),
+            ExpressionErrorKind::InvalidNumber(text) => write!(f, "`{text}` is not a number"),
+            ExpressionErrorKind::MissingUnit => write!(f, "expected a unit after the number"),
+            ExpressionErrorKind::UnknownUnit(text) => write!(f, "unknown unit `{text}`"),
+            ExpressionErrorKind::MissingTarget => {
+                write!(f, "expected `to`, `in` or `->` followed by a unit")
+            }
+            ExpressionErrorKind::UnexpectedInput(text) => write!(f, "unexpected `{text}`"),
+            ExpressionErrorKind::InvalidTemperature(error) => write!(f, "{error}"),
+        }?;
+        write!(f, " (column {})", self.column)
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+/// Parse a full conversion expression, e.g. `300 kelvin in fahrenheit`
+///
+/// # Arguments
+///
+/// * `input` - The expression typed by the user
+///
+/// # Returns
+///
+/// * The conversion request, or the reason and column where parsing failed
+pub fn parse_expression(input: &str) -> Result<ConversionRequest, ExpressionError> {
+    let mut parser = Parser::new(input);
+    let temperature = parser.temperature()?;
+    let target = parser.target()?.ok_or(ExpressionError {
+        column: parser.column(),
+        kind: ExpressionErrorKind::MissingTarget,
+    })?;
+    parser.end()?;
+    Ok(ConversionRequest::new(temperature, target))
+}
+
+/// Parse a temperature with its unit, e.g. `25c` or `98.6 °F`
+///
+/// # Arguments
+///
+/// * `input` - The temperature typed by the user
+///
+/// # Returns
+///
+/// * The temperature, or the reason and column where parsing failed
+pub fn parse_temperature(input: &str) -> Result<TemperatureUnit, ExpressionError> {
+    let mut parser = Parser::new(input);
+    let temperature = parser.temperature()?;
+    parser.end()?;
+    Ok(temperature)
+}
+
+/// Returns true if the input contains a connector such as `to` or `->`
+///
+/// Callers use it to decide whether an input was meant as an expression
+/// before showing an expression parse error.
+pub fn has_connector(input: &str) -> bool {
+    input.contains("->")
+        || input.contains("=>")
+        || input
+            .split_whitespace()
+            .any(|word| CONNECTORS.contains(&word.to_lowercase().as_str()))
+}
+
+/// Struct to represent the parser state: the input and the current position
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    /// Constructor for Parser
+    fn new(input: &str) -> Self {
+        Parser {
+            chars: input.chars().collect(),
+            position: 0,
+        }
+    }
+
+    /// Returns the 1-based column of the current position
+    fn column(&self) -> usize {
+        self.position + 1
+    }
+
+    /// Build an error at a given 0-based position
+    fn error_at(&self, position: usize, kind: ExpressionErrorKind) -> ExpressionError {
+        ExpressionError {
+            column: position + 1,
+            kind,
+        }
+    }
+
+    /// Move past any whitespace
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .get(self.position)
+            .is_some_and(|c| c.is_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    /// Take characters while `accept` holds and return them with their start position
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> (usize, String) {
+        let start = self.position;
+        while self.chars.get(self.position).is_some_and(|c| accept(*c)) {
+            self.position += 1;
+        }
+        (start, self.chars[start..self.position].iter().collect())
+    }
+
+    /// Parse the value and its unit
+    fn temperature(&mut self) -> Result<TemperatureUnit, ExpressionError> {
+        self.skip_whitespace();
+        let (start, number) = self.take_while(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+        if number.is_empty() {
+            return Err(self.error_at(start, ExpressionErrorKind::MissingValue));
+        }
+        // A trailing `e` belongs to the unit in `25e`, not to an exponent, so trim it off
+        let trimmed = number.trim_end_matches(['e', 'E']);
+        self.position = start + trimmed.chars().count();
+        let value = trimmed
+            .parse::<f64>()
+            .map_err(|_| self.error_at(start, ExpressionErrorKind::InvalidNumber(number)))?;
+
+        self.skip_whitespace();
+        let (unit_start, unit) = self.unit_token();
+        if unit.is_empty() || CONNECTORS.contains(&unit.to_lowercase().as_str()) {
+            return Err(self.error_at(unit_start, ExpressionErrorKind::MissingUnit));
+        }
+        let unit = self.parse_unit(unit_start, &unit)?;
+        TemperatureUnit::try_new(unit, value)
+            .map_err(|error| self.error_at(start, ExpressionErrorKind::InvalidTemperature(error)))
+    }
+
+    /// Parse the optional `to UNIT` part
+    fn target(&mut self) -> Result<Option<ConversionUnit>, ExpressionError> {
+        self.skip_whitespace();
+        if self.position >= self.chars.len() {
+            return Ok(None);
+        }
+        let (start, connector) = if self.chars[self.position].is_alphabetic() {
+            self.take_while(char::is_alphabetic)
+        } else {
+            self.take_while(|c| "-=>".contains(c))
+        };
+        if !CONNECTORS.contains(&connector.to_lowercase().as_str()) {
+            // Not a connector: rewind so the error points at the unexpected text
+            self.position = start;
+            return Ok(None);
+        }
+        self.skip_whitespace();
+        let (unit_start, unit) = self.unit_token();
+        if unit.is_empty() {
+            return Err(self.error_at(unit_start, ExpressionErrorKind::MissingUnit));
+        }
+        self.parse_unit(unit_start, &unit).map(Some)
+    }
+
+    /// Fail if anything but whitespace is left
+    fn end(&mut self) -> Result<(), ExpressionError> {
+        self.skip_whitespace();
+        if self.position < self.chars.len() {
+            let rest: String = self.chars[self.position..].iter().collect();
+            return Err(self.error_at(self.position, ExpressionErrorKind::UnexpectedInput(rest)));
+        }
+        Ok(())
+    }
+
+    /// Read a unit word such as `°F`, `kelvin` or `℃`
+    fn unit_token(&mut self) -> (usize, String) {
+        self.take_while(|c| c.is_alphabetic() || "°º℃℉".contains(c))
+    }
+
+    /// Turn a unit word into a scale
+    fn parse_unit(&self, start: usize, unit: &str) -> Result<ConversionUnit, ExpressionError> {
+        unit.parse()
+            .map_err(|_| self.error_at(start, ExpressionErrorKind::UnknownUnit(unit.to_string())))
+    }
+}
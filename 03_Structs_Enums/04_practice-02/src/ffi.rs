@@ -0,0 +1,257 @@
+//! Module with the C ABI of the converter, for tools written in C.
+//! The library is also built as `libtemperature.so`/`libtemperature.a`; the declarations
+//! live in `include/temperature.h`, which is generated by `header` and checked by the tests.
+//!
+//! Scales are passed as integers, in the order of `ConversionUnit::ALL` (`0` is Celsius,
+//! `1` Fahrenheit, `2` Kelvin, ...), and every function returns a `Status` instead of
+//! panicking, so invalid input never unwinds into C.
+//!
+//! ```c
+//! double fahrenheit;
+//! int status = temperature_convert(100.0, TEMPERATURE_SCALE_CELSIUS,
+//!                                  TEMPERATURE_SCALE_FAHRENHEIT, &fahrenheit);
+//! if (status != TEMPERATURE_OK) {
+//!     puts(temperature_status_message(status));
+//! }
+//! ```
+
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::ptr;
+use std::sync::OnceLock;
+
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Enum to represent the status codes returned to C
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok = 0,
+    /// The scale number is not in `ConversionUnit::ALL`
+    UnknownScale = 1,
+    /// The value is NaN or infinite, or its conversion is too large for a `double`
+    NotFinite = 2,
+    /// The value is colder than 0 K
+    BelowAbsoluteZero = 3,
+    /// The result pointer is `NULL`
+    NullPointer = 4,
+}
+
+impl Status {
+    /// Every status, in the order of their codes
+    pub const ALL: [Status; 5] = [
+        Status::Ok,
+        Status::UnknownScale,
+        Status::NotFinite,
+        Status::BelowAbsoluteZero,
+        Status::NullPointer,
+    ];
+
+    /// Returns the name of the C constant, e.g. `TEMPERATURE_NOT_FINITE`
+    pub fn constant(&self) -> &'static str {
+        match self {
+            Status::Ok => "TEMPERATURE_OK",
+            Status::UnknownScale => "TEMPERATURE_UNKNOWN_SCALE",
+            Status::NotFinite => "TEMPERATURE_NOT_FINITE",
+            Status::BelowAbsoluteZero => "TEMPERATURE_BELOW_ABSOLUTE_ZERO",
+            Status::NullPointer => "TEMPERATURE_NULL_POINTER",
+        }
+    }
+
+    /// Returns a short English description of the status
+    pub fn message(&self) -> &'static CStr {
+        match self {
+            Status::Ok => c"success",
+            Status::UnknownScale => c"unknown temperature scale",
+            Status::NotFinite => c"the value or its result is not a finite number",
+            Status::BelowAbsoluteZero => c"the value is below absolute zero",
+            Status::NullPointer => c"the result pointer is NULL",
+        }
+    }
+}
+
+impl From<TemperatureError> for Status {
+    fn from(error: TemperatureError) -> Self {
+        match error {
+            TemperatureError::BelowAbsoluteZero { .. } => Status::BelowAbsoluteZero,
+            TemperatureError::NotFinite { .. } | TemperatureError::TooLarge { .. } => {
+                Status::NotFinite
+            }
+        }
+    }
+}
+
+/// Returns the scale with the given number, if there is one
+fn scale(number: c_int) -> Option<ConversionUnit> {
+    usize::try_from(number)
+        .ok()
+        .and_then(|index| ConversionUnit::ALL.get(index).copied())
+}
+
+/// Returns the NUL-terminated codes of `ConversionUnit::ALL`, in the same order
+///
+/// Built once from `ConversionUnit::code`, so C sees the same codes as the rest of the library.
+fn scale_codes() -> &'static [CString] {
+    static CODES: OnceLock<Vec<CString>> = OnceLock::new();
+    CODES.get_or_init(|| {
+        ConversionUnit::ALL
+            .iter()
+            // Codes are plain ASCII letters; an empty string is still better than unwinding
+            .map(|unit| CString::new(unit.code()).unwrap_or_default())
+            .collect()
+    })
+}
+
+/// Returns the name of the C constant of a scale, e.g. `TEMPERATURE_SCALE_REAUMUR`
+fn scale_constant(unit: ConversionUnit) -> String {
+    let name = format!("{unit:?}");
+    format!(
+        "TEMPERATURE_SCALE_{}",
+        name.trim_start_matches("To").to_uppercase()
+    )
+}
+
+/// Convert a temperature between two scales
+///
+/// # Arguments
+///
+/// * `value` - The temperature to convert
+/// * `from` - Number of the scale of `value`
+/// * `to` - Number of the target scale
+/// * `result` - Where the converted value is written, only on success
+///
+/// # Returns
+///
+/// * `Status::Ok`, or why nothing was converted
+///
+/// # Safety
+///
+/// `result` must be `NULL` or point to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn temperature_convert(
+    value: f64,
+    from: c_int,
+    to: c_int,
+    result: *mut f64,
+) -> Status {
+    let (Some(from), Some(to)) = (scale(from), scale(to)) else {
+        return Status::UnknownScale;
+    };
+    if result.is_null() {
+        return Status::NullPointer;
+    }
+    let request = match TemperatureUnit::try_new(from, value)
+        .and_then(|temperature| ConversionRequest::try_new(temperature, to))
+    {
+        Ok(request) => request,
+        Err(error) => return error.into(),
+    };
+    // Nothing is written unless the whole conversion succeeded
+    let converted = match request.try_convert() {
+        Ok(converted) => converted,
+        Err(error) => return error.into(),
+    };
+    // SAFETY: the caller promises a non-NULL `result` is writable
+    unsafe { *result = converted };
+    Status::Ok
+}
+
+/// Write the absolute zero of a scale, e.g. `-273.15` for Celsius
+///
+/// # Safety
+///
+/// `result` must be `NULL` or point to a writable `double`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn temperature_absolute_zero(
+    scale_number: c_int,
+    result: *mut f64,
+) -> Status {
+    let Some(unit) = scale(scale_number) else {
+        return Status::UnknownScale;
+    };
+    if result.is_null() {
+        return Status::NullPointer;
+    }
+    // SAFETY: the caller promises a non-NULL `result` is writable
+    unsafe { *result = unit.absolute_zero() };
+    Status::Ok
+}
+
+/// Returns the ASCII code of a scale, e.g. `"F"`, or `NULL` for an unknown scale
+///
+/// The string is static and must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn temperature_scale_code(scale_number: c_int) -> *const c_char {
+    usize::try_from(scale_number)
+        .ok()
+        .and_then(|index| scale_codes().get(index))
+        .map_or(ptr::null(), |code| code.as_ptr())
+}
+
+/// Returns a description of a status code, or `NULL` for an unknown code
+///
+/// The string is static and must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn temperature_status_message(status: c_int) -> *const c_char {
+    Status::ALL
+        .iter()
+        .find(|known| **known as c_int == status)
+        .map_or(ptr::null(), |known| known.message().as_ptr())
+}
+
+/// Generate the C header with the scales, the status codes and the functions
+///
+/// # Returns
+///
+/// * The contents of `include/temperature.h`
+pub fn header() -> String {
+    let mut header = String::from(
+        "/* Generated by `temperature::ffi::header`; run the `ffi` tests with UPDATE_HEADER=1\n \
+         * to regenerate it. */\n\
+         #ifndef TEMPERATURE_H\n\
+         #define TEMPERATURE_H\n\n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {\n\
+         #endif\n\n\
+         /* Temperature scales, passed as `int`. */\n",
+    );
+    let scales = ConversionUnit::ALL.iter().zip(scale_codes());
+    for (number, (unit, code)) in scales.enumerate() {
+        header += &format!(
+            "#define {} {number} /* {} */\n",
+            scale_constant(*unit),
+            code.to_string_lossy()
+        );
+    }
+    header += "\n/* Status codes returned by the functions below. */\n";
+    header += "typedef enum temperature_status {\n";
+    for status in Status::ALL {
+        header += &format!(
+            "    {} = {}, /* {} */\n",
+            status.constant(),
+            status as c_int,
+            status.message().to_string_lossy()
+        );
+    }
+    header += "} temperature_status;\n\n";
+    header += "\
+/* Convert `value` from one scale to another; `*result` is only written on success. */
+temperature_status temperature_convert(double value, int from, int to, double *result);
+
+/* Write the absolute zero of a scale into `*result`. */
+temperature_status temperature_absolute_zero(int scale, double *result);
+
+/* ASCII code of a scale such as \"F\", or NULL; the string is static. */
+const char *temperature_scale_code(int scale);
+
+/* Description of a status code, or NULL; the string is static. */
+const char *temperature_status_message(int status);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TEMPERATURE_H */
+";
+    header
+}
@@ -0,0 +1,378 @@
+//! Module with the watch mode of the converter.
+//! Readings are read one line at a time from stdin or from a file that keeps growing, like
+//! `tail -f`, and every reading is converted and written as soon as its line is complete.
+//!
+//! A line holds a value with an optional unit, after an optional timestamp. Lines without a
+//! unit are in the `--from` scale:
+//!
+//! ```
+//! use std::io;
+//! use temperature::ConversionUnit;
+//! use temperature::follow::{FollowOptions, follow};
+//!
+//! let log = "2024-05-01T12:00:00Z 21.5C\n2024-05-01T12:01:00Z 295 K\n22\n";
+//! let options = FollowOptions::new(Some(ConversionUnit::ToCelsius), ConversionUnit::ToFahrenheit);
+//! let mut output = Vec::new();
+//! let report = follow(log.as_bytes(), &mut output, io::sink(), &options).unwrap();
+//! assert_eq!(report.converted, 3);
+//! assert_eq!(
+//!     String::from_utf8(output).unwrap(),
+//!     "2024-05-01T12:00:00Z 70.70 °F\n2024-05-01T12:01:00Z 71.33 °F\n71.60 °F\n"
+//! );
+//! ```
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use crate::alert::AlertMonitor;
+use crate::locale::Locale;
+use crate::output::{self, OutputFormat};
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, ParseUnitError, TemperatureError, TemperatureUnit};
+
+/// How long `Tail` waits before looking for new lines at the end of the file
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Enum to represent what happens with a line that holds no reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MalformedLines {
+    /// Leave the line out without a word
+    Skip,
+    /// Leave the line out and say why on the warnings output
+    #[default]
+    Warn,
+    /// Stop following at the first such line
+    Stop,
+}
+
+/// Parse `skip`, `warn` or `stop`, ignoring case
+impl FromStr for MalformedLines {
+    type Err = ParseMalformedLinesError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "skip" => Ok(MalformedLines::Skip),
+            "warn" => Ok(MalformedLines::Warn),
+            "stop" => Ok(MalformedLines::Stop),
+            _ => Err(ParseMalformedLinesError {
+                input: input.to_string(),
+            }),
+        }
+    }
+}
+
+/// Struct to represent a `--on-malformed` value that was not recognised
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMalformedLinesError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseMalformedLinesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown handling of malformed lines `{}`, use skip, warn or stop",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseMalformedLinesError {}
+
+/// Struct to represent the settings of the watch mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowOptions {
+    /// Scale of the readings written without a unit
+    pub from: Option<ConversionUnit>,
+    pub to: ConversionUnit,
+    pub precision: usize,
+    /// Text writes the timestamp and the converted temperature; both JSON formats write
+    /// one object per line, since the stream has no end to close an array at
+    pub output: OutputFormat,
+    pub locale: Locale,
+    pub on_malformed: MalformedLines,
+}
+
+impl FollowOptions {
+    /// Constructor with two decimals, text output and a warning for every malformed line
+    pub fn new(from: Option<ConversionUnit>, to: ConversionUnit) -> Self {
+        FollowOptions {
+            from,
+            to,
+            precision: 2,
+            output: OutputFormat::Text,
+            locale: Locale::En,
+            on_malformed: MalformedLines::Warn,
+        }
+    }
+}
+
+/// Struct to represent one reading of the stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    /// Everything before the value, kept as written, e.g. `2024-05-01 12:00:00`
+    pub timestamp: Option<String>,
+    pub request: ConversionRequest,
+}
+
+/// Enum to represent why a line holds no reading
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineErrorKind {
+    /// The value is not a number
+    InvalidValue(String),
+    /// The value has no unit and no `--from` scale was given
+    MissingUnit,
+    /// The text after the value does not name any scale
+    UnknownUnit(ParseUnitError),
+    /// The value is a number but not a possible temperature
+    InvalidTemperature(TemperatureError),
+}
+
+/// Struct to represent a malformed line, with its 1-based line number
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineError {
+    pub line: usize,
+    pub kind: LineErrorKind,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            LineErrorKind::InvalidValue(value) => {
+                write!(f, "line {}: `{value}` is not a temperature", self.line)
+            }
+            LineErrorKind::MissingUnit => {
+                write!(f, "line {}: the reading has no unit", self.line)
+            }
+            LineErrorKind::UnknownUnit(error) => write!(f, "line {}: {error}", self.line),
+            LineErrorKind::InvalidTemperature(error) => write!(f, "line {}: {error}", self.line),
+        }
+    }
+}
+
+/// Struct to represent how many lines were converted and left out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FollowReport {
+    pub converted: usize,
+    pub skipped: usize,
+}
+
+/// Enum to represent errors that stop the watch mode
+#[derive(Debug)]
+pub enum FollowError {
+    /// Reading the input or writing the output failed
+    Io(io::Error),
+    /// A malformed line was found with `MalformedLines::Stop`
+    Malformed(LineError),
+}
+
+impl fmt::Display for FollowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FollowError::Io(error) => write!(f, "{error}"),
+            FollowError::Malformed(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for FollowError {}
+
+impl From<io::Error> for FollowError {
+    fn from(error: io::Error) -> Self {
+        FollowError::Io(error)
+    }
+}
+
+/// Read the reading of one line
+///
+/// The unit is the text after the number, attached (`21.5C`) or not (`21.5 °C`); the value
+/// is the last word before it and anything earlier is the timestamp.
+///
+/// # Arguments
+///
+/// * `line` - One line of the stream, not blank
+/// * `options` - The scales and the language of the decimals
+///
+/// # Returns
+///
+/// * The reading with its conversion request, or why the line holds none
+pub fn parse_reading(line: &str, options: &FollowOptions) -> Result<Reading, LineErrorKind> {
+    let line = line.trim();
+    let rest = line.trim_end_matches(|c: char| c.is_alphabetic() || "°º℃℉".contains(c));
+    let unit = &line[rest.len()..];
+    let rest = rest.trim_end();
+    let (timestamp, value) = match rest.rfind(char::is_whitespace) {
+        Some(index) => (Some(rest[..index].trim_end()), rest[index..].trim_start()),
+        None => (None, rest),
+    };
+
+    let value = options
+        .locale
+        .parse_number(value)
+        .ok_or_else(|| LineErrorKind::InvalidValue(line.to_string()))?;
+    let unit = if unit.is_empty() {
+        options.from.ok_or(LineErrorKind::MissingUnit)?
+    } else {
+        unit.parse().map_err(LineErrorKind::UnknownUnit)?
+    };
+    let temperature =
+        TemperatureUnit::try_new(unit, value).map_err(LineErrorKind::InvalidTemperature)?;
+    Ok(Reading {
+        timestamp: timestamp.map(str::to_string),
+        request: ConversionRequest::new(temperature, options.to),
+    })
+}
+
+/// Format a converted reading as one line of the output, without the line break
+pub fn format_reading(reading: &Reading, options: &FollowOptions) -> String {
+    match options.output {
+        OutputFormat::Text => {
+            let converted = options.locale.format_temperature(
+                reading.request.from().convert_to(reading.request.to()),
+                options.precision,
+            );
+            match &reading.timestamp {
+                Some(timestamp) => format!("{timestamp} {converted}"),
+                None => converted,
+            }
+        }
+        OutputFormat::Json | OutputFormat::JsonLines => output::format_timestamped_json(
+            reading.timestamp.as_deref(),
+            &reading.request,
+            options.precision,
+        ),
+    }
+}
+
+/// Convert every line of a stream as soon as it arrives
+///
+/// Blank lines are ignored. Every converted line is flushed right away, so the results
+/// can be read while the stream goes on.
+///
+/// # Arguments
+///
+/// * `reader` - The stream, e.g. stdin or a `Tail` of a log file
+/// * `writer` - Where the converted readings are written
+/// * `warnings` - Where malformed lines are reported with `MalformedLines::Warn`
+/// * `options` - Scales, output format and what to do with malformed lines
+///
+/// # Returns
+///
+/// * How many lines were converted and left out once the stream ends
+pub fn follow<R: BufRead, W: Write, E: Write>(
+    reader: R,
+    writer: W,
+    warnings: E,
+    options: &FollowOptions,
+) -> Result<FollowReport, FollowError> {
+    follow_with_alerts(reader, writer, warnings, options, None)
+}
+
+/// Convert every line of a stream like `follow`, checking every reading against alert rules
+///
+/// # Arguments
+///
+/// * `reader` - The stream, e.g. stdin or a `Tail` of a log file
+/// * `writer` - Where the converted readings are written
+/// * `warnings` - Where malformed lines are reported with `MalformedLines::Warn`
+/// * `options` - Scales, output format and what to do with malformed lines
+/// * `alerts` - The rules and the log of their events, if any
+///
+/// # Returns
+///
+/// * How many lines were converted and left out once the stream ends
+pub fn follow_with_alerts<R: BufRead, W: Write, E: Write>(
+    reader: R,
+    mut writer: W,
+    mut warnings: E,
+    options: &FollowOptions,
+    mut alerts: Option<&mut AlertMonitor<'_>>,
+) -> Result<FollowReport, FollowError> {
+    let mut report = FollowReport::default();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_reading(&line, options) {
+            Ok(reading) => {
+                writeln!(writer, "{}", format_reading(&reading, options))?;
+                writer.flush()?;
+                report.converted += 1;
+                if let Some(alerts) = alerts.as_deref_mut() {
+                    let timestamp = reading.timestamp.as_deref();
+                    alerts.observe(number + 1, timestamp, reading.request.from())?;
+                }
+            }
+            Err(kind) => {
+                let error = LineError {
+                    line: number + 1,
+                    kind,
+                };
+                match options.on_malformed {
+                    MalformedLines::Skip => {}
+                    MalformedLines::Warn => writeln!(warnings, "warning: {error}")?,
+                    MalformedLines::Stop => return Err(FollowError::Malformed(error)),
+                }
+                report.skipped += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Struct to represent a file read like `tail -f`
+///
+/// At the end of the file a read waits for more data instead of returning 0 bytes, so the
+/// stream never ends. A file that shrinks, because it was truncated or rotated, is opened
+/// again and read from the start.
+#[derive(Debug)]
+pub struct Tail {
+    path: PathBuf,
+    file: File,
+    position: u64,
+    poll: Duration,
+}
+
+impl Tail {
+    /// Open a file to follow from its first line, checking for new data every `POLL_INTERVAL`
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(Tail {
+            path: path.to_path_buf(),
+            file: File::open(path)?,
+            position: 0,
+            poll: POLL_INTERVAL,
+        })
+    }
+
+    /// Builder method to check for new data more or less often
+    pub fn with_poll_interval(mut self, poll: Duration) -> Self {
+        self.poll = poll;
+        self
+    }
+}
+
+impl Read for Tail {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.file.read(buf)?;
+            if read > 0 {
+                self.position += read as u64;
+                return Ok(read);
+            }
+            // While a log is rotated the path may briefly not exist, so just wait
+            let shrunk = fs::metadata(&self.path).is_ok_and(|meta| meta.len() < self.position);
+            if shrunk && let Ok(file) = File::open(&self.path) {
+                self.file = file;
+                self.position = 0;
+                continue;
+            }
+            thread::sleep(self.poll);
+        }
+    }
+}
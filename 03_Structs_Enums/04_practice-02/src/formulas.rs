@@ -0,0 +1,5 @@
+//! Module with the temperature conversion formulas.
+//! They live in the `temperature_core` crate, shared with the Practice 1 converter,
+//! and are re-exported here so `temperature::formulas::celsius_to_fahrenheit` keeps working.
+
+pub use temperature_core::*;
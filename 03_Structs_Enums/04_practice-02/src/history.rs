@@ -0,0 +1,230 @@
+//! Module with the conversion history of an interactive session.
+//! Every result shown by the menu is kept, so it can be listed, repeated or inverted,
+//! and saved as CSV or JSON when the session ends.
+//!
+//! ```
+//! use temperature::history::History;
+//! use temperature::{ConversionRequest, ConversionUnit, TemperatureUnit};
+//!
+//! let mut history = History::default();
+//! history.push(ConversionRequest::new(TemperatureUnit::Celsius(100.0), ConversionUnit::ToFahrenheit));
+//! let swapped = history.get(1).unwrap().inverse();
+//! assert_eq!(swapped.from(), TemperatureUnit::Fahrenheit(212.0));
+//! assert_eq!(swapped.to(), ConversionUnit::ToCelsius);
+//! ```
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::batch::{join_record, split_record};
+use crate::output::json_temperature;
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureUnit};
+
+/// Header row of the CSV history files
+const CSV_HEADER: &str = "from_value,from_unit,to_value,to_unit";
+
+/// Struct to represent one conversion of the history with its result
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryEntry {
+    request: ConversionRequest,
+    result: TemperatureUnit,
+}
+
+impl HistoryEntry {
+    /// Constructor for HistoryEntry, performing the conversion once
+    pub fn new(request: ConversionRequest) -> Self {
+        HistoryEntry {
+            request,
+            result: request.from().convert_to(request.to()),
+        }
+    }
+
+    /// Returns the conversion that was asked for
+    pub fn request(&self) -> ConversionRequest {
+        self.request
+    }
+
+    /// Returns the converted temperature
+    pub fn result(&self) -> TemperatureUnit {
+        self.result
+    }
+
+    /// Returns the opposite conversion: the result back into the source unit
+    pub fn inverse(&self) -> ConversionRequest {
+        ConversionRequest::new(self.result, self.request.from().unit())
+    }
+}
+
+/// Struct to represent the conversions of a session, oldest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Add a conversion at the end of the history
+    pub fn push(&mut self, request: ConversionRequest) {
+        self.entries.push(HistoryEntry::new(request));
+    }
+
+    /// Returns every entry, oldest first
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Returns the entry with the given number, counting from 1 like the listing
+    pub fn get(&self, number: usize) -> Option<&HistoryEntry> {
+        self.entries.get(number.checked_sub(1)?)
+    }
+
+    /// Returns the most recent entry
+    pub fn last(&self) -> Option<&HistoryEntry> {
+        self.entries.last()
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nothing was converted yet
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the history as CSV with a header row
+    ///
+    /// Values keep their full precision, so the file can be read back with `read_csv`.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{CSV_HEADER}")?;
+        for entry in &self.entries {
+            let from = entry.request.from();
+            let fields = [
+                from.value().to_string(),
+                from.unit().code().to_string(),
+                entry.result.value().to_string(),
+                entry.result.unit().code().to_string(),
+            ];
+            writeln!(writer, "{}", join_record(&fields, ','))?;
+        }
+        writer.flush()
+    }
+
+    /// Write the history as a JSON array with one object per conversion
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let objects: Vec<String> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    r#"{{"from":{},"to":{}}}"#,
+                    json_temperature(entry.request.from(), None),
+                    json_temperature(entry.result, None)
+                )
+            })
+            .collect();
+        writeln!(writer, "[{}]", objects.join(","))?;
+        writer.flush()
+    }
+
+    /// Read a history written by `write_csv`
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The CSV input, header row included
+    ///
+    /// # Returns
+    ///
+    /// * The history, or an `InvalidData` error naming the first line that cannot be read
+    pub fn read_csv<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut history = History::default();
+        for (number, line) in reader.lines().enumerate().skip(1) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let request = parse_csv_row(&line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: not a history entry", number + 1),
+                )
+            })?;
+            history.push(request);
+        }
+        Ok(history)
+    }
+
+    /// Save the history to a file, as JSON for `.json` files and as CSV otherwise
+    ///
+    /// Missing parent directories are created.
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let writer = BufWriter::new(File::create(path)?);
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        if is_json {
+            self.write_json(writer)
+        } else {
+            self.write_csv(writer)
+        }
+    }
+
+    /// Load a CSV history file, or start empty if the file does not exist yet
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match File::open(path) {
+            Ok(file) => History::read_csv(BufReader::new(file)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// Parse one `from_value,from_unit,to_value,to_unit` row back into a request
+///
+/// The stored result is not needed: converting again gives the same value.
+fn parse_csv_row(line: &str) -> Option<ConversionRequest> {
+    let fields = split_record(line, ',');
+    let [value, from, _, to] = fields.as_slice() else {
+        return None;
+    };
+    let from: ConversionUnit = from.parse().ok()?;
+    let temperature = TemperatureUnit::try_new(from, value.trim().parse().ok()?).ok()?;
+    Some(ConversionRequest::new(temperature, to.parse().ok()?))
+}
+
+/// Returns the directory where applications keep their data for this user
+///
+/// `$XDG_DATA_HOME` wins everywhere; otherwise `%APPDATA%` on Windows,
+/// `~/Library/Application Support` on macOS and `~/.local/share` elsewhere.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(PathBuf::from);
+    }
+    let home = PathBuf::from(env::var_os("HOME")?);
+    if cfg!(target_os = "macos") {
+        Some(home.join("Library").join("Application Support"))
+    } else {
+        Some(home.join(".local").join("share"))
+    }
+}
+
+/// Returns the file that keeps the history between sessions, under `data_dir`
+pub fn default_path() -> Option<PathBuf> {
+    Some(
+        data_dir()?
+            .join("temperature-converter")
+            .join("history.csv"),
+    )
+}
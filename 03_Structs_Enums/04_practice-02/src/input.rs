@@ -0,0 +1,130 @@
+//! Module with the input of the interactive menus.
+//! A thread reads the lines and hands them over, so the menu can be told the input ended
+//! while it is still waiting for a line: Ctrl-C then closes the session the same way
+//! Ctrl-D does, and the history is saved on the way out.
+//!
+//! ```
+//! use temperature::input::ThreadedInput;
+//! use temperature::session::Session;
+//!
+//! let (input, interrupter) = ThreadedInput::spawn("1\n2\n100\n".as_bytes());
+//! let mut session = Session::new(input, Vec::new());
+//! session.run().unwrap();
+//! assert_eq!(session.history().len(), 1);
+//! assert!(!interrupter.is_interrupted());
+//! ```
+
+use std::io::{self, BufRead, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// Enum to represent what the reading thread hands over
+enum Line {
+    Text(Vec<u8>),
+    Failed(io::Error),
+    End,
+}
+
+/// Struct to represent lines read on their own thread
+pub struct ThreadedInput {
+    lines: Receiver<Line>,
+    current: Vec<u8>,
+    position: usize,
+    ended: bool,
+}
+
+/// Struct to represent the handle that ends a `ThreadedInput` early, e.g. from a Ctrl-C handler
+#[derive(Clone)]
+pub struct Interrupter {
+    sender: Sender<Line>,
+    interrupted: Arc<AtomicBool>,
+}
+
+impl ThreadedInput {
+    /// Start reading `reader` on a new thread
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Where the lines come from, e.g. stdin
+    ///
+    /// # Returns
+    ///
+    /// * The input to hand to a session, and the handle that interrupts it
+    pub fn spawn<R: BufRead + Send + 'static>(mut reader: R) -> (Self, Interrupter) {
+        let (sender, lines) = mpsc::channel();
+        let thread_sender = sender.clone();
+        thread::spawn(move || {
+            loop {
+                let mut line = Vec::new();
+                let message = match reader.read_until(b'\n', &mut line) {
+                    Ok(0) => Line::End,
+                    Ok(_) => Line::Text(line),
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(error) => Line::Failed(error),
+                };
+                let last = !matches!(message, Line::Text(_));
+                // A closed receiver means the session is over
+                if thread_sender.send(message).is_err() || last {
+                    break;
+                }
+            }
+        });
+        let input = ThreadedInput {
+            lines,
+            current: Vec::new(),
+            position: 0,
+            ended: false,
+        };
+        let interrupter = Interrupter {
+            sender,
+            interrupted: Arc::new(AtomicBool::new(false)),
+        };
+        (input, interrupter)
+    }
+}
+
+impl Read for ThreadedInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl BufRead for ThreadedInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position == self.current.len() && !self.ended {
+            match self.lines.recv() {
+                Ok(Line::Text(line)) => {
+                    self.current = line;
+                    self.position = 0;
+                }
+                Ok(Line::Failed(error)) => return Err(error),
+                Ok(Line::End) | Err(_) => self.ended = true,
+            }
+        }
+        Ok(&self.current[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.current.len());
+    }
+}
+
+impl Interrupter {
+    /// End the input after the lines already handed over; the rest of the reader is left unread
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+        // Nobody is reading any more if the session already ended
+        let _ = self.sender.send(Line::End);
+    }
+
+    /// Returns true once `interrupt` was called
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+}
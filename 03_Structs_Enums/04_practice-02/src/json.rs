@@ -0,0 +1,306 @@
+//! Module with a small JSON reader for the request bodies of the HTTP API.
+//! The converter writes its JSON by hand (see `output`), and reading is kept just as small:
+//! objects, arrays, strings, numbers, booleans and null, with the position of the first
+//! mistake.
+//!
+//! ```
+//! use temperature::json::{self, JsonValue};
+//!
+//! let value = json::parse(r#"{"from": "C", "values": [0, 37.5]}"#).unwrap();
+//! assert_eq!(value.get("from").and_then(JsonValue::as_str), Some("C"));
+//! assert_eq!(value.get("values").and_then(JsonValue::as_array).map(|v| v.len()), Some(2));
+//! ```
+
+use std::fmt;
+
+/// Deepest nesting of arrays and objects read, so a document of brackets cannot
+/// exhaust the stack
+pub const MAX_DEPTH: usize = 64;
+
+/// Enum to represent a parsed JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// Keys keep their order; with duplicate keys `get` returns the first one
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the value of a key if this is an object that has it
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the number, if this is one
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Returns the text, if this is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Returns the items, if this is an array
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Struct to represent invalid JSON and the byte offset where reading stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON at byte {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// Parse a whole JSON document
+///
+/// # Arguments
+///
+/// * `input` - The document; whitespace around the value is allowed
+///
+/// # Returns
+///
+/// * The value, or where and why the document is not valid JSON
+pub fn parse(input: &str) -> Result<JsonValue, JsonError> {
+    let mut parser = Parser {
+        input,
+        offset: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.offset < input.len() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+    Ok(value)
+}
+
+/// Escape a string so it can be written between double quotes in JSON
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Struct to represent the position of the reader in the document
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    /// Arrays and objects open around the current position
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Build an error at the current position
+    fn error(&self, message: &'static str) -> JsonError {
+        JsonError {
+            offset: self.offset,
+            message,
+        }
+    }
+
+    /// Returns the rest of the document
+    fn rest(&self) -> &str {
+        &self.input[self.offset..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    /// Consume `expected` after optional whitespace, or fail
+    fn expect(&mut self, expected: char, message: &'static str) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.rest().starts_with(expected) {
+            self.offset += expected.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    /// Read any value
+    fn value(&mut self) -> Result<JsonValue, JsonError> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        for (literal, value) in [
+            ("null", JsonValue::Null),
+            ("true", JsonValue::Bool(true)),
+            ("false", JsonValue::Bool(false)),
+        ] {
+            if rest.starts_with(literal) {
+                self.offset += literal.len();
+                return Ok(value);
+            }
+        }
+        match rest.chars().next() {
+            Some(bracket @ ('{' | '[')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(
+                        self.error("arrays and objects are nested more than 64 levels deep")
+                    );
+                }
+                self.depth += 1;
+                let value = if bracket == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => Ok(JsonValue::String(self.string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of the document")),
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('{', "expected `{`")?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.rest().starts_with('}') {
+            self.offset += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if !self.rest().starts_with('"') {
+                return Err(self.error("expected a key in double quotes"));
+            }
+            let key = self.string()?;
+            self.expect(':', "expected `:` after the key")?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.rest().chars().next() {
+                Some(',') => self.offset += 1,
+                Some('}') => {
+                    self.offset += 1;
+                    return Ok(JsonValue::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('[', "expected `[`")?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.rest().starts_with(']') {
+            self.offset += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.rest().chars().next() {
+                Some(',') => self.offset += 1,
+                Some(']') => {
+                    self.offset += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    /// Read a string, starting at its opening quote
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.offset += 1;
+        let mut text = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.offset += index + 1;
+                    return Ok(text);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let digits: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&digits, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        _ => {
+                            self.offset += index;
+                            return Err(self.error("invalid escape sequence"));
+                        }
+                    };
+                    text.push(escaped);
+                }
+                c if c.is_control() => {
+                    self.offset += index;
+                    return Err(self.error("control character in a string"));
+                }
+                c => text.push(c),
+            }
+        }
+        self.offset = self.input.len();
+        Err(self.error("unterminated string"))
+    }
+
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(rest.len());
+        let number = rest[..length]
+            .parse()
+            .map_err(|_| self.error("invalid number"))?;
+        self.offset += length;
+        Ok(JsonValue::Number(number))
+    }
+}
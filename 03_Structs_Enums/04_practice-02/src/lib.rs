@@ -0,0 +1,70 @@
+//! Reusable temperature conversion library.
+//!
+//! This crate holds the conversion logic that started as the Practice 2 binary,
+//! so other tools can convert temperatures without going through the interactive menu.
+//!
+//! ```
+//! use temperature::{ConversionUnit, TemperatureUnit};
+//!
+//! let boiling = TemperatureUnit::Celsius(100.0);
+//! let converted = boiling.convert_to(ConversionUnit::ToFahrenheit);
+//! assert_eq!(converted, TemperatureUnit::Fahrenheit(212.0));
+//! ```
+
+/// Threshold alerts on streams of readings, with hysteresis against flapping.
+pub mod alert;
+/// Batch conversion of a column in CSV/TSV files.
+pub mod batch;
+/// Command line parsing for the non-interactive mode.
+pub mod cli;
+/// User-defined units read from an INI/TOML definitions file.
+pub mod definitions;
+/// Temperature differences and the arithmetic between temperatures and differences.
+pub mod delta;
+/// Exact conversions with fractions, so values such as `0.1` °C never drift.
+pub mod exact;
+/// Parser for conversion expressions such as `98.6 °F to C`.
+pub mod expression;
+/// The C ABI: `extern "C"` conversion functions and the generator of their header.
+pub mod ffi;
+/// The watch mode: readings of a growing file or of stdin converted as they arrive.
+pub mod follow;
+/// Conversion constants and the plain `f64` conversion functions.
+pub mod formulas;
+/// History of the conversions made in the interactive menu.
+pub mod history;
+/// Menu input read on its own thread, so Ctrl-C can end it like the end of the input.
+pub mod input;
+/// A small JSON reader for the bodies of the HTTP API.
+pub mod json;
+/// English and Spanish messages and decimal separators.
+pub mod locale;
+/// Text, JSON and JSON Lines output of conversion results.
+pub mod output;
+/// Units of several dimensions registered against a base unit, and conversions between them.
+pub mod registry;
+/// Conversion requests that pair a temperature with a target unit.
+pub mod request;
+/// The local HTTP API: `GET /convert` and `POST /convert/batch`.
+pub mod server;
+/// The interactive menu, reading from any reader and writing to any writer.
+pub mod session;
+/// Summary statistics of a series of readings, with differences kept as deltas.
+pub mod stats;
+/// Conversion tables for a range of temperatures.
+pub mod table;
+/// The full-screen terminal interface, with a layout that follows the terminal size.
+pub mod tui;
+/// Temperatures whose scale is checked at compile time, such as `Celsius`.
+pub mod typed;
+/// The interactive menu of the unit registry, built from the registered units.
+pub mod unit_session;
+/// Temperature values tagged with their unit and the available target units.
+pub mod units;
+/// Heat index, wind chill, dew point and apparent temperature in any scale.
+pub mod weather;
+
+// Re-export the main types so users can write `temperature::TemperatureUnit`.
+pub use delta::TemperatureDelta;
+pub use request::ConversionRequest;
+pub use units::{ConversionUnit, ParseUnitError, TemperatureError, TemperatureUnit};
@@ -0,0 +1,557 @@
+//! Module with the message catalog of the converter.
+//! Every sentence shown by the menu has an English and a Spanish version, and numbers
+//! are written and read with the decimal separator of the language (`36.6` or `36,6`).
+//!
+//! ```
+//! use temperature::locale::{Locale, Message};
+//! use temperature::TemperatureUnit;
+//!
+//! let spanish: Locale = "es_ES.UTF-8".parse().unwrap();
+//! assert_eq!(spanish.format_temperature(TemperatureUnit::Celsius(36.6), 1), "36,6 °C");
+//! assert_eq!(spanish.parse_number("36,6"), Some(36.6));
+//! assert_eq!(spanish.message(Message::Goodbye), "Saliendo del programa. ¡Adiós!");
+//! ```
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::exact::ExactTemperature;
+use crate::expression::{ExpressionError, ExpressionErrorKind};
+use crate::registry::{ConversionError, Dimension, Quantity};
+use crate::request::ConversionRequest;
+use crate::stats::Statistic;
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+use crate::weather::{Calculator, WeatherError};
+
+/// Enum to represent the languages of the catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// English, with `.` as the decimal separator
+    #[default]
+    En,
+    /// Spanish, with `,` as the decimal separator
+    Es,
+}
+
+/// Enum to represent the sentences of the menu
+#[derive(Debug, Clone, Copy)]
+pub enum Message<'a> {
+    MenuTitle,
+    ConvertFrom,
+    Exit,
+    ExpressionHint,
+    HistoryHint,
+    EnterChoice,
+    /// Asks for the target scale after the source scale was picked
+    ConvertTo(ConversionUnit),
+    EnterValue,
+    InvalidChoice,
+    InvalidValue,
+    Goodbye,
+    ResultTitle,
+    HistoryTitle,
+    NoConversions,
+    HistoryEmpty,
+    NoHistoryEntry(usize),
+    NotEntryNumber(&'a str),
+    /// A temperature that was rejected, e.g. below absolute zero
+    Temperature(&'a TemperatureError),
+    /// The expression with a caret under the mistake, followed by the explanation
+    Expression {
+        input: &'a str,
+        error: &'a ExpressionError,
+    },
+    UnitMenuTitle,
+    ChooseQuantity,
+    UnitExpressionHint,
+    /// The name of a dimension, e.g. `Length`
+    Dimension(Dimension),
+    /// Asks for the target unit after the source unit was picked, given by name
+    ConvertUnitTo(&'a str),
+    EnterQuantity,
+    InvalidQuantity,
+    /// A conversion of the unit registry that was rejected
+    Unit(&'a ConversionError),
+    /// The label of one line of a statistics summary
+    Statistic(Statistic),
+    TuiFrom,
+    TuiTo,
+    TuiValue,
+    AllScales,
+    TuiHelp,
+    /// The smallest terminal the full-screen interface fits in
+    TerminalTooSmall {
+        width: u16,
+        height: u16,
+    },
+    WeatherMenu,
+    /// The name of a weather calculator, e.g. `Heat index`
+    Calculator(Calculator),
+    /// Asks for the scale of a weather calculation
+    CalculateIn(Calculator),
+    EnterHumidity,
+    EnterWindSpeed,
+    /// What to type in the full-screen interface for a weather calculator
+    TuiWeatherInputs(Calculator),
+    /// Inputs a weather calculator cannot work with
+    Weather(&'a WeatherError),
+}
+
+impl Locale {
+    /// Pick the language from the environment, like other command line tools do
+    ///
+    /// `LC_ALL` wins over `LC_MESSAGES`, which wins over `LANG`.
+    /// Unknown or missing languages fall back to English.
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the character between the integer and fractional parts of a number
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Locale::En => '.',
+            Locale::Es => ',',
+        }
+    }
+
+    /// Format a number with the given decimals and this language's separator
+    pub fn format_number(&self, value: f64, precision: usize) -> String {
+        self.localize_decimals(format!("{value:.precision$}"))
+    }
+
+    /// Format a temperature with its symbol, e.g. `36,60 °C`
+    pub fn format_temperature(&self, temperature: TemperatureUnit, precision: usize) -> String {
+        self.localize_decimals(format!("{temperature:.precision$}"))
+    }
+
+    /// Format a conversion as a sentence, e.g. `37.00 °C is 98.60 °F`
+    pub fn format_conversion(&self, request: &ConversionRequest, precision: usize) -> String {
+        let from = self.format_temperature(request.from(), precision);
+        let to = self.format_temperature(request.from().convert_to(request.to()), precision);
+        match self {
+            Locale::En => format!("{from} is {to}"),
+            Locale::Es => format!("{from} equivalen a {to}"),
+        }
+    }
+
+    /// Format an exact conversion as a sentence, e.g. `100 °F is 340/9 °C (≈ 37.78 °C)`
+    ///
+    /// Results that are not finite decimals are followed by their rounded value.
+    pub fn format_exact_conversion(
+        &self,
+        from: &ExactTemperature,
+        to: &ExactTemperature,
+        precision: usize,
+    ) -> String {
+        let mut to_text = self.localize_decimals(to.to_string());
+        if !to.value().is_decimal() {
+            let rounded = self.format_temperature(to.to_temperature(), precision);
+            to_text = format!("{to_text} (≈ {rounded})");
+        }
+        let from = self.localize_decimals(from.to_string());
+        match self {
+            Locale::En => format!("{from} is {to_text}"),
+            Locale::Es => format!("{from} equivalen a {to_text}"),
+        }
+    }
+
+    /// Format the result of a weather calculator, e.g. `Heat index at 90.00 °F: 105.92 °F`
+    pub fn format_weather(
+        &self,
+        calculator: Calculator,
+        from: TemperatureUnit,
+        result: TemperatureUnit,
+        precision: usize,
+    ) -> String {
+        let name = self.message(Message::Calculator(calculator));
+        let from = self.format_temperature(from, precision);
+        let result = self.format_temperature(result, precision);
+        match self {
+            Locale::En => format!("{name} at {from}: {result}"),
+            Locale::Es => format!("{name} a {from}: {result}"),
+        }
+    }
+
+    /// Format a value of the unit registry with its symbol, e.g. `3,11 mi`
+    pub fn format_quantity(&self, quantity: &Quantity<'_>, precision: usize) -> String {
+        format!(
+            "{} {}",
+            self.format_number(quantity.value, precision),
+            quantity.unit.symbol
+        )
+    }
+
+    /// Format a conversion of the unit registry as a sentence, e.g. `5.00 km is 3.11 mi`
+    pub fn format_quantity_conversion(
+        &self,
+        from: &Quantity<'_>,
+        to: &Quantity<'_>,
+        precision: usize,
+    ) -> String {
+        let from = self.format_quantity(from, precision);
+        let to = self.format_quantity(to, precision);
+        match self {
+            Locale::En => format!("{from} is {to}"),
+            Locale::Es => format!("{from} equivalen a {to}"),
+        }
+    }
+
+    /// Read a number typed in this language
+    ///
+    /// A dot is always accepted, so `36.6` works in Spanish too;
+    /// a comma is only a decimal separator in Spanish.
+    pub fn parse_number(&self, input: &str) -> Option<f64> {
+        self.normalize_decimals(input.trim()).parse().ok()
+    }
+
+    /// Turn decimal commas between digits into dots, so `36,6C` can be parsed as `36.6C`
+    ///
+    /// The text keeps its length, so error columns still point at the original input.
+    pub fn normalize_decimals(&self, input: &str) -> String {
+        if *self == Locale::En {
+            return input.to_string();
+        }
+        let chars: Vec<char> = input.chars().collect();
+        chars
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                let between_digits = index > 0
+                    && chars[index - 1].is_ascii_digit()
+                    && chars.get(index + 1).is_some_and(char::is_ascii_digit);
+                if *c == ',' && between_digits { '.' } else { *c }
+            })
+            .collect()
+    }
+
+    /// Returns the sentence in this language
+    pub fn message(&self, message: Message<'_>) -> String {
+        match self {
+            Locale::En => self.english(message),
+            Locale::Es => self.spanish(message),
+        }
+    }
+
+    /// The English catalog
+    fn english(&self, message: Message<'_>) -> String {
+        match message {
+            Message::MenuTitle => "====== Temperature Converter ======".to_string(),
+            Message::ConvertFrom => "Convert from:".to_string(),
+            Message::Exit => "0. Exit".to_string(),
+            Message::ExpressionHint => "Or type an expression such as `98.6F in C`".to_string(),
+            Message::HistoryHint => {
+                "Type `history`, `!N` to repeat entry N or `swap` to convert the last result back"
+                    .to_string()
+            }
+            Message::EnterChoice => "Enter your choice: ".to_string(),
+            Message::ConvertTo(unit) => format!("Convert {} to:", unit.name()),
+            Message::EnterValue => "Enter the temperature value: ".to_string(),
+            Message::InvalidChoice => "Invalid choice, please try again.".to_string(),
+            Message::InvalidValue => "Invalid temperature value, please try again.".to_string(),
+            Message::Goodbye => "Exiting the program. Goodbye!".to_string(),
+            Message::ResultTitle => "====== Conversion Result ======".to_string(),
+            Message::HistoryTitle => "====== Conversion History ======".to_string(),
+            Message::NoConversions => "No conversions yet.".to_string(),
+            Message::HistoryEmpty => "The history is empty.".to_string(),
+            Message::NoHistoryEntry(number) => {
+                format!("There is no conversion number {number} in the history.")
+            }
+            Message::NotEntryNumber(text) => format!("`{text}` is not a history entry number."),
+            Message::Temperature(error) => error.to_string(),
+            Message::Expression { input, error } => error.highlight(input),
+            Message::UnitMenuTitle => "====== Unit Converter ======".to_string(),
+            Message::ChooseQuantity => "Choose a quantity:".to_string(),
+            Message::UnitExpressionHint => "Or type a conversion such as `5 km to mi`".to_string(),
+            Message::Dimension(dimension) => dimension.name().to_string(),
+            Message::ConvertUnitTo(name) => format!("Convert {name} to:"),
+            Message::EnterQuantity => "Enter the value: ".to_string(),
+            Message::InvalidQuantity => "Invalid value, please try again.".to_string(),
+            Message::Unit(error) => error.to_string(),
+            Message::Statistic(statistic) => match statistic {
+                Statistic::Count => "Readings",
+                Statistic::Minimum => "Minimum",
+                Statistic::Maximum => "Maximum",
+                Statistic::Mean => "Mean",
+                Statistic::Median => "Median",
+                Statistic::Range => "Range",
+                Statistic::StdDev => "Standard deviation",
+            }
+            .to_string(),
+            Message::TuiFrom => "From".to_string(),
+            Message::TuiTo => "To".to_string(),
+            Message::TuiValue => "Value:".to_string(),
+            Message::AllScales => "All scales".to_string(),
+            Message::TuiHelp => {
+                "↑↓ pick a scale · ←→ switch list · s swap · w weather · Esc quit".to_string()
+            }
+            Message::TerminalTooSmall { width, height } => {
+                format!("The terminal is too small, it needs {width}x{height}.")
+            }
+            Message::WeatherMenu => "Weather:".to_string(),
+            Message::Calculator(calculator) => calculator.name().to_string(),
+            Message::CalculateIn(calculator) => format!("{} in:", calculator.name()),
+            Message::EnterHumidity => "Enter the relative humidity (%): ".to_string(),
+            Message::EnterWindSpeed => "Enter the wind speed (km/h): ".to_string(),
+            Message::TuiWeatherInputs(calculator) => {
+                let inputs = match calculator {
+                    Calculator::HeatIndex | Calculator::DewPoint => "humidity %",
+                    Calculator::WindChill => "wind km/h",
+                    Calculator::ApparentTemperature => "humidity % and wind km/h",
+                };
+                format!("{}: type the value, then {inputs}", calculator.name())
+            }
+            Message::Weather(error) => error.to_string(),
+        }
+    }
+
+    /// The Spanish catalog
+    fn spanish(&self, message: Message<'_>) -> String {
+        match message {
+            Message::MenuTitle => "====== Conversor de temperatura ======".to_string(),
+            Message::ConvertFrom => "Convertir de:".to_string(),
+            Message::Exit => "0. Salir".to_string(),
+            Message::ExpressionHint => "O escribe una expresión como `36,6C in F`".to_string(),
+            Message::HistoryHint => "Escribe `history`, `!N` para repetir la entrada N o `swap` \
+                 para volver a convertir el último resultado"
+                .to_string(),
+            Message::EnterChoice => "Elige una opción: ".to_string(),
+            Message::ConvertTo(unit) => format!("Convertir {} a:", unit.name()),
+            Message::EnterValue => "Introduce la temperatura: ".to_string(),
+            Message::InvalidChoice => "Opción no válida, inténtalo de nuevo.".to_string(),
+            Message::InvalidValue => "Temperatura no válida, inténtalo de nuevo.".to_string(),
+            Message::Goodbye => "Saliendo del programa. ¡Adiós!".to_string(),
+            Message::ResultTitle => "====== Resultado de la conversión ======".to_string(),
+            Message::HistoryTitle => "====== Historial de conversiones ======".to_string(),
+            Message::NoConversions => "Todavía no hay conversiones.".to_string(),
+            Message::HistoryEmpty => "El historial está vacío.".to_string(),
+            Message::NoHistoryEntry(number) => {
+                format!("No hay ninguna conversión número {number} en el historial.")
+            }
+            Message::NotEntryNumber(text) => {
+                format!("`{text}` no es un número de entrada del historial.")
+            }
+            Message::Temperature(error) => self.spanish_temperature_error(error),
+            Message::Expression { input, error } => {
+                let explanation = match &error.kind {
+                    ExpressionErrorKind::MissingValue => "se esperaba un número".to_string(),
+                    ExpressionErrorKind::InvalidNumber(text) => format!("`{text}` no es un número"),
+                    ExpressionErrorKind::MissingUnit => {
+                        "se esperaba una unidad después del número".to_string()
+                    }
+                    ExpressionErrorKind::UnknownUnit(text) => {
+                        format!("unidad desconocida `{text}`")
+                    }
+                    ExpressionErrorKind::MissingTarget => {
+                        "se esperaba `to`, `in` o `->` seguido de una unidad".to_string()
+                    }
+                    ExpressionErrorKind::UnexpectedInput(text) => format!("`{text}` inesperado"),
+                    ExpressionErrorKind::InvalidTemperature(error) => {
+                        self.spanish_temperature_error(error)
+                    }
+                };
+                format!(
+                    "{input}\n{}^ {explanation} (columna {})",
+                    " ".repeat(error.column.saturating_sub(1)),
+                    error.column
+                )
+            }
+            Message::UnitMenuTitle => "====== Conversor de unidades ======".to_string(),
+            Message::ChooseQuantity => "Elige una magnitud:".to_string(),
+            Message::UnitExpressionHint => "O escribe una conversión como `5 km to mi`".to_string(),
+            Message::Dimension(dimension) => match dimension {
+                Dimension::Temperature => "Temperatura",
+                Dimension::Length => "Longitud",
+                Dimension::Mass => "Masa",
+                Dimension::Volume => "Volumen",
+                Dimension::Speed => "Velocidad",
+            }
+            .to_string(),
+            Message::ConvertUnitTo(name) => format!("Convertir {name} a:"),
+            Message::EnterQuantity => "Introduce el valor: ".to_string(),
+            Message::InvalidQuantity => "Valor no válido, inténtalo de nuevo.".to_string(),
+            Message::Unit(error) => self.spanish_unit_error(error),
+            Message::Statistic(statistic) => match statistic {
+                Statistic::Count => "Lecturas",
+                Statistic::Minimum => "Mínimo",
+                Statistic::Maximum => "Máximo",
+                Statistic::Mean => "Media",
+                Statistic::Median => "Mediana",
+                Statistic::Range => "Rango",
+                Statistic::StdDev => "Desviación típica",
+            }
+            .to_string(),
+            Message::TuiFrom => "De".to_string(),
+            Message::TuiTo => "A".to_string(),
+            Message::TuiValue => "Valor:".to_string(),
+            Message::AllScales => "Todas las escalas".to_string(),
+            Message::TuiHelp => {
+                "↑↓ elegir escala · ←→ cambiar de lista · s intercambiar · w meteorología · Esc salir"
+                    .to_string()
+            }
+            Message::TerminalTooSmall { width, height } => {
+                format!("La terminal es demasiado pequeña, necesita {width}x{height}.")
+            }
+            Message::WeatherMenu => "Meteorología:".to_string(),
+            Message::Calculator(calculator) => match calculator {
+                Calculator::HeatIndex => "Índice de calor",
+                Calculator::WindChill => "Sensación por viento",
+                Calculator::DewPoint => "Punto de rocío",
+                Calculator::ApparentTemperature => "Temperatura aparente",
+            }
+            .to_string(),
+            Message::CalculateIn(calculator) => {
+                format!("{} en:", self.message(Message::Calculator(calculator)))
+            }
+            Message::EnterHumidity => "Introduce la humedad relativa (%): ".to_string(),
+            Message::EnterWindSpeed => "Introduce la velocidad del viento (km/h): ".to_string(),
+            Message::TuiWeatherInputs(calculator) => {
+                let inputs = match calculator {
+                    Calculator::HeatIndex | Calculator::DewPoint => "la humedad %",
+                    Calculator::WindChill => "el viento km/h",
+                    Calculator::ApparentTemperature => "la humedad % y el viento km/h",
+                };
+                format!(
+                    "{}: escribe el valor y luego {inputs}",
+                    self.message(Message::Calculator(calculator))
+                )
+            }
+            Message::Weather(error) => self.spanish_weather_error(error),
+        }
+    }
+
+    /// Explain a rejected temperature in Spanish
+    fn spanish_temperature_error(&self, error: &TemperatureError) -> String {
+        match error {
+            TemperatureError::BelowAbsoluteZero { value, unit } => format!(
+                "{} {} está por debajo del cero absoluto ({} {})",
+                self.localize_decimals(value.to_string()),
+                unit.symbol(),
+                self.format_number(unit.absolute_zero(), 2),
+                unit.symbol()
+            ),
+            TemperatureError::NotFinite { value, unit } => {
+                format!("{value} {} no es una temperatura finita", unit.symbol())
+            }
+            TemperatureError::TooLarge { value, unit, to } => format!(
+                "{} {} es demasiado grande para convertirlo a {}",
+                self.localize_decimals(format!("{value:e}")),
+                unit.symbol(),
+                to.symbol()
+            ),
+        }
+    }
+
+    /// Explain inputs a weather calculator cannot work with in Spanish
+    fn spanish_weather_error(&self, error: &WeatherError) -> String {
+        match error {
+            WeatherError::Temperature(error) => self.spanish_temperature_error(error),
+            WeatherError::Humidity(value) => format!(
+                "{} % no es una humedad relativa, usa un valor mayor que 0 y hasta 100",
+                self.localize_decimals(value.to_string())
+            ),
+            WeatherError::WindSpeed(value) => format!(
+                "{} km/h no es una velocidad del viento",
+                self.localize_decimals(value.to_string())
+            ),
+            WeatherError::TooCold {
+                calculator,
+                minimum,
+            } => format!(
+                "{}: la fórmula solo vale desde {}",
+                self.message(Message::Calculator(*calculator)),
+                self.format_temperature(*minimum, 2)
+            ),
+            WeatherError::TooWarm {
+                calculator,
+                maximum,
+            } => format!(
+                "{}: la fórmula solo vale hasta {}",
+                self.message(Message::Calculator(*calculator)),
+                self.format_temperature(*maximum, 2)
+            ),
+            WeatherError::TooCalm { minimum } => format!(
+                "{}: la fórmula solo vale con viento de al menos {} km/h",
+                self.message(Message::Calculator(Calculator::WindChill)),
+                self.localize_decimals(minimum.to_string())
+            ),
+            WeatherError::TooWindy {
+                calculator,
+                maximum,
+            } => format!(
+                "{}: la fórmula solo vale con viento de hasta {} km/h",
+                self.message(Message::Calculator(*calculator)),
+                self.localize_decimals(maximum.to_string())
+            ),
+        }
+    }
+
+    /// Explain a rejected conversion of the unit registry in Spanish
+    fn spanish_unit_error(&self, error: &ConversionError) -> String {
+        match error {
+            ConversionError::UnknownUnit(name) => format!("unidad desconocida `{name}`"),
+            ConversionError::IncompatibleDimensions { from, to } => format!(
+                "no se puede convertir {} en {}",
+                self.message(Message::Dimension(*from)).to_lowercase(),
+                self.message(Message::Dimension(*to)).to_lowercase()
+            ),
+            ConversionError::NotFinite(value) => format!("{value} no es un valor finito"),
+            ConversionError::BelowMinimum { value, symbol } => format!(
+                "{} {symbol} está por debajo del valor mínimo posible",
+                self.localize_decimals(value.to_string())
+            ),
+            ConversionError::InvalidExpression(input) => {
+                format!("`{input}` no tiene la forma `VALOR UNIDAD to UNIDAD`")
+            }
+        }
+    }
+
+    /// Replace the decimal point of an already formatted number
+    fn localize_decimals(&self, text: String) -> String {
+        match self {
+            Locale::En => text,
+            Locale::Es => text.replace('.', ","),
+        }
+    }
+}
+
+/// Parse a language tag such as `es`, `es-MX` or `es_ES.UTF-8`, ignoring case
+///
+/// The POSIX `C` locale counts as English.
+impl FromStr for Locale {
+    type Err = ParseLocaleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let language = input
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Ok(Locale::En),
+            "es" => Ok(Locale::Es),
+            _ => Err(ParseLocaleError {
+                input: input.to_string(),
+            }),
+        }
+    }
+}
+
+/// Struct to represent a language that the catalog does not have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLocaleError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseLocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown language `{}`, use en or es", self.input)
+    }
+}
+
+impl std::error::Error for ParseLocaleError {}
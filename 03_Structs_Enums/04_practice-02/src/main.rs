@@ -3,352 +3,521 @@
 //! In the anoter Rust program, we created a simple temperature converter that converted
 //! between Celsius and Fahrenheit.
 //! In this program, we will refactor the code to use Structs and Enums to make it more organized and extensible.
-use std::io::{self, Write}; //  Use this to handle user input and clear the console
+//! The conversion logic and the menu session live in the `temperature` library crate;
+//! this binary only wires them to the terminal.
+//! When arguments are given the program converts them and exits instead of showing the menu.
+//! With `--output json` or `--output jsonl` the results are printed as JSON, so they can be piped.
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+use temperature::TemperatureUnit;
+use temperature::alert::AlertMonitor;
+use temperature::batch::{self, BatchError};
+use temperature::cli::{
+    self, AlertArgs, BatchArgs, Command, ExactArgs, FollowArgs, MenuArgs, StatsArgs, UnitArgs,
+};
+use temperature::definitions;
+use temperature::follow::{self, FollowError, MalformedLines, Tail};
+use temperature::history::{self, History};
+use temperature::input::ThreadedInput;
+use temperature::locale::{Locale, Message};
+use temperature::output::{self, OutputFormat};
+use temperature::registry::Registry;
+use temperature::session::{Session, SessionError};
+use temperature::stats;
+use temperature::table;
+use temperature::tui;
+use temperature::unit_session::UnitSession;
 
-///Global constants for temperature conversion
-const SCALE_FACTOR: f64 = 1.8;
-const FAHRENHEIT_OFFSET: f64 = 32.0;
-const KELVIN_OFFSET: f64 = 273.15;
+/// Exit code used when some input could not be converted, read or written
+const CONVERSION_ERROR: u8 = 1;
+/// Exit code used when the command line arguments are wrong
+const USAGE_ERROR: u8 = 2;
+/// Exit code used when the menu is stopped with Ctrl-C, following the shell's 128 + SIGINT
+const INTERRUPTED: u8 = 130;
 
-/// Function to clear the console screen
-fn clear_console() {
-    // Clear the console screen
-    print!("\x1B[2J\x1B[1;1H");
-    match io::stdout().flush() {
-        Ok(_) => {}
-        Err(e) => println!("Error flushing stdout: {}", e),
+/// Runs the temperature or unit menu on stdin and stdout until the user chooses Exit
+///
+/// # Arguments
+///
+/// * `menu_args` - Which menu, output format and history files; in machine formats the menu
+///   goes to stderr
+/// * `registry` - The units offered by the `--units` menu
+///
+/// # Returns
+///
+/// * The exit code for the process
+fn run_menu(menu_args: &MenuArgs, registry: Registry) -> ExitCode {
+    let format = menu_args.output;
+    let goodbye = menu_args.locale.message(Message::Goodbye);
+    // The full-screen interface keeps no history and prints no results to pipe, so any of
+    // those settings, or a stdin or stdout that is not a terminal, keeps the line menu
+    let full_screen = !menu_args.units
+        && !menu_args.line
+        && !format.is_machine()
+        && menu_args.history.is_none()
+        && !menu_args.keep_history
+        && tui::is_supported();
+    if full_screen {
+        return match tui::run(menu_args.locale) {
+            Ok(()) => {
+                println!("{goodbye}");
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("error: {error}");
+                ExitCode::from(CONVERSION_ERROR)
+            }
+        };
     }
-}
-
-/// Enum to represent different options in the menu
-#[derive(PartialEq)] // Derive PartialEq to compare enum variants
-enum MenuOptions {
-    CelsiusToFahrenheit,
-    CelsiusToKelvin,
-    FahrenheitToCelsius,
-    FahrenheitToKelvin,
-    KelvinToCelsius,
-    KelvinToFahrenheit,
-    Exit,
-}
-/// Implement methods for MenuOptions
-impl MenuOptions {
-    /// Function to display the menu options to the user
-    fn display_menu() {
-        println!("====== Temperature Converter ======");
-        println!("1. Celsius to Fahrenheit");
-        println!("2. Celsius to Kelvin");
-        println!("3. Fahrenheit to Celsius");
-        println!("4. Fahrenheit to Kelvin");
-        println!("5. Kelvin to Celsius");
-        println!("6. Kelvin to Fahrenheit");
-        println!("0. Exit");
-        println!("===================================");
+    // Ctrl-C arrives on another thread while the menu waits for input, so it ends the input
+    // there and the session says goodbye and saves its history as on Ctrl-D
+    let (input, interrupter) = ThreadedInput::spawn(BufReader::new(io::stdin()));
+    let handler_interrupter = interrupter.clone();
+    let handler = ctrlc::set_handler(move || {
+        // Move past the prompt the user was typing at
+        if format.is_machine() {
+            eprintln!();
+        } else {
+            println!();
+        }
+        handler_interrupter.interrupt();
+    });
+    if let Err(error) = handler {
+        eprintln!("warning: Ctrl-C will end the menu without saving the history: {error}");
     }
+    let exit_code = |code: ExitCode| {
+        if code == ExitCode::SUCCESS && interrupter.is_interrupted() {
+            ExitCode::from(INTERRUPTED)
+        } else {
+            code
+        }
+    };
 
-    /// Parse a u32 into a MenuOptions enum
-    ///
-    /// # Arguments
-    ///
-    /// * `input` - A u32 representing the menu option
-    ///
-    /// # Returns
-    ///
-    /// * A menuOptions enum
-    fn from_u32(input: u32) -> Option<Self> {
-        match input {
-            1 => Some(MenuOptions::CelsiusToFahrenheit),
-            2 => Some(MenuOptions::CelsiusToKelvin),
-            3 => Some(MenuOptions::FahrenheitToCelsius),
-            4 => Some(MenuOptions::FahrenheitToKelvin),
-            5 => Some(MenuOptions::KelvinToCelsius),
-            6 => Some(MenuOptions::KelvinToFahrenheit),
-            0 => Some(MenuOptions::Exit),
-            _ => None,
+    if menu_args.units {
+        let mut session = UnitSession::new(input, io::stdout())
+            .with_format(format)
+            .with_locale(menu_args.locale)
+            .with_registry(registry);
+        if format.is_machine() {
+            session = session.with_menu_output(Box::new(io::stderr()));
         }
+        return exit_code(menu_exit_code(session.run()));
     }
 
-    /// Read user input and return the selected menu option
-    ///
-    /// # Returns
-    ///
-    /// * Selected menu option as `MenuOptions` enum
-    fn read_menu_options() -> Option<Self> {
-        println!("Enter your choice: ");
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line, please try again.");
-        match input.trim().parse::<u32>() {
-            Ok(num) => match MenuOptions::from_u32(num) {
-                Some(option) => Some(option),
-                None => {
-                    println!("Invalid choice, please try again.");
-                    None
-                }
-            },
-            Err(_) => {
-                println!("Invalid choice, please try again.");
-                None
-            }
-        }
+    // The kept history is optional, so a missing or broken file only starts an empty one
+    let kept_history = menu_args.keep_history.then(history::default_path).flatten();
+    if menu_args.keep_history && kept_history.is_none() {
+        eprintln!("warning: no data directory found, the history will not be kept");
     }
+    let previous = match &kept_history {
+        Some(path) => History::load(path).unwrap_or_else(|error| {
+            eprintln!("warning: cannot read {}: {error}", path.display());
+            History::default()
+        }),
+        None => History::default(),
+    };
 
-    /// Read temperature value from user input
-    ///
-    /// # Returns
-    ///
-    /// * A TemperatureUnit enum with the input value
-    fn read_temperature_value(&self) -> Option<TemperatureUnit> {
-        // If the option is Exit, return None
-        if let Self::Exit = self {
-            return None;
-        }
-        println!("Enter the temperature value: ");
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line, please try again.");
-        // match input.trim().parse::<f64>() {
-        //     Ok(value) => TemperatureUnit::new(self, value),
-        //     Err(_) => {
-        //         println!("Invalid temperature value, please try again.");
-        //         None
-        //     }
-        // }
-        let Ok(value) = input.trim().parse::<f64>() else {
-            println!("Invalid temperature value, please try again.");
-            return None;
-        };
-        TemperatureUnit::new(self, value)
+    // Stdout is not locked for the whole session, so the Ctrl-C handler can still print
+    let mut session = Session::new(input, io::stdout())
+        .with_format(format)
+        .with_locale(menu_args.locale)
+        .with_history(previous);
+    if format.is_machine() {
+        session = session.with_menu_output(Box::new(io::stderr()));
     }
+    let mut code = menu_exit_code(session.run());
 
-    /// Function to perform conversion based on the menu option
-    ///
-    /// # Arguments
-    ///
-    /// * `temperature` - A TemperatureUnit enum representing the input temperature
-    fn perform_conversion(&self, temperature: TemperatureUnit) {
-        let (to_unit, from_label, to_label) = match self {
-            MenuOptions::CelsiusToFahrenheit => (ConversionUnit::ToFahrenheit, "°C", "°F"),
-            MenuOptions::CelsiusToKelvin => (ConversionUnit::ToKelvin, "°C", "K"),
-            MenuOptions::FahrenheitToCelsius => (ConversionUnit::ToCelsius, "°F", "°C"),
-            MenuOptions::FahrenheitToKelvin => (ConversionUnit::ToKelvin, "°F", "K"),
-            MenuOptions::KelvinToCelsius => (ConversionUnit::ToCelsius, "K", "°C"),
-            MenuOptions::KelvinToFahrenheit => (ConversionUnit::ToFahrenheit, "K", "°F"),
-            _ => return,
-        };
-        let request = ConversionRequest::new(temperature, Some(to_unit));
-        let result = request.convert();
-        clear_console();
-        println!("====== Conversion Result ======");
-        println!(
-            "{:.2} {} is {:.2} {}",
-            request.from.value(),
-            from_label,
-            result,
-            to_label
-        );
-        println!("===============================");
+    // Save the history even after a read error, the conversions made so far are still good
+    for path in kept_history.iter().chain(&menu_args.history) {
+        if let Err(error) = session.history().export(path) {
+            eprintln!(
+                "error: cannot save the history to {}: {error}",
+                path.display()
+            );
+            code = ExitCode::from(CONVERSION_ERROR);
+        }
     }
+    exit_code(code)
 }
-/// Enum to represent different temperature units
-enum TemperatureUnit {
-    Celsius(f64),
-    Fahrenheit(f64),
-    Kelvin(f64),
+
+/// Report how a menu session ended and turn it into the exit code for the process
+fn menu_exit_code(result: Result<(), SessionError>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(CONVERSION_ERROR)
+        }
+    }
 }
 
-impl TemperatureUnit {
-    /// Constructor for TemperatureUnit
-    fn new(option: &MenuOptions, value: f64) -> Option<Self> {
-        match option {
-            MenuOptions::CelsiusToFahrenheit | MenuOptions::CelsiusToKelvin => {
-                Some(TemperatureUnit::Celsius(value))
+/// Converts the values given on the command line and prints one line per value
+///
+/// # Arguments
+///
+/// * `args` - The program arguments, without the program name
+/// * `registry` - The units known besides the temperature scales
+///
+/// # Returns
+///
+/// * The exit code for the process
+fn run_command_line(args: &[String], registry: Registry) -> ExitCode {
+    match cli::parse_args_with_registry(args, Locale::from_env(), &registry) {
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Menu(menu_args)) => run_menu(&menu_args, registry),
+        Ok(Command::Convert(cli_args)) => {
+            let mut exit_code = ExitCode::SUCCESS;
+            let mut requests = Vec::new();
+            for request in cli_args.requests() {
+                match request {
+                    Ok(request) => requests.push(request),
+                    // Keep converting the other values, but report the failure
+                    Err(error) => {
+                        eprintln!("error: {error}");
+                        exit_code = ExitCode::from(CONVERSION_ERROR);
+                    }
+                }
             }
-            MenuOptions::FahrenheitToCelsius | MenuOptions::FahrenheitToKelvin => {
-                Some(TemperatureUnit::Fahrenheit(value))
+            print!(
+                "{}",
+                output::format_conversions(
+                    &requests,
+                    cli_args.output(),
+                    cli_args.precision(),
+                    cli_args.locale()
+                )
+            );
+            exit_code
+        }
+        Ok(Command::ConvertUnits(unit_args)) => run_unit_conversions(&unit_args, &registry),
+        Ok(Command::ConvertExact(exact_args)) => run_exact_conversions(&exact_args),
+        Ok(Command::Batch(batch_args)) => run_batch(&batch_args),
+        Ok(Command::Stats(stats_args)) => run_stats(&stats_args),
+        Ok(Command::Follow(follow_args)) => run_follow(&follow_args),
+        Ok(Command::Table(options)) => match table::render_table(&options) {
+            Ok(rendered) => {
+                print!("{rendered}");
+                ExitCode::SUCCESS
             }
-            MenuOptions::KelvinToCelsius | MenuOptions::KelvinToFahrenheit => {
-                Some(TemperatureUnit::Kelvin(value))
+            Err(error) => {
+                eprintln!("error: {error}");
+                ExitCode::from(USAGE_ERROR)
             }
-            _ => None,
-        }
-    }
-    fn value(&self) -> f64 {
-        match self {
-            TemperatureUnit::Celsius(v) => *v,
-            TemperatureUnit::Fahrenheit(v) => *v,
-            TemperatureUnit::Kelvin(v) => *v,
+        },
+        Err(error) => {
+            eprintln!("error: {error}");
+            eprintln!("{}", cli::USAGE);
+            ExitCode::from(USAGE_ERROR)
         }
     }
 }
 
-/// Enum to represent conversion target units
-enum ConversionUnit {
-    ToCelsius,
-    ToFahrenheit,
-    ToKelvin,
-}
-/// Struct to represent a conversion request
-struct ConversionRequest {
-    from: TemperatureUnit,
-    to: ConversionUnit,
-}
-/// Implement methods for ConversionRequest
-impl ConversionRequest {
-    /// Constructor for ConversionRequest
-    fn new(from: TemperatureUnit, to: Option<ConversionUnit>) -> Self {
-        let from_unit = from;
-        let to_unit = to.expect("Invalid conversion unit");
-        ConversionRequest {
-            from: from_unit,
-            to: to_unit,
+/// Summarizes the readings given as arguments, in a file or on stdin
+///
+/// Readings below absolute zero are reported on stderr and left out of the summary.
+///
+/// # Arguments
+///
+/// * `stats_args` - The readings, their scale and the scale of the summary
+///
+/// # Returns
+///
+/// * The exit code for the process
+fn run_stats(stats_args: &StatsArgs) -> ExitCode {
+    let values = if stats_args.values.is_empty() {
+        let read = match &stats_args.input {
+            Some(path) => File::open(path)
+                .and_then(|file| stats::read_readings(BufReader::new(file), stats_args.locale)),
+            None => stats::read_readings(io::stdin().lock(), stats_args.locale),
+        };
+        match read {
+            Ok(values) => values,
+            Err(error) => {
+                let source = stats_args
+                    .input
+                    .as_ref()
+                    .map_or("stdin".to_string(), |path| path.display().to_string());
+                eprintln!("error: cannot read {source}: {error}");
+                return ExitCode::from(CONVERSION_ERROR);
+            }
         }
-    }
+    } else {
+        stats_args.values.clone()
+    };
 
-    /// Perform the conversion and return the result
-    ///
-    /// # Returns
-    ///
-    /// * Converted temperature value as f64
-    fn convert(&self) -> f64 {
-        match (&self.from, &self.to) {
-            (TemperatureUnit::Celsius(value), ConversionUnit::ToFahrenheit) => {
-                celsius_to_fahrenheit(*value)
-            }
-            (TemperatureUnit::Celsius(value), ConversionUnit::ToKelvin) => {
-                celsius_to_kelvin(*value)
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut readings = Vec::new();
+    for value in values {
+        match TemperatureUnit::try_new(stats_args.from, value) {
+            Ok(reading) => readings.push(reading),
+            Err(error) => {
+                eprintln!("warning: skipping {error}");
+                exit_code = ExitCode::from(CONVERSION_ERROR);
             }
-            (TemperatureUnit::Fahrenheit(value), ConversionUnit::ToCelsius) => {
-                fahrenheit_to_celsius(*value)
-            }
-            (TemperatureUnit::Fahrenheit(value), ConversionUnit::ToKelvin) => {
-                fahrenheit_to_kelvin(*value)
-            }
-            (TemperatureUnit::Kelvin(value), ConversionUnit::ToCelsius) => {
-                kelvin_to_celsius(*value)
-            }
-            (TemperatureUnit::Kelvin(value), ConversionUnit::ToFahrenheit) => {
-                kelvin_to_fahrenheit(*value)
-            }
-            _ => 0.0,
         }
     }
+    let Some(summary) = stats::summarize(&readings, stats_args.to) else {
+        eprintln!("error: no readings to summarize");
+        return ExitCode::from(CONVERSION_ERROR);
+    };
+    print!(
+        "{}",
+        stats::render_summary(
+            &summary,
+            stats_args.output,
+            stats_args.precision,
+            stats_args.locale
+        )
+    );
+    exit_code
 }
 
-/// Converts Celsius to Fahrenheit.
+/// Converts the values exactly and prints one line per value
 ///
 /// # Arguments
-/// * `celsius` - Temperature in Celsius.
+///
+/// * `exact_args` - The scales, values and output options
 ///
 /// # Returns
-/// * Temperature in Fahrenheit.
-fn celsius_to_fahrenheit(celsius: f64) -> f64 {
-    // Formula to convert Celsius to Fahrenheit
-    (celsius * SCALE_FACTOR) + FAHRENHEIT_OFFSET
+///
+/// * The exit code for the process
+fn run_exact_conversions(exact_args: &ExactArgs) -> ExitCode {
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut conversions = Vec::new();
+    for conversion in exact_args.conversions() {
+        match conversion {
+            Ok(conversion) => conversions.push(conversion),
+            Err(error) => {
+                eprintln!("error: {error}");
+                exit_code = ExitCode::from(CONVERSION_ERROR);
+            }
+        }
+    }
+    print!(
+        "{}",
+        output::format_exact_conversions(
+            &conversions,
+            exact_args.output,
+            exact_args.precision,
+            exact_args.locale
+        )
+    );
+    exit_code
 }
 
-/// Converts Celsius to Kelvin.
+/// Converts values between two units of the registry and prints one line per value
 ///
 /// # Arguments
-/// * `celsius` - Temperature in Celsius.
+///
+/// * `unit_args` - The units, values and output options
+/// * `registry` - The registry the arguments were parsed with
 ///
 /// # Returns
-/// * Temperature in Kelvin.
-fn celsius_to_kelvin(celsius: f64) -> f64 {
-    // Formula to convert Celsius to Kelvin
-    celsius + KELVIN_OFFSET
+///
+/// * The exit code for the process
+fn run_unit_conversions(unit_args: &UnitArgs, registry: &Registry) -> ExitCode {
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut conversions = Vec::new();
+    for conversion in unit_args.conversions(registry) {
+        match conversion {
+            Ok(conversion) => conversions.push(conversion),
+            Err(error) => {
+                eprintln!("error: {error}");
+                exit_code = ExitCode::from(CONVERSION_ERROR);
+            }
+        }
+    }
+    print!(
+        "{}",
+        output::format_quantity_conversions(
+            &conversions,
+            unit_args.output,
+            unit_args.precision,
+            unit_args.locale
+        )
+    );
+    exit_code
 }
 
-/// Converts Fahrenheit to Celsius.
+/// Builds the known units: the built-in ones plus the user's definitions file
 ///
-/// # Arguments
-/// * `fahrenheit` - Temperature in Fahrenheit.
+/// The file given with `--definitions` must exist; the default one is optional.
 ///
 /// # Returns
-/// * Temperature in Celsius.
-fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
-    // Formula to convert Fahrenheit to Celsius
-    (fahrenheit - FAHRENHEIT_OFFSET) / SCALE_FACTOR
+///
+/// * The registry, or the message explaining why the definitions were rejected
+fn load_registry(args: &[String]) -> Result<Registry, String> {
+    let mut registry = Registry::builtin();
+    let (path, required) = match cli::definitions_path(args).map_err(|e| e.to_string())? {
+        Some(path) => (Some(path), true),
+        None => (definitions::default_path(), false),
+    };
+    if let Some(path) = path {
+        definitions::load(&mut registry, &path, required)
+            .map_err(|error| format!("cannot load units from {}: {error}", path.display()))?;
+    }
+    Ok(registry)
 }
 
-/// Converts Fahrenheit to Kelvin.
+/// Converts a column of a CSV/TSV file and writes the result to stdout
+///
+/// Rows that cannot be converted are reported on stderr with their line number.
 ///
 /// # Arguments
-/// * `fahrenheit` - Temperature in Fahrenheit.
+///
+/// * `batch_args` - The input file and the conversion options
 ///
 /// # Returns
-/// * Temperature in Kelvin.
-fn fahrenheit_to_kelvin(fahrenheit: f64) -> f64 {
-    // First convert Fahrenheit to Celsius, then Celsius to Kelvin
-    let celsius = fahrenheit_to_celsius(fahrenheit);
-    celsius_to_kelvin(celsius)
+///
+/// * The exit code for the process
+fn run_batch(batch_args: &BatchArgs) -> ExitCode {
+    let reader: Box<dyn BufRead> = match &batch_args.input {
+        Some(path) => match File::open(path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(error) => {
+                eprintln!("error: cannot open {}: {error}", path.display());
+                return ExitCode::from(CONVERSION_ERROR);
+            }
+        },
+        None => Box::new(io::stdin().lock()),
+    };
+    let mut alerts = match open_alerts(&batch_args.alerts, batch_args.options.precision) {
+        Ok(alerts) => alerts,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::from(CONVERSION_ERROR);
+        }
+    };
+    let stdout = io::stdout().lock();
+    match batch::convert_delimited_with_alerts(reader, stdout, &batch_args.options, alerts.as_mut())
+    {
+        Ok(report) if report.errors.is_empty() => ExitCode::SUCCESS,
+        Ok(report) => {
+            for error in &report.errors {
+                eprintln!("warning: {error}");
+            }
+            eprintln!(
+                "{} rows converted, {} rows skipped",
+                report.converted,
+                report.errors.len()
+            );
+            ExitCode::from(CONVERSION_ERROR)
+        }
+        Err(BatchError::Io(error)) => {
+            eprintln!("error: {error}");
+            ExitCode::from(CONVERSION_ERROR)
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(USAGE_ERROR)
+        }
+    }
 }
 
-/// Converts Kelvin to Celsius.
+/// Builds the monitor of the alert rules, writing to stderr or appending to the alert log
 ///
 /// # Arguments
-/// * `kelvin` - Temperature in Kelvin.
+///
+/// * `alerts` - The rules and the optional log file
+/// * `precision` - Decimal places of the readings in the events
 ///
 /// # Returns
-/// * Temperature in Celsius.
-fn kelvin_to_celsius(kelvin: f64) -> f64 {
-    // Formula to convert Kelvin to Celsius
-    kelvin - KELVIN_OFFSET
+///
+/// * `None` without rules, or the message explaining why the log cannot be opened
+fn open_alerts(
+    alerts: &AlertArgs,
+    precision: usize,
+) -> Result<Option<AlertMonitor<'static>>, String> {
+    if alerts.rules.is_empty() {
+        return Ok(None);
+    }
+    let rules = alerts.rules.clone();
+    let monitor = match &alerts.log {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|error| format!("cannot open {}: {error}", path.display()))?;
+            AlertMonitor::new(rules, Box::new(file)).with_format(OutputFormat::JsonLines)
+        }
+        None => AlertMonitor::new(rules, Box::new(io::stderr())),
+    };
+    Ok(Some(monitor.with_precision(precision)))
 }
 
-/// Converts Kelvin to Fahrenheit.
+/// Converts the readings of a file or stdin as they arrive, until stdin ends or Ctrl-C
 ///
 /// # Arguments
-/// * `kelvin` - Temperature in Kelvin.
+///
+/// * `follow_args` - The followed file, the scales and what to do with malformed lines
 ///
 /// # Returns
-/// * Temperature in Fahrenheit.
-fn kelvin_to_fahrenheit(kelvin: f64) -> f64 {
-    // First convert Kelvin to Celsius, then Celsius to Fahrenheit
-    let celsius = kelvin_to_celsius(kelvin);
-    celsius_to_fahrenheit(celsius)
+///
+/// * The exit code for the process
+fn run_follow(follow_args: &FollowArgs) -> ExitCode {
+    let reader: Box<dyn BufRead> = match &follow_args.input {
+        Some(path) => match Tail::open(path) {
+            Ok(tail) => Box::new(BufReader::new(tail)),
+            Err(error) => {
+                eprintln!("error: cannot open {}: {error}", path.display());
+                return ExitCode::from(CONVERSION_ERROR);
+            }
+        },
+        None => Box::new(io::stdin().lock()),
+    };
+    let options = &follow_args.options;
+    let mut alerts = match open_alerts(&follow_args.alerts, options.precision) {
+        Ok(alerts) => alerts,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::from(CONVERSION_ERROR);
+        }
+    };
+    let (stdout, stderr) = (io::stdout().lock(), io::stderr());
+    match follow::follow_with_alerts(reader, stdout, stderr, options, alerts.as_mut()) {
+        Ok(report) if report.skipped == 0 || options.on_malformed == MalformedLines::Skip => {
+            ExitCode::SUCCESS
+        }
+        Ok(report) => {
+            eprintln!(
+                "{} readings converted, {} lines skipped",
+                report.converted, report.skipped
+            );
+            ExitCode::from(CONVERSION_ERROR)
+        }
+        Err(FollowError::Malformed(error)) => {
+            eprintln!("error: {error}");
+            ExitCode::from(CONVERSION_ERROR)
+        }
+        Err(FollowError::Io(error)) => {
+            eprintln!("error: {error}");
+            ExitCode::from(CONVERSION_ERROR)
+        }
+    }
 }
 
-fn main() {
-    // Clear the console screen at the start of the program
-    clear_console();
-    // Flag to control the main loop
-    let mut program_state: Option<MenuOptions> = None;
-
-    // Main loop
-    while program_state != Some(MenuOptions::Exit) {
-        // Display the menu options
-        MenuOptions::display_menu();
-        // Read user input
-        program_state = MenuOptions::read_menu_options();
-
-        // Handle the selected menu option
-        match &program_state {
-            // Handle temperature conversions
-            Some(MenuOptions::CelsiusToFahrenheit)
-            | Some(MenuOptions::CelsiusToKelvin)
-            | Some(MenuOptions::FahrenheitToCelsius)
-            | Some(MenuOptions::FahrenheitToKelvin)
-            | Some(MenuOptions::KelvinToCelsius)
-            | Some(MenuOptions::KelvinToFahrenheit) => {
-                if let Some(program_state) = &program_state {
-                    if let Some(temperature) = program_state.read_temperature_value() {
-                        program_state.perform_conversion(temperature);
-                    }
-                }
-            }
-            // Handle exit option
-            Some(MenuOptions::Exit) => {
-                println!("Exiting the program. Goodbye!");
-            }
-            // Handle invalid menu option
-            None => {
-                // Handle invalid menu option
-                clear_console();
-                println!("Invalid choice, please try again.");
-            }
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let registry = match load_registry(&args) {
+        Ok(registry) => registry,
+        Err(message) => {
+            eprintln!("error: {message}");
+            return ExitCode::from(USAGE_ERROR);
         }
+    };
+    // Without arguments we open the interactive menu
+    if args.is_empty() {
+        let menu_args = MenuArgs {
+            locale: Locale::from_env(),
+            ..MenuArgs::default()
+        };
+        return run_menu(&menu_args, registry);
     }
+    run_command_line(&args, registry)
 }
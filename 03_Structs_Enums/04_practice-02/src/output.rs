@@ -0,0 +1,327 @@
+//! Module with the output formats of the converter.
+//! Besides the human readable text, conversions can be printed as JSON or JSON Lines,
+//! so the results can be piped into other tools:
+//!
+//! ```
+//! use temperature::output::{OutputFormat, format_json};
+//! use temperature::{ConversionRequest, ConversionUnit, TemperatureUnit};
+//!
+//! let request = ConversionRequest::new(TemperatureUnit::Celsius(100.0), ConversionUnit::ToFahrenheit);
+//! assert_eq!(
+//!     format_json(&request, 1),
+//!     r#"{"from":{"value":100.0,"unit":"C"},"to":{"value":212.0,"unit":"F"}}"#
+//! );
+//! assert!(OutputFormat::JsonLines.is_machine());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::exact::ExactTemperature;
+use crate::json;
+use crate::locale::Locale;
+use crate::registry::Quantity;
+use crate::request::ConversionRequest;
+use crate::units::TemperatureUnit;
+use crate::weather::Calculator;
+
+/// Enum to represent how conversion results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Sentences such as `37.00 °C is 98.60 °F`
+    #[default]
+    Text,
+    /// A single JSON document: an array with one object per conversion
+    Json,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl OutputFormat {
+    /// Returns true for the formats read by programs rather than people
+    ///
+    /// Banners, prompts on stdout and screen clearing are left out in these formats.
+    pub fn is_machine(&self) -> bool {
+        *self != OutputFormat::Text
+    }
+}
+
+/// Parse a format name such as `json` or `jsonl`, ignoring case
+impl FromStr for OutputFormat {
+    type Err = ParseOutputFormatError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "jsonl" | "jsonlines" | "json-lines" | "ndjson" => Ok(OutputFormat::JsonLines),
+            _ => Err(ParseOutputFormatError {
+                input: input.to_string(),
+            }),
+        }
+    }
+}
+
+/// Struct to represent an output format name that was not recognised
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOutputFormatError {
+    pub input: String,
+}
+
+impl fmt::Display for ParseOutputFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown output format `{}`, use text, json or jsonl",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseOutputFormatError {}
+
+/// Format a conversion as a sentence, e.g. `37.00 °C is 98.60 °F`
+///
+/// # Arguments
+///
+/// * `request` - The conversion to perform
+/// * `precision` - Decimal places of both temperatures
+///
+/// # Returns
+///
+/// * The sentence, without a line break
+pub fn format_text(request: &ConversionRequest, precision: usize) -> String {
+    Locale::En.format_conversion(request, precision)
+}
+
+/// Format a conversion as a compact JSON object
+///
+/// # Arguments
+///
+/// * `request` - The conversion to perform
+/// * `precision` - Decimal places of both values
+///
+/// # Returns
+///
+/// * `{"from":{"value":..,"unit":".."},"to":{"value":..,"unit":".."}}` on a single line
+pub fn format_json(request: &ConversionRequest, precision: usize) -> String {
+    let result = request.from().convert_to(request.to());
+    format!(
+        r#"{{"from":{},"to":{}}}"#,
+        json_temperature(request.from(), Some(precision)),
+        json_temperature(result, Some(precision))
+    )
+}
+
+/// Format a conversion read from a log as a JSON object, with its timestamp when it had one
+///
+/// # Arguments
+///
+/// * `timestamp` - The text that came before the reading, e.g. `2024-05-01T12:00:00Z`
+/// * `request` - The conversion to perform
+/// * `precision` - Decimal places of both values
+///
+/// # Returns
+///
+/// * The object of `format_json` with a leading `"timestamp"` key, on a single line
+pub fn format_timestamped_json(
+    timestamp: Option<&str>,
+    request: &ConversionRequest,
+    precision: usize,
+) -> String {
+    let conversion = format_json(request, precision);
+    match timestamp {
+        Some(timestamp) => format!(
+            r#"{{"timestamp":"{}",{}"#,
+            json::escape(timestamp),
+            &conversion[1..]
+        ),
+        None => conversion,
+    }
+}
+
+/// Format the result of a weather calculator as a single-line JSON object
+///
+/// The object has the same shape as `format_json`, after a `"calculation"` key.
+pub fn format_weather_json(
+    calculator: Calculator,
+    from: TemperatureUnit,
+    result: TemperatureUnit,
+    precision: usize,
+) -> String {
+    format!(
+        r#"{{"calculation":"{}","from":{},"to":{}}}"#,
+        calculator.key(),
+        json_temperature(from, Some(precision)),
+        json_temperature(result, Some(precision))
+    )
+}
+
+/// Format a conversion of the unit registry as a single-line JSON object
+///
+/// The object has the same shape as `format_json`, with the unit symbol as `unit`.
+pub fn format_quantity_json(from: &Quantity<'_>, to: &Quantity<'_>, precision: usize) -> String {
+    format!(
+        r#"{{"from":{},"to":{}}}"#,
+        json_quantity(from, precision),
+        json_quantity(to, precision)
+    )
+}
+
+/// Format conversions of the unit registry as one document, like `format_conversions`
+///
+/// # Arguments
+///
+/// * `conversions` - Pairs of source and converted quantities, in order
+/// * `format` - How to print them
+/// * `precision` - Decimal places of every value
+/// * `locale` - Language of the text format
+pub fn format_quantity_conversions(
+    conversions: &[(Quantity<'_>, Quantity<'_>)],
+    format: OutputFormat,
+    precision: usize,
+    locale: Locale,
+) -> String {
+    let line = |(from, to): &(Quantity<'_>, Quantity<'_>)| match format {
+        OutputFormat::Text => locale.format_quantity_conversion(from, to, precision),
+        OutputFormat::Json | OutputFormat::JsonLines => format_quantity_json(from, to, precision),
+    };
+    match format {
+        OutputFormat::Json => {
+            let objects: Vec<String> = conversions.iter().map(line).collect();
+            format!("[{}]\n", objects.join(","))
+        }
+        OutputFormat::Text | OutputFormat::JsonLines => {
+            conversions.iter().map(|pair| line(pair) + "\n").collect()
+        }
+    }
+}
+
+/// Format an exact conversion as a single-line JSON object
+///
+/// Exact values are strings, since `1/3` is no JSON number; `approx` is the nearest `f64`.
+pub fn format_exact_json(from: &ExactTemperature, to: &ExactTemperature) -> String {
+    format!(r#"{{"from":{},"to":{}}}"#, json_exact(from), json_exact(to))
+}
+
+/// Format exact conversions as one document, like `format_conversions`
+///
+/// # Arguments
+///
+/// * `conversions` - Pairs of source and converted temperatures, in order
+/// * `format` - How to print them
+/// * `precision` - Decimal places of the rounded value printed after fractions
+/// * `locale` - Language of the text format
+pub fn format_exact_conversions(
+    conversions: &[(ExactTemperature, ExactTemperature)],
+    format: OutputFormat,
+    precision: usize,
+    locale: Locale,
+) -> String {
+    let line = |(from, to): &(ExactTemperature, ExactTemperature)| match format {
+        OutputFormat::Text => locale.format_exact_conversion(from, to, precision),
+        OutputFormat::Json | OutputFormat::JsonLines => format_exact_json(from, to),
+    };
+    match format {
+        OutputFormat::Json => {
+            let objects: Vec<String> = conversions.iter().map(line).collect();
+            format!("[{}]\n", objects.join(","))
+        }
+        OutputFormat::Text | OutputFormat::JsonLines => {
+            conversions.iter().map(|pair| line(pair) + "\n").collect()
+        }
+    }
+}
+
+/// Format the conversions as one document in the chosen format
+///
+/// # Arguments
+///
+/// * `requests` - The conversions to perform, in order
+/// * `format` - How to print them
+/// * `precision` - Decimal places of every value
+/// * `locale` - Language of the text format; JSON always uses `.` as the decimal separator
+///
+/// # Returns
+///
+/// * The whole output ending with a line break, or an empty string for no conversions
+///   in the text and JSON Lines formats
+pub fn format_conversions(
+    requests: &[ConversionRequest],
+    format: OutputFormat,
+    precision: usize,
+    locale: Locale,
+) -> String {
+    match format {
+        OutputFormat::Text => requests
+            .iter()
+            .map(|request| locale.format_conversion(request, precision) + "\n")
+            .collect(),
+        OutputFormat::Json => {
+            let objects: Vec<String> = requests
+                .iter()
+                .map(|request| format_json(request, precision))
+                .collect();
+            format!("[{}]\n", objects.join(","))
+        }
+        OutputFormat::JsonLines => requests
+            .iter()
+            .map(|request| format_json(request, precision) + "\n")
+            .collect(),
+    }
+}
+
+/// Format a temperature as `{"value":..,"unit":".."}`, the object every JSON output shares
+///
+/// # Arguments
+///
+/// * `temperature` - The temperature to format
+/// * `precision` - Decimal places of the value, or `None` for every digit
+///
+/// # Returns
+///
+/// * The object on a single line; JSON has no infinity, so a value that is not finite is
+///   written as `null`, though callers should reject such results before getting here
+pub fn json_temperature(temperature: TemperatureUnit, precision: Option<usize>) -> String {
+    let value = temperature.value();
+    let value = match precision {
+        _ if !value.is_finite() => "null".to_string(),
+        // A fixed number of decimals is still a valid JSON number, e.g. `98.60`
+        Some(precision) => format!("{value:.precision$}"),
+        None => value.to_string(),
+    };
+    format!(
+        r#"{{"value":{value},"unit":"{}"}}"#,
+        temperature.unit().code()
+    )
+}
+
+/// Format a quantity as `{"value":..,"unit":".."}`, escaping the symbol
+///
+/// The built-in temperature scales keep the codes of `json_temperature`, e.g. `C` rather
+/// than `°C`, so a conversion gives the same JSON whichever parser read it.
+fn json_quantity(quantity: &Quantity<'_>, precision: usize) -> String {
+    if let Some(scale) = quantity.unit.scale() {
+        return json_temperature(TemperatureUnit::new(scale, quantity.value), Some(precision));
+    }
+    let value = quantity.value;
+    let value = if value.is_finite() {
+        format!("{value:.precision$}")
+    } else {
+        "null".to_string()
+    };
+    format!(
+        r#"{{"value":{value},"unit":"{}"}}"#,
+        json::escape(&quantity.unit.symbol)
+    )
+}
+
+/// Format an exact temperature as `{"value":"..","approx":..,"unit":".."}`
+fn json_exact(temperature: &ExactTemperature) -> String {
+    format!(
+        r#"{{"value":"{}","approx":{},"unit":"{}"}}"#,
+        temperature.value(),
+        temperature.value().to_f64(),
+        temperature.unit().code()
+    )
+}
@@ -0,0 +1,521 @@
+//! Module with the dimension-aware unit registry.
+//! Every unit is registered with a factor and an offset against the base unit of its
+//! dimension, `base = value * factor + offset`, so one conversion routine covers
+//! temperatures, lengths, masses, volumes and speeds. Converting between two dimensions,
+//! e.g. kilograms into metres, is an error instead of a wrong number.
+//!
+//! ```
+//! use temperature::registry::{ConversionError, Dimension, Registry};
+//!
+//! let registry = Registry::builtin();
+//! let miles = registry.convert(42.195, "km", "mi").unwrap();
+//! assert!((miles - 26.219).abs() < 1e-3);
+//! assert!((registry.convert(100.0, "C", "F").unwrap() - 212.0).abs() < 1e-9);
+//! assert_eq!(
+//!     registry.convert(1.0, "kg", "m"),
+//!     Err(ConversionError::IncompatibleDimensions { from: Dimension::Mass, to: Dimension::Length })
+//! );
+//! ```
+
+use std::fmt;
+
+use crate::units::ConversionUnit;
+
+/// Enum to represent the physical quantities the registry knows about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Temperature,
+    Length,
+    Mass,
+    Volume,
+    Speed,
+}
+
+impl Dimension {
+    /// Every dimension, in the order the menu lists them
+    pub const ALL: [Dimension; 5] = [
+        Dimension::Temperature,
+        Dimension::Length,
+        Dimension::Mass,
+        Dimension::Volume,
+        Dimension::Speed,
+    ];
+
+    /// Returns the English name of this dimension, e.g. `Length`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dimension::Temperature => "Temperature",
+            Dimension::Length => "Length",
+            Dimension::Mass => "Mass",
+            Dimension::Volume => "Volume",
+            Dimension::Speed => "Speed",
+        }
+    }
+
+    /// Returns the symbol of the unit every other unit of this dimension is defined against
+    pub fn base_unit(&self) -> &'static str {
+        match self {
+            Dimension::Temperature => "K",
+            Dimension::Length => "m",
+            Dimension::Mass => "kg",
+            Dimension::Volume => "L",
+            Dimension::Speed => "m/s",
+        }
+    }
+
+    /// Returns the smallest possible value in the base unit, if there is one
+    ///
+    /// Only temperatures have a hard floor: nothing is colder than 0 K.
+    pub fn minimum(&self) -> Option<f64> {
+        match self {
+            Dimension::Temperature => Some(0.0),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Struct to represent a unit and how it relates to the base unit of its dimension
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub symbol: String,
+    pub name: String,
+    pub dimension: Dimension,
+    /// Size of one unit in base units
+    pub factor: f64,
+    /// Base value of this unit's zero, only non-zero for temperature scales
+    pub offset: f64,
+    /// Other names accepted when looking the unit up, besides the symbol and the name
+    pub aliases: Vec<String>,
+}
+
+impl Unit {
+    /// Constructor for a unit without offset and aliases
+    ///
+    /// # Arguments
+    ///
+    /// * `symbol` - Short name, e.g. `km`
+    /// * `name` - Full name, e.g. `kilometre`
+    /// * `dimension` - What the unit measures
+    /// * `factor` - How many base units one of these is, e.g. `1000.0` for kilometres
+    pub fn new(symbol: &str, name: &str, dimension: Dimension, factor: f64) -> Self {
+        Unit {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            dimension,
+            factor,
+            offset: 0.0,
+            aliases: Vec::new(),
+        }
+    }
+
+    /// Set the base value of this unit's zero, e.g. `273.15` for Celsius
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Add other names the unit can be looked up by
+    pub fn with_aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases
+            .extend(aliases.iter().map(|alias| alias.to_string()));
+        self
+    }
+
+    /// Returns the temperature scale of the converter this unit is, if it is a built-in one
+    pub fn scale(&self) -> Option<ConversionUnit> {
+        ConversionUnit::ALL.into_iter().find(|scale| {
+            self.dimension == Dimension::Temperature
+                && self.symbol == scale.symbol()
+                && self.name == scale.name()
+        })
+    }
+
+    /// Converts a value of this unit into the base unit
+    pub fn to_base(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    /// Converts a value of the base unit into this unit
+    pub fn base_to_unit(&self, base: f64) -> f64 {
+        (base - self.offset) / self.factor
+    }
+
+    /// Returns every name this unit answers to: symbol, name and aliases
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        [self.symbol.as_str(), self.name.as_str()]
+            .into_iter()
+            .chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+/// Enum to represent why a unit cannot be registered
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// The symbol is empty
+    EmptySymbol,
+    /// The factor is zero or not finite, so values could not be converted back
+    InvalidFactor { symbol: String, factor: f64 },
+    /// The offset is not finite
+    InvalidOffset { symbol: String, offset: f64 },
+    /// Another unit already answers to this name
+    DuplicateName { name: String, existing: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::EmptySymbol => write!(f, "a unit needs a symbol"),
+            RegistryError::InvalidFactor { symbol, factor } => {
+                write!(f, "unit `{symbol}` has an invalid factor {factor}")
+            }
+            RegistryError::InvalidOffset { symbol, offset } => {
+                write!(f, "unit `{symbol}` has an invalid offset {offset}")
+            }
+            RegistryError::DuplicateName { name, existing } => {
+                write!(f, "`{name}` is already a name of unit `{existing}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Enum to represent why a conversion through the registry failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    /// No unit answers to this name
+    UnknownUnit(String),
+    /// The units measure different things, e.g. mass and length
+    IncompatibleDimensions { from: Dimension, to: Dimension },
+    /// The value is NaN or infinite
+    NotFinite(f64),
+    /// The value is below the floor of its dimension, e.g. colder than 0 K
+    BelowMinimum { value: f64, symbol: String },
+    /// The text is not of the form `VALUE UNIT to UNIT`
+    InvalidExpression(String),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::UnknownUnit(name) => write!(f, "unknown unit `{name}`"),
+            ConversionError::IncompatibleDimensions { from, to } => write!(
+                f,
+                "cannot convert {} into {}",
+                from.name().to_lowercase(),
+                to.name().to_lowercase()
+            ),
+            ConversionError::NotFinite(value) => write!(f, "{value} is not a finite value"),
+            ConversionError::BelowMinimum { value, symbol } => {
+                write!(f, "{value} {symbol} is below the lowest possible value")
+            }
+            ConversionError::InvalidExpression(input) => {
+                write!(f, "`{input}` is not of the form `VALUE UNIT to UNIT`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// Struct to represent a value together with the unit it is measured in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity<'a> {
+    pub value: f64,
+    pub unit: &'a Unit,
+}
+
+impl<'a> Quantity<'a> {
+    /// Convert this quantity into another unit of the same dimension
+    pub fn convert_to<'b>(&self, to: &'b Unit) -> Result<Quantity<'b>, ConversionError> {
+        Ok(Quantity {
+            value: convert_between(self.value, self.unit, to)?,
+            unit: to,
+        })
+    }
+}
+
+/// Struct to represent the set of known units
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Registry {
+    units: Vec<Unit>,
+}
+
+impl Registry {
+    /// Constructor for an empty registry
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Constructor for a registry with every built-in unit
+    ///
+    /// The temperature scales come from `ConversionUnit`, so both converters agree.
+    pub fn builtin() -> Self {
+        let mut registry = Registry::new();
+        for unit in builtin_units() {
+            registry
+                .register(unit)
+                .expect("the built-in units have unique names");
+        }
+        registry
+    }
+
+    /// Add a unit, checking that it can be converted and that its names are free
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The unit to add
+    ///
+    /// # Returns
+    ///
+    /// * An error explaining why the unit was not added
+    pub fn register(&mut self, unit: Unit) -> Result<(), RegistryError> {
+        if unit.symbol.trim().is_empty() {
+            return Err(RegistryError::EmptySymbol);
+        }
+        if !unit.factor.is_finite() || unit.factor == 0.0 {
+            return Err(RegistryError::InvalidFactor {
+                symbol: unit.symbol,
+                factor: unit.factor,
+            });
+        }
+        if !unit.offset.is_finite() {
+            return Err(RegistryError::InvalidOffset {
+                symbol: unit.symbol,
+                offset: unit.offset,
+            });
+        }
+        for name in unit.names() {
+            if let Some(existing) = self.find(name) {
+                return Err(RegistryError::DuplicateName {
+                    name: name.to_string(),
+                    existing: existing.symbol.clone(),
+                });
+            }
+        }
+        self.units.push(unit);
+        Ok(())
+    }
+
+    /// Look a unit up by symbol, name or alias, ignoring case
+    pub fn find(&self, name: &str) -> Option<&Unit> {
+        let name = name.trim().to_lowercase();
+        self.units
+            .iter()
+            .find(|unit| unit.names().any(|known| known.to_lowercase() == name))
+    }
+
+    /// Returns every unit, in the order they were registered
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// Returns the units of one dimension, in the order they were registered
+    pub fn units_of(&self, dimension: Dimension) -> impl Iterator<Item = &Unit> {
+        self.units
+            .iter()
+            .filter(move |unit| unit.dimension == dimension)
+    }
+
+    /// Returns the dimensions that have at least one unit, in `Dimension::ALL` order
+    pub fn dimensions(&self) -> Vec<Dimension> {
+        Dimension::ALL
+            .into_iter()
+            .filter(|dimension| self.units_of(*dimension).next().is_some())
+            .collect()
+    }
+
+    /// Convert a value between two units of the same dimension
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to convert
+    /// * `from` - Name of the unit of `value`
+    /// * `to` - Name of the unit to convert into
+    ///
+    /// # Returns
+    ///
+    /// * The converted value, or why the conversion makes no sense
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Result<f64, ConversionError> {
+        let from = self
+            .find(from)
+            .ok_or_else(|| ConversionError::UnknownUnit(from.to_string()))?;
+        let to = self
+            .find(to)
+            .ok_or_else(|| ConversionError::UnknownUnit(to.to_string()))?;
+        convert_between(value, from, to)
+    }
+
+    /// Parse a value with its unit, such as `5 km`, `5km`, `1e3 m` or `3 nautical mile`
+    pub fn parse_quantity(&self, input: &str) -> Result<Quantity<'_>, ConversionError> {
+        let input = input.trim();
+        let invalid = || ConversionError::InvalidExpression(input.to_string());
+        let (number, unit) = input.split_at(number_length(input));
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        let unit = unit.trim();
+        if unit.is_empty() {
+            return Err(invalid());
+        }
+        let unit = self
+            .find(unit)
+            .ok_or_else(|| ConversionError::UnknownUnit(unit.to_string()))?;
+        Ok(Quantity { value, unit })
+    }
+
+    /// Parse and perform a conversion such as `5 km to mi`, `3 in in cm` or `1 kg -> lb`
+    ///
+    /// The last `to`, `in` or `->` separates the two units, so inches can be converted too.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The expression, with `.` as the decimal separator
+    ///
+    /// # Returns
+    ///
+    /// * The source quantity and the converted one, or why the conversion makes no sense
+    pub fn parse_conversion(
+        &self,
+        input: &str,
+    ) -> Result<(Quantity<'_>, Quantity<'_>), ConversionError> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let separator = words
+            .iter()
+            .rposition(|word| matches!(word.to_lowercase().as_str(), "to" | "in" | "->"))
+            .filter(|index| *index > 0 && *index + 1 < words.len())
+            .ok_or_else(|| ConversionError::InvalidExpression(input.trim().to_string()))?;
+        let from = self.parse_quantity(&words[..separator].join(" "))?;
+        let target = words[separator + 1..].join(" ");
+        let to = self
+            .find(&target)
+            .ok_or(ConversionError::UnknownUnit(target))?;
+        Ok((from, from.convert_to(to)?))
+    }
+}
+
+/// Convert a value between two units that are already looked up
+///
+/// # Arguments
+///
+/// * `value` - The value to convert, in `from`
+/// * `from` - The unit of `value`
+/// * `to` - The unit to convert into
+///
+/// # Returns
+///
+/// * The converted value, or why the conversion makes no sense, e.g. a result too large
+///   for an `f64`
+pub fn convert_between(value: f64, from: &Unit, to: &Unit) -> Result<f64, ConversionError> {
+    if from.dimension != to.dimension {
+        return Err(ConversionError::IncompatibleDimensions {
+            from: from.dimension,
+            to: to.dimension,
+        });
+    }
+    if !value.is_finite() {
+        return Err(ConversionError::NotFinite(value));
+    }
+    let base = from.to_base(value);
+    // Same tolerance as `TemperatureUnit::validate`, so rounding at 0 K is not an error
+    if let Some(minimum) = from.dimension.minimum()
+        && base < minimum - 1e-9
+    {
+        return Err(ConversionError::BelowMinimum {
+            value,
+            symbol: from.symbol.clone(),
+        });
+    }
+    let converted = to.base_to_unit(base);
+    if !converted.is_finite() {
+        return Err(ConversionError::NotFinite(converted));
+    }
+    Ok(converted)
+}
+
+/// Returns the length of the number at the start of `input`, e.g. 3 in `1e3m`
+///
+/// An `e` only starts an exponent when digits follow it, so `5em` leaves `em` as the unit.
+fn number_length(input: &str) -> usize {
+    let bytes = input.as_bytes();
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    end = digits(end);
+    if bytes.get(end) == Some(&b'.') {
+        end = digits(end + 1);
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent_end = digits(end + 1 + sign);
+        if exponent_end > end + 1 + sign {
+            end = exponent_end;
+        }
+    }
+    end
+}
+
+/// The units shipped with the converter
+fn builtin_units() -> Vec<Unit> {
+    // Temperature scales are derived from their Kelvin formulas, which are all linear.
+    // They print with the converter's symbols such as `°C`; codes such as `C` are aliases
+    let mut units: Vec<Unit> = ConversionUnit::ALL
+        .iter()
+        .map(|scale| {
+            let offset = scale.scale_to_kelvin(0.0);
+            let factor = scale.scale_to_kelvin(1.0) - offset;
+            let aliases: Vec<&str> = scale.aliases().to_vec();
+            Unit::new(scale.symbol(), scale.name(), Dimension::Temperature, factor)
+                .with_offset(offset)
+                .with_aliases(&aliases)
+        })
+        .collect();
+
+    units.extend([
+        Unit::new("m", "metre", Dimension::Length, 1.0)
+            .with_aliases(&["meter", "metres", "meters"]),
+        Unit::new("km", "kilometre", Dimension::Length, 1000.0).with_aliases(&["kilometer"]),
+        Unit::new("cm", "centimetre", Dimension::Length, 0.01).with_aliases(&["centimeter"]),
+        Unit::new("mm", "millimetre", Dimension::Length, 0.001).with_aliases(&["millimeter"]),
+        Unit::new("mi", "mile", Dimension::Length, 1609.344).with_aliases(&["miles"]),
+        Unit::new("yd", "yard", Dimension::Length, 0.9144).with_aliases(&["yards"]),
+        Unit::new("ft", "foot", Dimension::Length, 0.3048).with_aliases(&["feet"]),
+        Unit::new("in", "inch", Dimension::Length, 0.0254).with_aliases(&["inches"]),
+        Unit::new("nmi", "nautical mile", Dimension::Length, 1852.0),
+        Unit::new("kg", "kilogram", Dimension::Mass, 1.0).with_aliases(&["kilograms"]),
+        Unit::new("g", "gram", Dimension::Mass, 0.001).with_aliases(&["grams"]),
+        Unit::new("mg", "milligram", Dimension::Mass, 1e-6).with_aliases(&["milligrams"]),
+        Unit::new("t", "tonne", Dimension::Mass, 1000.0).with_aliases(&["tonnes"]),
+        Unit::new("lb", "pound", Dimension::Mass, 0.453_592_37).with_aliases(&["lbs", "pounds"]),
+        Unit::new("oz", "ounce", Dimension::Mass, 0.028_349_523_125).with_aliases(&["ounces"]),
+        Unit::new("st", "stone", Dimension::Mass, 6.350_293_18),
+        Unit::new("L", "litre", Dimension::Volume, 1.0).with_aliases(&["liter", "litres"]),
+        Unit::new("mL", "millilitre", Dimension::Volume, 0.001).with_aliases(&["milliliter"]),
+        Unit::new("m3", "cubic metre", Dimension::Volume, 1000.0).with_aliases(&["m³"]),
+        Unit::new("gal", "US gallon", Dimension::Volume, 3.785_411_784).with_aliases(&["gallon"]),
+        Unit::new("qt", "US quart", Dimension::Volume, 0.946_352_946).with_aliases(&["quart"]),
+        Unit::new("pt", "US pint", Dimension::Volume, 0.473_176_473).with_aliases(&["pint"]),
+        Unit::new("cup", "US cup", Dimension::Volume, 0.236_588_236_5).with_aliases(&["cups"]),
+        Unit::new(
+            "floz",
+            "US fluid ounce",
+            Dimension::Volume,
+            0.029_573_529_562_5,
+        ),
+        Unit::new("m/s", "metre per second", Dimension::Speed, 1.0).with_aliases(&["mps"]),
+        Unit::new("km/h", "kilometre per hour", Dimension::Speed, 1.0 / 3.6)
+            .with_aliases(&["kph", "kmh"]),
+        Unit::new("mph", "mile per hour", Dimension::Speed, 0.447_04),
+        Unit::new("kn", "knot", Dimension::Speed, 1852.0 / 3600.0).with_aliases(&["knots"]),
+        Unit::new("ft/s", "foot per second", Dimension::Speed, 0.3048).with_aliases(&["fps"]),
+    ]);
+    units
+}
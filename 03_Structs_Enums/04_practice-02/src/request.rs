@@ -0,0 +1,77 @@
+//! Module with the `ConversionRequest` struct.
+//! A request groups the source temperature and the target unit in a single value.
+
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Struct to represent a conversion request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConversionRequest {
+    from: TemperatureUnit,
+    to: ConversionUnit,
+}
+
+/// Implement methods for ConversionRequest
+impl ConversionRequest {
+    /// Constructor for ConversionRequest
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The temperature to convert
+    /// * `to` - The target unit
+    ///
+    /// The temperature is not checked; use `try_new` for values typed by users.
+    pub fn new(from: TemperatureUnit, to: ConversionUnit) -> Self {
+        ConversionRequest { from, to }
+    }
+
+    /// Validated constructor for ConversionRequest
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The temperature to convert
+    /// * `to` - The target unit
+    ///
+    /// # Returns
+    ///
+    /// * The request, or an error if `from` is not a possible temperature
+    pub fn try_new(from: TemperatureUnit, to: ConversionUnit) -> Result<Self, TemperatureError> {
+        from.validate()?;
+        Ok(ConversionRequest::new(from, to))
+    }
+
+    /// Returns the source temperature
+    pub fn from(&self) -> TemperatureUnit {
+        self.from
+    }
+
+    /// Returns the target unit
+    pub fn to(&self) -> ConversionUnit {
+        self.to
+    }
+
+    /// Perform the conversion and return the result
+    ///
+    /// # Returns
+    ///
+    /// * Converted temperature value as f64
+    pub fn convert(&self) -> f64 {
+        self.from.convert_to(self.to).value()
+    }
+
+    /// Perform the conversion, failing if the result does not fit in an `f64`
+    ///
+    /// # Returns
+    ///
+    /// * Converted temperature value, or `TemperatureError::TooLarge` if it is not finite
+    pub fn try_convert(&self) -> Result<f64, TemperatureError> {
+        let result = self.convert();
+        if !result.is_finite() {
+            return Err(TemperatureError::TooLarge {
+                value: self.from.value(),
+                unit: self.from.unit(),
+                to: self.to,
+            });
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,374 @@
+//! Module with the local HTTP API of the converter.
+//! Two endpoints answer with JSON, every conversion going through `ConversionRequest`:
+//!
+//! ```text
+//! GET  /convert?value=100&from=C&to=F
+//!      {"from":{"value":100,"unit":"C"},"to":{"value":212,"unit":"F"}}
+//! POST /convert/batch   {"from":"C","to":"F","values":[0,37,100]}
+//!      {"results":[{"from":...,"to":...},...]}
+//! ```
+//!
+//! Unknown units, missing or non-finite values, temperatures below absolute zero and
+//! results too large for an `f64` are answered with `400 Bad Request` and `{"error":"..."}`; a batch also says which `index`
+//! was rejected. Values keep every digit, so clients round them as they like.
+//!
+//! Slow or oversized requests cannot tie the server up: a client gets `READ_TIMEOUT` to
+//! send its request, the request line and headers are capped at `MAX_HEAD` bytes, and at
+//! most `MAX_CONNECTIONS` are answered at once; anything past that gets `503`.
+//!
+//! ```
+//! use temperature::server;
+//!
+//! let response = server::route("GET", "/convert?value=100&from=C&to=F", "");
+//! assert_eq!(response.status, 200);
+//! assert_eq!(
+//!     response.body,
+//!     r#"{"from":{"value":100,"unit":"C"},"to":{"value":212,"unit":"F"}}"#
+//! );
+//! assert_eq!(server::route("GET", "/convert?value=-500&from=C&to=F", "").status, 400);
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::json::{self, JsonValue};
+use crate::output::json_temperature;
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureUnit};
+
+/// Largest request body accepted, in bytes
+pub const MAX_BODY: usize = 1024 * 1024;
+
+/// Largest request line plus headers accepted, in bytes
+pub const MAX_HEAD: u64 = 16 * 1024;
+
+/// How long a client may stay silent while sending its request or reading the answer
+pub const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections answered at the same time, each on its own thread
+pub const MAX_CONNECTIONS: usize = 64;
+
+/// Pause after a failed `accept`, e.g. when the process is out of file descriptors
+const ACCEPT_RETRY: Duration = Duration::from_millis(100);
+
+/// Struct to represent an HTTP response with a JSON body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+    /// Methods listed in the `Allow` header of a `405` response
+    pub allow: Option<&'static str>,
+}
+
+impl Response {
+    /// Constructor for a response with a JSON body
+    pub fn json(status: u16, body: String) -> Self {
+        Response {
+            status,
+            body,
+            allow: None,
+        }
+    }
+
+    /// Constructor for an error response, `{"error":"..."}`
+    pub fn error(status: u16, message: &str) -> Self {
+        Response::json(
+            status,
+            format!(r#"{{"error":"{}"}}"#, json::escape(message)),
+        )
+    }
+
+    /// Returns the reason phrase of the status code
+    pub fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            414 => "URI Too Long",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    /// Write the status line, the headers and the body
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "HTTP/1.1 {} {}\r\n", self.status, self.reason())?;
+        write!(writer, "Content-Type: application/json\r\n")?;
+        write!(writer, "Content-Length: {}\r\n", self.body.len())?;
+        if let Some(allow) = self.allow {
+            write!(writer, "Allow: {allow}\r\n")?;
+        }
+        write!(writer, "Connection: close\r\n\r\n{}", self.body)?;
+        writer.flush()
+    }
+}
+
+/// Answer one request
+///
+/// # Arguments
+///
+/// * `method` - The HTTP method, e.g. `GET`
+/// * `target` - The path with its query string, e.g. `/convert?value=1&from=C&to=F`
+/// * `body` - The request body, empty for `GET`
+///
+/// # Returns
+///
+/// * The response to send back
+pub fn route(method: &str, target: &str, body: &str) -> Response {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    match (method, path) {
+        ("GET", "/convert") => convert(query),
+        ("POST", "/convert/batch") => convert_batch(body),
+        (_, "/convert") => not_allowed("GET"),
+        (_, "/convert/batch") => not_allowed("POST"),
+        _ => Response::error(404, &format!("no endpoint at `{path}`")),
+    }
+}
+
+/// Read one request from a connection, answer it and close
+///
+/// Only the request line, `Content-Length` and the body are looked at.
+/// A read that times out is answered with `408 Request Timeout`.
+pub fn handle_connection<S: Read + Write>(mut stream: S) -> io::Result<()> {
+    let response = {
+        let mut reader = BufReader::new(&mut stream);
+        match read_request(&mut reader) {
+            Ok(Ok((method, target, body))) => route(&method, &target, &body),
+            Ok(Err(response)) => response,
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Response::error(408, "the request was not sent in time")
+            }
+            Err(error) => return Err(error),
+        }
+    };
+    response.write_to(&mut stream)
+}
+
+/// Answer the connections of a listener, each on its own thread, for as long as it is open
+///
+/// A failed `accept` is logged to stderr and the next connection is waited for.
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("warning: cannot accept a connection: {error}");
+                thread::sleep(ACCEPT_RETRY);
+                continue;
+            }
+        };
+        if let Err(error) = set_timeouts(&stream) {
+            eprintln!("warning: cannot set the timeouts of a connection: {error}");
+            continue;
+        }
+        if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            open.fetch_sub(1, Ordering::SeqCst);
+            let busy = Response::error(503, "too many connections, try again later");
+            let _ = busy.write_to(&stream);
+            continue;
+        }
+        let open = Arc::clone(&open);
+        thread::spawn(move || {
+            // A client that hangs up early only loses its own answer
+            let _ = handle_connection(stream);
+            open.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    Ok(())
+}
+
+/// Keep a silent client from holding its thread forever
+fn set_timeouts(stream: &TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(READ_TIMEOUT))
+}
+
+/// Read the request line, the headers and the body
+///
+/// # Returns
+///
+/// * `(method, target, body)`, or the error response for a malformed request
+fn read_request<R: BufRead>(
+    reader: &mut R,
+) -> io::Result<Result<(String, String, String), Response>> {
+    // Every line of the head comes out of the same budget, so no line can grow forever
+    let mut head = reader.take(MAX_HEAD);
+    let mut line = String::new();
+    if !read_head_line(&mut head, &mut line)? {
+        return Ok(Err(Response::error(414, "the request line is too long")));
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Ok(Err(Response::error(400, "malformed request line")));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if !read_head_line(&mut head, &mut header)? {
+            return Ok(Err(Response::error(431, "the headers are too large")));
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            let Ok(length) = value.trim().parse::<usize>() else {
+                return Ok(Err(Response::error(400, "invalid Content-Length")));
+            };
+            content_length = length;
+        }
+    }
+    if content_length > MAX_BODY {
+        return Ok(Err(Response::error(413, "the body is larger than 1 MiB")));
+    }
+    let mut body = vec![0; content_length];
+    head.into_inner().read_exact(&mut body)?;
+    match String::from_utf8(body) {
+        Ok(body) => Ok(Ok((method, target, body))),
+        Err(_) => Ok(Err(Response::error(400, "the body is not valid UTF-8"))),
+    }
+}
+
+/// Read one line of the request line and headers
+///
+/// # Returns
+///
+/// * `false` if the line was cut off because the head ran out of bytes
+fn read_head_line<R: BufRead>(head: &mut io::Take<R>, line: &mut String) -> io::Result<bool> {
+    head.read_line(line)?;
+    Ok(line.ends_with('\n') || head.limit() > 0)
+}
+
+/// `405 Method Not Allowed` for an endpoint that only answers `allow`
+fn not_allowed(allow: &'static str) -> Response {
+    Response {
+        allow: Some(allow),
+        ..Response::error(405, &format!("use {allow} for this endpoint"))
+    }
+}
+
+/// `GET /convert?value=..&from=..&to=..`
+fn convert(query: &str) -> Response {
+    match convert_query(query) {
+        Ok(body) => Response::json(200, body),
+        Err(message) => Response::error(400, &message),
+    }
+}
+
+/// Read the parameters of `GET /convert` and convert them
+fn convert_query(query: &str) -> Result<String, String> {
+    let parameter = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| percent_decode(key) == name)
+            .map(|(_, value)| percent_decode(value))
+            .ok_or_else(|| format!("missing the `{name}` parameter"))
+    };
+    let value = parameter("value")?;
+    let value: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{value}` is not a number"))?;
+    conversion_json(value, &parameter("from")?, &parameter("to")?)
+}
+
+/// `POST /convert/batch` with `{"from":..,"to":..,"values":[..]}`
+fn convert_batch(body: &str) -> Response {
+    let document = match json::parse(body) {
+        Ok(document) => document,
+        Err(error) => return Response::error(400, &error.to_string()),
+    };
+    let text_field = |name: &str| {
+        document
+            .get(name)
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| format!("`{name}` must be a unit name"))
+    };
+    let (from, to) = match (text_field("from"), text_field("to")) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(message), _) | (_, Err(message)) => return Response::error(400, &message),
+    };
+    let Some(values) = document.get("values").and_then(JsonValue::as_array) else {
+        return Response::error(400, "`values` must be an array of numbers");
+    };
+    let mut results = Vec::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        let converted = value
+            .as_f64()
+            .ok_or_else(|| "not a number".to_string())
+            .and_then(|value| conversion_json(value, from, to));
+        match converted {
+            Ok(result) => results.push(result),
+            Err(message) => {
+                return Response::json(
+                    400,
+                    format!(
+                        r#"{{"error":"{}","index":{index}}}"#,
+                        json::escape(&message)
+                    ),
+                );
+            }
+        }
+    }
+    Response::json(200, format!(r#"{{"results":[{}]}}"#, results.join(",")))
+}
+
+/// Validate and perform one conversion, formatted as the API's JSON object
+fn conversion_json(value: f64, from: &str, to: &str) -> Result<String, String> {
+    let from: ConversionUnit = from.parse().map_err(|error| format!("{error}"))?;
+    let to: ConversionUnit = to.parse().map_err(|error| format!("{error}"))?;
+    let temperature = TemperatureUnit::try_new(from, value).map_err(|error| error.to_string())?;
+    let request = ConversionRequest::try_new(temperature, to).map_err(|error| error.to_string())?;
+    let result = request.try_convert().map_err(|error| error.to_string())?;
+    Ok(format!(
+        r#"{{"from":{},"to":{}}}"#,
+        json_temperature(temperature, None),
+        json_temperature(TemperatureUnit::new(to, result), None)
+    ))
+}
+
+/// Decode `+` and `%XX` escapes of a query string component
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = text
+                    .get(index + 1..index + 3)
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()));
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        index += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,674 @@
+//! Module with the interactive menu session.
+//! The session reads from any `BufRead` and writes to any `Write`, so the binary hands it
+//! stdin and stdout while tests hand it scripted input and read back the transcript.
+//!
+//! ```
+//! use temperature::session::Session;
+//!
+//! let mut session = Session::new("0\n".as_bytes(), Vec::new());
+//! session.run().unwrap();
+//! let transcript = String::from_utf8(session.into_output()).unwrap();
+//! assert!(transcript.ends_with("Exiting the program. Goodbye!\n"));
+//! ```
+//!
+//! Besides the menu numbers and expressions, the prompt understands `history`,
+//! `!N` to repeat entry N, and `swap` (or `swap N`) to convert a result back.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::cli::DEFAULT_PRECISION;
+use crate::expression;
+use crate::history::History;
+use crate::locale::{Locale, Message};
+use crate::output::{self, OutputFormat};
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureUnit};
+use crate::weather::{self, Calculator};
+
+/// Escape sequence that clears the terminal and moves the cursor to the top left corner
+pub const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+/// Enum to represent different options in the menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuOptions {
+    Convert(ConversionUnit, ConversionUnit),
+    /// A full expression typed at the prompt, e.g. `98.6F in C`
+    Expression(ConversionRequest),
+    /// List the conversions of the session
+    History,
+    /// Run history entry N again, typed as `!N`
+    Repeat(usize),
+    /// Convert the result of an entry back, the last one if no number is given
+    Swap(Option<usize>),
+    /// One of the weather calculators listed after the scales
+    Weather(Calculator),
+    Exit,
+}
+
+/// Implement methods for MenuOptions
+impl MenuOptions {
+    /// Parse a u32 into one of the scales listed in the menu
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A u32 representing the menu entry, starting at 1
+    ///
+    /// # Returns
+    ///
+    /// * The matching ConversionUnit, or `None` if the entry does not exist
+    pub fn scale_from_u32(input: u32) -> Option<ConversionUnit> {
+        let index = usize::try_from(input).ok()?.checked_sub(1)?;
+        ConversionUnit::ALL.get(index).copied()
+    }
+
+    /// Parse a u32 into one of the weather calculators, numbered after the scales
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A u32 representing the menu entry, starting at 1
+    ///
+    /// # Returns
+    ///
+    /// * The matching Calculator, or `None` if the entry is a scale or does not exist
+    pub fn calculator_from_u32(input: u32) -> Option<Calculator> {
+        let index = usize::try_from(input)
+            .ok()?
+            .checked_sub(ConversionUnit::ALL.len() + 1)?;
+        Calculator::ALL.get(index).copied()
+    }
+
+    /// Returns the source and target units for a conversion option
+    ///
+    /// # Returns
+    ///
+    /// * A `(from, to)` pair, or `None` for the Exit option
+    pub fn units(&self) -> Option<(ConversionUnit, ConversionUnit)> {
+        match self {
+            MenuOptions::Convert(from, to) => Some((*from, *to)),
+            MenuOptions::Expression(request) => Some((request.from().unit(), request.to())),
+            MenuOptions::History
+            | MenuOptions::Repeat(_)
+            | MenuOptions::Swap(_)
+            | MenuOptions::Weather(_)
+            | MenuOptions::Exit => None,
+        }
+    }
+
+    /// Parse the history commands: `history`, `!N`, `swap` and `swap N`
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The trimmed line typed at the prompt
+    /// * `locale` - Language of the error message
+    ///
+    /// # Returns
+    ///
+    /// * `None` if the input is not a history command, otherwise the option or
+    ///   the message explaining the mistake
+    pub fn parse_command(input: &str, locale: Locale) -> Option<Result<Self, String>> {
+        let mut words = input.split_whitespace();
+        let command = words.next()?.to_lowercase();
+        let argument = words.next();
+        if words.next().is_some() {
+            return None;
+        }
+        let entry_number = |text: &str| {
+            text.parse::<usize>()
+                .map_err(|_| locale.message(Message::NotEntryNumber(text)))
+        };
+        match (command.as_str(), argument) {
+            ("history", None) => Some(Ok(MenuOptions::History)),
+            ("swap", None) => Some(Ok(MenuOptions::Swap(None))),
+            ("swap", Some(number)) => {
+                Some(entry_number(number).map(|n| MenuOptions::Swap(Some(n))))
+            }
+            (repeat, None) if repeat.starts_with('!') => {
+                Some(entry_number(&repeat[1..]).map(MenuOptions::Repeat))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Enum to represent the failures that end a session early
+#[derive(Debug)]
+pub enum SessionError {
+    /// Reading the user's answers failed, e.g. the input is not valid UTF-8
+    Input(io::Error),
+    /// Writing the menu or the results failed, e.g. a closed pipe
+    Output(io::Error),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::Input(error) => write!(f, "cannot read the input: {error}"),
+            SessionError::Output(error) => write!(f, "cannot write the output: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SessionError::Input(error) | SessionError::Output(error) => Some(error),
+        }
+    }
+}
+
+/// Plain I/O errors come from writing; reads are wrapped in `Input` where they happen
+impl From<io::Error> for SessionError {
+    fn from(error: io::Error) -> Self {
+        SessionError::Output(error)
+    }
+}
+
+/// Struct to represent an interactive menu session over a reader and a writer
+pub struct Session<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    /// Where the menu goes in machine formats; `None` leaves it out
+    menu_output: Option<Box<dyn Write>>,
+    format: OutputFormat,
+    history: History,
+    locale: Locale,
+}
+
+/// Implement methods for Session
+impl<R: BufRead, W: Write> Session<R, W> {
+    /// Constructor for Session, printing results as text
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the user's answers are read from
+    /// * `output` - Where the menu and the results are written
+    pub fn new(input: R, output: W) -> Self {
+        Session {
+            input,
+            output,
+            menu_output: None,
+            format: OutputFormat::Text,
+            history: History::default(),
+            locale: Locale::En,
+        }
+    }
+
+    /// Print the results in another format
+    ///
+    /// In machine formats only the results are written to `output`, one JSON object
+    /// per line, and the screen is never cleared.
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Send the menu and prompts to a separate writer in machine formats, e.g. stderr
+    pub fn with_menu_output(mut self, menu_output: Box<dyn Write>) -> Self {
+        self.menu_output = Some(menu_output);
+        self
+    }
+
+    /// Show the menu in another language, with its decimal separator
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Start from the history of an earlier session
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Returns the conversions shown so far, including the ones given by `with_history`
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Consume the session and return its writer, e.g. to inspect a transcript
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Runs the interactive menu until the user chooses Exit or the input ends
+    ///
+    /// The end of the input (Ctrl-D, or a closed pipe) exits like the Exit option.
+    ///
+    /// # Returns
+    ///
+    /// * An error if reading the input or writing the output failed
+    pub fn run(&mut self) -> Result<(), SessionError> {
+        // Clear the console screen at the start of the program
+        self.clear_console()?;
+        // Flag to control the main loop
+        let mut program_state: Option<MenuOptions> = None;
+
+        // Main loop
+        while program_state != Some(MenuOptions::Exit) {
+            // Display the menu options
+            self.display_menu()?;
+            // Read user input
+            let choice = self.read_menu_options()?;
+
+            // Handle the selected menu option
+            match &choice {
+                // Handle temperature conversions
+                Ok(option @ MenuOptions::Convert(from, _)) => {
+                    if let Some(temperature) = self.read_temperature_value(*from)? {
+                        self.perform_conversion(option, temperature)?;
+                    }
+                }
+                // Handle expressions typed at the prompt
+                Ok(MenuOptions::Expression(request)) => self.show_result(request)?,
+                // Handle the history commands
+                Ok(MenuOptions::History) => self.show_history()?,
+                Ok(option @ (MenuOptions::Repeat(_) | MenuOptions::Swap(_))) => {
+                    match self.recall(option) {
+                        Ok(request) => self.show_result(&request)?,
+                        Err(message) => self.show_error(&message)?,
+                    }
+                }
+                // Handle the weather calculators
+                Ok(MenuOptions::Weather(calculator)) => self.run_calculator(*calculator)?,
+                // Handle exit option
+                Ok(MenuOptions::Exit) => {
+                    self.say(Message::Goodbye)?;
+                }
+                // Handle invalid menu option
+                Err(message) => self.show_error(message)?,
+            }
+            program_state = choice.ok();
+        }
+        Ok(self.output.flush()?)
+    }
+
+    /// Write one line of menu text
+    ///
+    /// In machine formats the line goes to the menu writer, so `output` only holds results.
+    fn menu_line(&mut self, line: fmt::Arguments<'_>) -> io::Result<()> {
+        if !self.format.is_machine() {
+            return writeln!(self.output, "{line}");
+        }
+        match &mut self.menu_output {
+            Some(menu_output) => writeln!(menu_output, "{line}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Write one line of the message catalog in the session's language
+    fn say(&mut self, message: Message<'_>) -> io::Result<()> {
+        let text = self.locale.message(message);
+        self.menu_line(format_args!("{text}"))
+    }
+
+    /// Clear the screen and explain what went wrong
+    fn show_error(&mut self, message: &str) -> io::Result<()> {
+        self.clear_console()?;
+        self.menu_line(format_args!("{message}"))
+    }
+
+    /// Function to clear the console screen
+    ///
+    /// Nothing is cleared in machine formats, where the output may be a pipe.
+    fn clear_console(&mut self) -> io::Result<()> {
+        if self.format.is_machine() {
+            return Ok(());
+        }
+        write!(self.output, "{CLEAR_SCREEN}")?;
+        self.output.flush()
+    }
+
+    /// Function to read one line of user input
+    ///
+    /// # Returns
+    ///
+    /// * The line without surrounding whitespace, or `None` once the input has ended
+    fn read_input(&mut self) -> Result<Option<String>, SessionError> {
+        let mut input = String::new();
+        loop {
+            match self.input.read_line(&mut input) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(input.trim().to_string())),
+                // A signal arrived while waiting, so just wait again
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(SessionError::Input(error)),
+            }
+        }
+    }
+
+    /// Function to display the menu options to the user
+    ///
+    /// The scale entries are generated from `ConversionUnit::ALL`,
+    /// so new scales show up here without touching the menu.
+    fn display_menu(&mut self) -> io::Result<()> {
+        self.say(Message::MenuTitle)?;
+        self.say(Message::ConvertFrom)?;
+        self.display_scales()?;
+        self.say(Message::WeatherMenu)?;
+        self.display_calculators()?;
+        self.say(Message::Exit)?;
+        self.say(Message::ExpressionHint)?;
+        self.say(Message::HistoryHint)?;
+        self.menu_line(format_args!("==================================="))
+    }
+
+    /// Function to display one numbered entry per supported scale
+    fn display_scales(&mut self) -> io::Result<()> {
+        for (index, unit) in ConversionUnit::ALL.iter().enumerate() {
+            self.menu_line(format_args!(
+                "{}. {} ({})",
+                index + 1,
+                unit.name(),
+                unit.symbol()
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Function to display the weather calculators, numbered after the scales
+    fn display_calculators(&mut self) -> io::Result<()> {
+        let first = ConversionUnit::ALL.len() + 1;
+        for (index, calculator) in Calculator::ALL.iter().enumerate() {
+            let name = self.locale.message(Message::Calculator(*calculator));
+            self.menu_line(format_args!("{}. {name}", first + index))?;
+        }
+        Ok(())
+    }
+
+    /// Read user input and return the selected menu option
+    ///
+    /// The user first picks the source scale and then the target scale,
+    /// or types a whole expression such as `300 kelvin in fahrenheit`.
+    ///
+    /// # Returns
+    ///
+    /// * Selected menu option as `MenuOptions` enum, or the message explaining the mistake.
+    ///   The end of the input counts as Exit.
+    fn read_menu_options(&mut self) -> Result<Result<MenuOptions, String>, SessionError> {
+        self.say(Message::EnterChoice)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(Ok(MenuOptions::Exit));
+        };
+        if let Some(command) = MenuOptions::parse_command(&input, self.locale) {
+            return Ok(command);
+        }
+        let from = match input.parse::<u32>() {
+            Ok(0) => return Ok(Ok(MenuOptions::Exit)),
+            Ok(num) => {
+                if let Some(calculator) = MenuOptions::calculator_from_u32(num) {
+                    return Ok(Ok(MenuOptions::Weather(calculator)));
+                }
+                MenuOptions::scale_from_u32(num)
+            }
+            // Not a menu number, so it may be an expression
+            Err(_) => {
+                let normalized = self.locale.normalize_decimals(&input);
+                return Ok(expression::parse_expression(&normalized)
+                    .map(MenuOptions::Expression)
+                    .map_err(|error| {
+                        let input = input.as_str();
+                        self.locale.message(Message::Expression {
+                            input,
+                            error: &error,
+                        })
+                    }));
+            }
+        };
+        let Some(from) = from else {
+            return Ok(Err(self.locale.message(Message::InvalidChoice)));
+        };
+        self.say(Message::ConvertTo(from))?;
+        self.display_scales()?;
+        self.say(Message::EnterChoice)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(Ok(MenuOptions::Exit));
+        };
+        match input
+            .parse::<u32>()
+            .ok()
+            .and_then(MenuOptions::scale_from_u32)
+        {
+            Some(to) => Ok(Ok(MenuOptions::Convert(from, to))),
+            None => Ok(Err(self.locale.message(Message::InvalidChoice))),
+        }
+    }
+
+    /// Read temperature value from user input
+    ///
+    /// A bare number uses the given unit, while a value with its own unit
+    /// such as `25c` keeps that unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_unit` - The unit of a bare number
+    ///
+    /// # Returns
+    ///
+    /// * A TemperatureUnit enum with the input value, or `None` if it was invalid or missing
+    fn read_temperature_value(
+        &mut self,
+        from_unit: ConversionUnit,
+    ) -> Result<Option<TemperatureUnit>, SessionError> {
+        self.say(Message::EnterValue)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(None);
+        };
+        let locale = self.locale;
+        let parsed = match locale.parse_number(&input) {
+            Some(value) => TemperatureUnit::try_new(from_unit, value)
+                .map_err(|error| locale.message(Message::Temperature(&error))),
+            None => {
+                expression::parse_temperature(&locale.normalize_decimals(&input)).map_err(|error| {
+                    let input = input.as_str();
+                    locale.message(Message::Expression {
+                        input,
+                        error: &error,
+                    })
+                })
+            }
+        };
+        match parsed {
+            Ok(temperature) => Ok(Some(temperature)),
+            Err(message) => {
+                self.say(Message::InvalidValue)?;
+                self.menu_line(format_args!("{message}"))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Read a plain number such as a humidity or a wind speed
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The question asked for the number
+    ///
+    /// # Returns
+    ///
+    /// * The number, or `None` if it was invalid or missing
+    fn read_number(&mut self, prompt: Message<'_>) -> Result<Option<f64>, SessionError> {
+        self.say(prompt)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(None);
+        };
+        let number = self.locale.parse_number(&input);
+        if number.is_none() {
+            self.say(Message::InvalidQuantity)?;
+        }
+        Ok(number)
+    }
+
+    /// Function to ask for the inputs of a weather calculator and show its result
+    ///
+    /// The user picks the scale first: bare temperatures are read in it and the
+    /// result is shown in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `calculator` - The calculator picked in the menu
+    fn run_calculator(&mut self, calculator: Calculator) -> Result<(), SessionError> {
+        self.say(Message::CalculateIn(calculator))?;
+        self.display_scales()?;
+        self.say(Message::EnterChoice)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(());
+        };
+        let Some(unit) = input
+            .parse::<u32>()
+            .ok()
+            .and_then(MenuOptions::scale_from_u32)
+        else {
+            let message = self.locale.message(Message::InvalidChoice);
+            return Ok(self.show_error(&message)?);
+        };
+        let Some(temperature) = self.read_temperature_value(unit)? else {
+            return Ok(());
+        };
+        // Each calculator asks for what its formula needs, humidity before wind
+        let result = match calculator {
+            Calculator::HeatIndex => {
+                let Some(humidity) = self.read_number(Message::EnterHumidity)? else {
+                    return Ok(());
+                };
+                weather::heat_index(temperature, humidity, unit)
+            }
+            Calculator::WindChill => {
+                let Some(wind_speed) = self.read_number(Message::EnterWindSpeed)? else {
+                    return Ok(());
+                };
+                weather::wind_chill(temperature, wind_speed, unit)
+            }
+            Calculator::DewPoint => {
+                let Some(humidity) = self.read_number(Message::EnterHumidity)? else {
+                    return Ok(());
+                };
+                weather::dew_point(temperature, humidity, unit)
+            }
+            Calculator::ApparentTemperature => {
+                let Some(humidity) = self.read_number(Message::EnterHumidity)? else {
+                    return Ok(());
+                };
+                let Some(wind_speed) = self.read_number(Message::EnterWindSpeed)? else {
+                    return Ok(());
+                };
+                weather::apparent_temperature(temperature, humidity, wind_speed, unit)
+            }
+        };
+        match result {
+            Ok(result) => self.show_weather(calculator, temperature, result),
+            Err(error) => {
+                let message = self.locale.message(Message::Weather(&error));
+                Ok(self.menu_line(format_args!("{message}"))?)
+            }
+        }
+    }
+
+    /// Function to print the result of a weather calculator in a banner
+    ///
+    /// Results are not conversions, so they stay out of the history.
+    fn show_weather(
+        &mut self,
+        calculator: Calculator,
+        from: TemperatureUnit,
+        result: TemperatureUnit,
+    ) -> Result<(), SessionError> {
+        if self.format.is_machine() {
+            let json = output::format_weather_json(calculator, from, result, DEFAULT_PRECISION);
+            writeln!(self.output, "{json}")?;
+            return Ok(self.output.flush()?);
+        }
+        self.clear_console()?;
+        let name = self.locale.message(Message::Calculator(calculator));
+        let text = self
+            .locale
+            .format_weather(calculator, from, result, DEFAULT_PRECISION);
+        writeln!(self.output, "====== {name} ======\n{text}")?;
+        Ok(writeln!(self.output, "===============================")?)
+    }
+
+    /// Function to perform conversion based on the menu option
+    ///
+    /// # Arguments
+    ///
+    /// * `option` - The menu option that gives the target unit
+    /// * `temperature` - A TemperatureUnit enum representing the input temperature
+    fn perform_conversion(
+        &mut self,
+        option: &MenuOptions,
+        temperature: TemperatureUnit,
+    ) -> Result<(), SessionError> {
+        let Some((_, to_unit)) = option.units() else {
+            return Ok(());
+        };
+        self.show_result(&ConversionRequest::new(temperature, to_unit))
+    }
+
+    /// Find the conversion asked for by `!N` or `swap`
+    ///
+    /// # Returns
+    ///
+    /// * The request to run, or the message explaining why there is none
+    fn recall(&self, option: &MenuOptions) -> Result<ConversionRequest, String> {
+        let entry = match option {
+            MenuOptions::Repeat(number) | MenuOptions::Swap(Some(number)) => self
+                .history
+                .get(*number)
+                .ok_or_else(|| self.locale.message(Message::NoHistoryEntry(*number)))?,
+            _ => self
+                .history
+                .last()
+                .ok_or_else(|| self.locale.message(Message::HistoryEmpty))?,
+        };
+        Ok(match option {
+            MenuOptions::Swap(_) => entry.inverse(),
+            _ => entry.request(),
+        })
+    }
+
+    /// Function to list the conversions of the session, oldest first
+    fn show_history(&mut self) -> io::Result<()> {
+        self.clear_console()?;
+        self.say(Message::HistoryTitle)?;
+        if self.history.is_empty() {
+            self.say(Message::NoConversions)?;
+        }
+        let lines: Vec<String> = self
+            .history
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let text = self
+                    .locale
+                    .format_conversion(&entry.request(), DEFAULT_PRECISION);
+                format!("{}. {text}", index + 1)
+            })
+            .collect();
+        for line in lines {
+            self.menu_line(format_args!("{line}"))?;
+        }
+        self.menu_line(format_args!("================================"))
+    }
+
+    /// Function to print the result of a conversion in a banner
+    ///
+    /// In machine formats the banner is replaced by one JSON object per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The conversion to perform and print
+    fn show_result(&mut self, request: &ConversionRequest) -> Result<(), SessionError> {
+        self.history.push(*request);
+        if self.format.is_machine() {
+            let json = output::format_json(request, DEFAULT_PRECISION);
+            writeln!(self.output, "{json}")?;
+            return Ok(self.output.flush()?);
+        }
+        self.clear_console()?;
+        let title = self.locale.message(Message::ResultTitle);
+        let text = self.locale.format_conversion(request, DEFAULT_PRECISION);
+        writeln!(self.output, "{title}\n{text}")?;
+        Ok(writeln!(self.output, "===============================")?)
+    }
+}
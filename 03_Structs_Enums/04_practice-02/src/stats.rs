@@ -0,0 +1,255 @@
+//! Module with summary statistics of a series of temperature readings.
+//! The readings are converted into the target scale first, then summarized there.
+//! Minimum, maximum, mean and median are temperatures, while the range and the standard
+//! deviation are differences: they are `TemperatureDelta`s, so converting a summary into
+//! another scale never adds an offset to them (a spread of 10 °C is 18 °F, not 50 °F).
+//!
+//! ```
+//! use temperature::stats;
+//! use temperature::{ConversionUnit, TemperatureUnit};
+//!
+//! let readings = [20.0, 22.0, 24.0].map(TemperatureUnit::Celsius);
+//! let summary = stats::summarize(&readings, ConversionUnit::ToFahrenheit).unwrap();
+//! assert!((summary.mean.value() - 71.6).abs() < 1e-9);
+//! assert!((summary.range.value() - 7.2).abs() < 1e-9);
+//! ```
+
+use std::io::{self, BufRead};
+
+use crate::delta::TemperatureDelta;
+use crate::locale::{Locale, Message};
+use crate::output::OutputFormat;
+use crate::units::{ConversionUnit, TemperatureUnit};
+
+/// Enum to represent the lines of a summary, in the order they are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+    Count,
+    Minimum,
+    Maximum,
+    Mean,
+    Median,
+    Range,
+    StdDev,
+}
+
+impl Statistic {
+    /// Every statistic, in the order they are printed
+    pub const ALL: [Statistic; 7] = [
+        Statistic::Count,
+        Statistic::Minimum,
+        Statistic::Maximum,
+        Statistic::Mean,
+        Statistic::Median,
+        Statistic::Range,
+        Statistic::StdDev,
+    ];
+
+    /// Returns the key of this statistic in the JSON output
+    pub fn key(&self) -> &'static str {
+        match self {
+            Statistic::Count => "count",
+            Statistic::Minimum => "min",
+            Statistic::Maximum => "max",
+            Statistic::Mean => "mean",
+            Statistic::Median => "median",
+            Statistic::Range => "range",
+            Statistic::StdDev => "stddev",
+        }
+    }
+}
+
+/// Struct to represent the summary of a series of readings in one scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    /// The lowest reading in the summary's scale
+    pub min: TemperatureUnit,
+    /// The highest reading in the summary's scale
+    pub max: TemperatureUnit,
+    pub mean: TemperatureUnit,
+    pub median: TemperatureUnit,
+    /// Distance between the lowest and the highest reading
+    pub range: TemperatureDelta,
+    /// Sample standard deviation, zero for a single reading
+    pub std_dev: TemperatureDelta,
+}
+
+impl Summary {
+    /// Returns the scale of every value of the summary
+    pub fn unit(&self) -> ConversionUnit {
+        self.mean.unit()
+    }
+
+    /// Converts the summary into another scale
+    ///
+    /// Temperatures are converted with the scale offsets, the range and the standard
+    /// deviation only change the size of the degree. On Delisle, whose numbers fall as
+    /// temperatures rise, the minimum and the maximum swap places.
+    pub fn convert_to(&self, to: ConversionUnit) -> Summary {
+        let (mut min, mut max) = (self.min.convert_to(to), self.max.convert_to(to));
+        if min.value() > max.value() {
+            (min, max) = (max, min);
+        }
+        let spread =
+            |delta: TemperatureDelta| TemperatureDelta::new(to, delta.convert_to(to).value().abs());
+        Summary {
+            count: self.count,
+            min,
+            max,
+            mean: self.mean.convert_to(to),
+            median: self.median.convert_to(to),
+            range: spread(self.range),
+            std_dev: spread(self.std_dev),
+        }
+    }
+
+    /// Returns the value of one statistic, as printed
+    fn value(&self, statistic: Statistic) -> f64 {
+        match statistic {
+            Statistic::Count => self.count as f64,
+            Statistic::Minimum => self.min.value(),
+            Statistic::Maximum => self.max.value(),
+            Statistic::Mean => self.mean.value(),
+            Statistic::Median => self.median.value(),
+            Statistic::Range => self.range.value(),
+            Statistic::StdDev => self.std_dev.value(),
+        }
+    }
+}
+
+/// Summarize readings after converting them into one scale
+///
+/// # Arguments
+///
+/// * `readings` - The readings, in any scales
+/// * `to` - The scale of the summary
+///
+/// # Returns
+///
+/// * The summary, or `None` if there are no readings
+pub fn summarize(readings: &[TemperatureUnit], to: ConversionUnit) -> Option<Summary> {
+    let mut values: Vec<f64> = readings
+        .iter()
+        .map(|reading| reading.convert_to(to).value())
+        .collect();
+    values.sort_by(f64::total_cmp);
+    let (&min, &max) = (values.first()?, values.last()?);
+    let count = values.len();
+    let mean = values.iter().sum::<f64>() / count as f64;
+    let median = if count % 2 == 1 {
+        values[count / 2]
+    } else {
+        (values[count / 2 - 1] + values[count / 2]) / 2.0
+    };
+    let std_dev = if count > 1 {
+        let squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+        (squares / (count - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+    let absolute = |value| TemperatureUnit::new(to, value);
+    Some(Summary {
+        count,
+        min: absolute(min),
+        max: absolute(max),
+        mean: absolute(mean),
+        median: absolute(median),
+        range: TemperatureDelta::new(to, max - min),
+        std_dev: TemperatureDelta::new(to, std_dev),
+    })
+}
+
+/// Read the numbers of a readings file, separated by spaces or line breaks
+///
+/// Empty lines and lines starting with `#` are skipped.
+///
+/// # Arguments
+///
+/// * `reader` - The file or stdin
+/// * `locale` - Language of the decimal separator, e.g. `36,6` in Spanish
+///
+/// # Returns
+///
+/// * The numbers in order, or an `InvalidData` error naming the first word that is not one
+pub fn read_readings<R: BufRead>(reader: R, locale: Locale) -> io::Result<Vec<f64>> {
+    let mut values = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        for word in line.split_whitespace() {
+            let value = locale.parse_number(word).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: `{word}` is not a number", index + 1),
+                )
+            })?;
+            values.push(value);
+        }
+    }
+    Ok(values)
+}
+
+/// Format a summary as text, with aligned labels, or as a single JSON object
+///
+/// # Arguments
+///
+/// * `summary` - The summary to print
+/// * `format` - Text, or JSON for both JSON formats
+/// * `precision` - Decimal places of every value but the count
+/// * `locale` - Language of the text labels and decimals
+///
+/// # Returns
+///
+/// * The whole output ending with a line break
+pub fn render_summary(
+    summary: &Summary,
+    format: OutputFormat,
+    precision: usize,
+    locale: Locale,
+) -> String {
+    if format.is_machine() {
+        let fields: Vec<String> = Statistic::ALL
+            .iter()
+            .map(|statistic| match statistic {
+                Statistic::Count => format!(r#""count":{}"#, summary.count),
+                _ => format!(
+                    r#""{}":{:.precision$}"#,
+                    statistic.key(),
+                    summary.value(*statistic)
+                ),
+            })
+            .collect();
+        return format!(
+            "{{\"unit\":\"{}\",{}}}\n",
+            summary.unit().code(),
+            fields.join(",")
+        );
+    }
+    let labels: Vec<String> = Statistic::ALL
+        .iter()
+        .map(|statistic| locale.message(Message::Statistic(*statistic)) + ":")
+        .collect();
+    let width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let symbol = summary.unit().symbol();
+    Statistic::ALL
+        .iter()
+        .zip(&labels)
+        .map(|(statistic, label)| {
+            let value = match statistic {
+                Statistic::Count => summary.count.to_string(),
+                _ => format!(
+                    "{} {symbol}",
+                    locale.format_number(summary.value(*statistic), precision)
+                ),
+            };
+            format!("{label:<width$} {value}\n")
+        })
+        .collect()
+}
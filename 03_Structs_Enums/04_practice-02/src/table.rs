@@ -0,0 +1,255 @@
+//! Module with the conversion table generator.
+//! A table lists a range of temperatures in one scale next to the same temperatures in
+//! the other scales, ready to print as text, Markdown, CSV or HTML.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::batch::join_record;
+use crate::request::ConversionRequest;
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Upper bound on the number of rows, so a tiny step cannot exhaust memory
+pub const MAX_ROWS: usize = 10_000;
+
+/// Enum to represent the output formats of a table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Columns padded with spaces for terminals and printouts
+    Text,
+    Markdown,
+    Csv,
+    Html,
+}
+
+/// Parse a format name such as `md` or `html`, ignoring case
+impl FromStr for TableFormat {
+    type Err = TableError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "text" | "txt" => Ok(TableFormat::Text),
+            "markdown" | "md" => Ok(TableFormat::Markdown),
+            "csv" => Ok(TableFormat::Csv),
+            "html" => Ok(TableFormat::Html),
+            _ => Err(TableError::UnknownFormat(input.to_string())),
+        }
+    }
+}
+
+/// Struct to represent the settings of a conversion table
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableOptions {
+    pub from: ConversionUnit,
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+    /// Scales shown after the source column, every other scale by default
+    pub targets: Vec<ConversionUnit>,
+    pub precision: usize,
+    pub format: TableFormat,
+}
+
+impl TableOptions {
+    /// Constructor with every other scale as a target, two decimals and text output
+    pub fn new(from: ConversionUnit, start: f64, end: f64, step: f64) -> Self {
+        TableOptions {
+            from,
+            start,
+            end,
+            step,
+            targets: ConversionUnit::ALL
+                .into_iter()
+                .filter(|unit| *unit != from)
+                .collect(),
+            precision: 2,
+            format: TableFormat::Text,
+        }
+    }
+}
+
+/// Enum to represent the reasons a table cannot be built
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    /// The step is zero, not finite, or goes away from the end of the range
+    InvalidStep(f64),
+    /// The range would produce more than `MAX_ROWS` rows
+    TooManyRows,
+    /// The start or end of the range is not a possible temperature
+    InvalidTemperature(TemperatureError),
+    /// The format name is not one of text, markdown, csv or html
+    UnknownFormat(String),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::InvalidStep(step) => {
+                write!(f, "the step {step} does not move from the start to the end")
+            }
+            TableError::TooManyRows => write!(f, "the table would have more than {MAX_ROWS} rows"),
+            TableError::InvalidTemperature(error) => write!(f, "{error}"),
+            TableError::UnknownFormat(format) => {
+                write!(
+                    f,
+                    "unknown table format `{format}`, use text, markdown, csv or html"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<TemperatureError> for TableError {
+    fn from(error: TemperatureError) -> Self {
+        TableError::InvalidTemperature(error)
+    }
+}
+
+/// Build the rows of the table: the source value followed by one value per target
+///
+/// # Arguments
+///
+/// * `options` - Range, scales and layout of the table
+///
+/// # Returns
+///
+/// * One row per step, both ends of the range included
+pub fn table_rows(options: &TableOptions) -> Result<Vec<Vec<f64>>, TableError> {
+    TemperatureUnit::try_new(options.from, options.start)?;
+    TemperatureUnit::try_new(options.from, options.end)?;
+    let distance = options.end - options.start;
+    if !options.step.is_finite() || options.step == 0.0 || distance * options.step < 0.0 {
+        return Err(TableError::InvalidStep(options.step));
+    }
+    // Allow a little floating point slack so `0 to 1 step 0.1` still ends at 1
+    let steps = (distance / options.step + 1e-9).floor();
+    if steps >= MAX_ROWS as f64 {
+        return Err(TableError::TooManyRows);
+    }
+
+    let rows = (0..=steps as usize)
+        .map(|index| {
+            // Multiply instead of adding repeatedly so rounding errors do not pile up
+            let value = options.start + options.step * index as f64;
+            let from = TemperatureUnit::new(options.from, value);
+            let mut row = vec![value];
+            row.extend(
+                options
+                    .targets
+                    .iter()
+                    .map(|to| ConversionRequest::new(from, *to).convert()),
+            );
+            row
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// Render the table in the chosen format
+///
+/// # Arguments
+///
+/// * `options` - Range, scales and layout of the table
+///
+/// # Returns
+///
+/// * The whole table as a string ending with a line break
+pub fn render_table(options: &TableOptions) -> Result<String, TableError> {
+    let header: Vec<String> = std::iter::once(options.from)
+        .chain(options.targets.iter().copied())
+        .map(|unit| format!("{} ({})", unit.name(), unit.symbol()))
+        .collect();
+    let rows: Vec<Vec<String>> = table_rows(options)?
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|value| format!("{:.*}", options.precision, value))
+                .collect()
+        })
+        .collect();
+
+    Ok(match options.format {
+        TableFormat::Text => render_text(&header, &rows),
+        TableFormat::Markdown => render_markdown(&header, &rows),
+        TableFormat::Csv => render_csv(&header, &rows),
+        TableFormat::Html => render_html(&header, &rows),
+    })
+}
+
+/// Render columns padded to the same width, numbers aligned to the right
+fn render_text(header: &[String], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            std::iter::once(&header[column])
+                .chain(rows.iter().map(|row| &row[column]))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let mut output = String::new();
+    for cells in std::iter::once(header)
+        .chain(std::iter::once(separator.as_slice()))
+        .chain(rows.iter().map(Vec::as_slice))
+    {
+        output.push_str(line(cells).trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// Render a Markdown table with right-aligned columns
+fn render_markdown(header: &[String], rows: &[Vec<String>]) -> String {
+    let line = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+    let mut output = line(header);
+    output.push_str(&format!("|{}\n", "---:|".repeat(header.len())));
+    for row in rows {
+        output.push_str(&line(row));
+    }
+    output
+}
+
+/// Render comma separated values with a header row
+fn render_csv(header: &[String], rows: &[Vec<String>]) -> String {
+    std::iter::once(header)
+        .chain(rows.iter().map(Vec::as_slice))
+        .map(|cells| join_record(cells, ',') + "\n")
+        .collect()
+}
+
+/// Render an HTML `<table>` element
+fn render_html(header: &[String], rows: &[Vec<String>]) -> String {
+    let cells = |tag: &str, cells: &[String]| {
+        cells
+            .iter()
+            .map(|cell| format!("<{tag}>{}</{tag}>", escape_html(cell)))
+            .collect::<String>()
+    };
+    let mut output = String::from("<table>\n  <thead>\n");
+    output.push_str(&format!("    <tr>{}</tr>\n", cells("th", header)));
+    output.push_str("  </thead>\n  <tbody>\n");
+    for row in rows {
+        output.push_str(&format!("    <tr>{}</tr>\n", cells("td", row)));
+    }
+    output.push_str("  </tbody>\n</table>\n");
+    output
+}
+
+/// Escape the characters that have a meaning in HTML
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
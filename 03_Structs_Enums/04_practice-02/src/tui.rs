@@ -0,0 +1,423 @@
+//! Module with the full-screen terminal interface of the converter.
+//! The source and target scales are picked with the arrow keys, the value is converted
+//! while it is typed, and a panel shows it in every scale at once. The layout follows the
+//! size of the terminal: the panel moves below the lists on narrow screens and is left out
+//! when there is no room for it.
+//!
+//! Everything on screen comes from `TuiState`, which knows nothing about terminals, so the
+//! behaviour can be checked without one:
+//!
+//! ```
+//! use temperature::locale::Locale;
+//! use temperature::tui::{Key, TuiState};
+//!
+//! let mut state = TuiState::new(Locale::En);
+//! for c in "100".chars() {
+//!     state.handle_key(Key::Char(c));
+//! }
+//! let screen = state.render(80, 24);
+//! assert!(screen.iter().any(|line| line.contains("100.00 °C is 212.00 °F")));
+//! ```
+//!
+//! `w` switches to the weather calculators in turn: the value is then followed by the
+//! humidity or the wind speed, separated by spaces, e.g. `90 70` for the heat index of 90 °F
+//! at 70 %, and the result is given in the `To` scale.
+//!
+//! `run` draws the state on the alternate screen until Esc, `q` or Ctrl-C. When stdin or
+//! stdout is not a terminal, `is_supported` is false and the line menu of `session` is used.
+
+use std::io::{self, IsTerminal, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, style, terminal};
+
+use crate::locale::{Locale, Message};
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+use crate::weather::{self, Calculator};
+
+/// Narrowest terminal the interface is drawn in
+pub const MIN_WIDTH: u16 = 36;
+/// Shortest terminal the interface is drawn in: the lists, the value, the result and the help
+pub const MIN_HEIGHT: u16 = 16;
+/// Width of the `From` and `To` lists
+const LIST_WIDTH: usize = 16;
+/// Width of the panel with every scale
+const PANEL_WIDTH: usize = 28;
+/// Decimal places of the results
+const PRECISION: usize = 2;
+
+/// Enum to represent the list that receives the up and down keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    From,
+    To,
+}
+
+/// Enum to represent the keys the interface reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Tab,
+    Backspace,
+    /// A typed character; digits and signs go to the value, `s` swaps the scales and `w`
+    /// picks the next weather calculator
+    Char(char),
+    /// Esc, `q` or Ctrl-C
+    Quit,
+}
+
+/// Struct to represent the state of the full-screen interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuiState {
+    from: ConversionUnit,
+    to: ConversionUnit,
+    focus: Focus,
+    input: String,
+    locale: Locale,
+    calculator: Option<Calculator>,
+}
+
+impl TuiState {
+    /// Constructor for the interface, converting Celsius to Fahrenheit
+    pub fn new(locale: Locale) -> Self {
+        TuiState {
+            from: ConversionUnit::ToCelsius,
+            to: ConversionUnit::ToFahrenheit,
+            focus: Focus::From,
+            input: String::new(),
+            locale,
+            calculator: None,
+        }
+    }
+
+    /// Returns the scale of the typed value
+    pub fn from(&self) -> ConversionUnit {
+        self.from
+    }
+
+    /// Returns the scale to convert into
+    pub fn to(&self) -> ConversionUnit {
+        self.to
+    }
+
+    /// Returns the list that the arrow keys move in
+    pub fn focus(&self) -> Focus {
+        self.focus
+    }
+
+    /// Returns the value as typed so far
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the weather calculator in use, or `None` while converting
+    pub fn calculator(&self) -> Option<Calculator> {
+        self.calculator
+    }
+
+    /// Apply one key press
+    ///
+    /// # Returns
+    ///
+    /// * `false` once the user asked to quit
+    pub fn handle_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Quit | Key::Char('q') => return false,
+            Key::Up => self.move_selection(ConversionUnit::ALL.len() - 1),
+            Key::Down => self.move_selection(1),
+            Key::Left | Key::Right | Key::Tab => {
+                self.focus = match self.focus {
+                    Focus::From => Focus::To,
+                    Focus::To => Focus::From,
+                };
+            }
+            Key::Char('s') => (self.from, self.to) = (self.to, self.from),
+            // Converting, then every calculator, then converting again
+            Key::Char('w') => {
+                let next = match self.calculator {
+                    None => 0,
+                    Some(calculator) => {
+                        Calculator::ALL
+                            .iter()
+                            .position(|c| *c == calculator)
+                            .unwrap_or(0)
+                            + 1
+                    }
+                };
+                self.calculator = Calculator::ALL.get(next).copied();
+            }
+            // The humidity and the wind speed follow the value
+            Key::Char(' ') if self.calculator.is_some() => self.input.push(' '),
+            Key::Char(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | ',' | 'e') => {
+                self.input.push(c);
+            }
+            Key::Backspace => {
+                self.input.pop();
+            }
+            Key::Char(_) => {}
+        }
+        true
+    }
+
+    /// Move the selection of the focused list down by `steps`, wrapping around
+    fn move_selection(&mut self, steps: usize) {
+        let selected = match self.focus {
+            Focus::From => &mut self.from,
+            Focus::To => &mut self.to,
+        };
+        let count = ConversionUnit::ALL.len();
+        let index = ConversionUnit::ALL
+            .iter()
+            .position(|unit| unit == selected)
+            .unwrap_or(0);
+        *selected = ConversionUnit::ALL[(index + steps) % count];
+    }
+
+    /// Returns the typed temperature
+    ///
+    /// # Returns
+    ///
+    /// * `None` while nothing is typed, otherwise the temperature or why it is not one;
+    ///   a value that is not a number yet has no `TemperatureError`, so it is `Err(None)`.
+    ///   With a weather calculator only the first number is the temperature.
+    pub fn temperature(&self) -> Option<Result<TemperatureUnit, Option<TemperatureError>>> {
+        if self.input.is_empty() {
+            return None;
+        }
+        let value = match self.calculator {
+            Some(_) => self.input.split_whitespace().next().unwrap_or_default(),
+            None => &self.input,
+        };
+        Some(match self.locale.parse_number(value) {
+            Some(value) => TemperatureUnit::try_new(self.from, value).map_err(Some),
+            None => Err(None),
+        })
+    }
+
+    /// Lay out the screen for a terminal of the given size
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Columns of the terminal
+    /// * `height` - Rows of the terminal
+    ///
+    /// # Returns
+    ///
+    /// * One string per row, none longer than `width` characters
+    pub fn render(&self, width: u16, height: u16) -> Vec<String> {
+        let locale = self.locale;
+        if width < MIN_WIDTH || height < MIN_HEIGHT {
+            let message = locale.message(Message::TerminalTooSmall {
+                width: MIN_WIDTH,
+                height: MIN_HEIGHT,
+            });
+            return vec![fit(&message, width as usize)];
+        }
+        let width = width as usize;
+        let temperature = self.temperature();
+        let valid = match temperature {
+            Some(Ok(temperature)) => Some(temperature),
+            _ => None,
+        };
+
+        // The lists, with the panel beside them when it fits
+        let side_by_side = width >= 2 * LIST_WIDTH + PANEL_WIDTH;
+        let header = |focus: Focus, message: Message<'_>| {
+            let label = locale.message(message);
+            let label = if self.focus == focus {
+                format!("[{label}]")
+            } else {
+                format!(" {label} ")
+            };
+            format!("{label:<LIST_WIDTH$}")
+        };
+        let mut lists =
+            vec![header(Focus::From, Message::TuiFrom) + &header(Focus::To, Message::TuiTo)];
+        for unit in ConversionUnit::ALL {
+            let mark = |selected: ConversionUnit, focus: Focus| match (
+                selected == unit,
+                self.focus == focus,
+            ) {
+                (true, true) => "> ",
+                (true, false) => "* ",
+                _ => "  ",
+            };
+            lists.push(format!(
+                "{}{:<name$}{}{:<name$}",
+                mark(self.from, Focus::From),
+                unit.name(),
+                mark(self.to, Focus::To),
+                unit.name(),
+                name = LIST_WIDTH - 2
+            ));
+        }
+        let mut panel = vec![locale.message(Message::AllScales)];
+        for unit in ConversionUnit::ALL {
+            let value = valid.map_or(String::new(), |temperature| {
+                locale.format_temperature(temperature.convert_to(unit), PRECISION)
+            });
+            let target = if unit == self.to { "<" } else { "" };
+            panel.push(format!("  {:<11}{value} {target}", unit.name()));
+        }
+
+        let hint = self.calculator.map_or(String::new(), |calculator| {
+            locale.message(Message::TuiWeatherInputs(calculator))
+        });
+        let mut lines = vec![locale.message(Message::MenuTitle), hint];
+        if side_by_side {
+            lines.extend(
+                lists
+                    .iter()
+                    .zip(&panel)
+                    .map(|(list, panel)| list.clone() + panel),
+            );
+        } else {
+            lines.extend(lists);
+        }
+        lines.push(String::new());
+        lines.push(format!(
+            "{} {}_",
+            locale.message(Message::TuiValue),
+            self.input
+        ));
+        lines.push(match (self.calculator, temperature) {
+            (_, None) => String::new(),
+            (Some(calculator), Some(_)) => self.weather_line(calculator),
+            (None, Some(Ok(temperature))) => locale.format_conversion(
+                &crate::request::ConversionRequest::new(temperature, self.to),
+                PRECISION,
+            ),
+            (None, Some(Err(Some(error)))) => locale.message(Message::Temperature(&error)),
+            (None, Some(Err(None))) => locale.message(Message::InvalidValue),
+        });
+        // Below the lists the panel needs its own rows, so it only shows when they exist
+        let footer = [String::new(), locale.message(Message::TuiHelp)];
+        if !side_by_side && lines.len() + panel.len() + 1 + footer.len() <= height as usize {
+            lines.push(String::new());
+            lines.extend(panel);
+        }
+        lines.extend(footer);
+        lines.iter().map(|line| fit(line, width)).collect()
+    }
+
+    /// Returns the line with the result of a weather calculator, empty until every input is typed
+    fn weather_line(&self, calculator: Calculator) -> String {
+        let locale = self.locale;
+        let numbers: Option<Vec<f64>> = self
+            .input
+            .split_whitespace()
+            .map(|word| locale.parse_number(word))
+            .collect();
+        let Some(numbers) = numbers else {
+            return locale.message(Message::InvalidValue);
+        };
+        let temperature = TemperatureUnit::new(self.from, numbers[0]);
+        let result = match (calculator, &numbers[1..]) {
+            (Calculator::HeatIndex, [humidity]) => {
+                weather::heat_index(temperature, *humidity, self.to)
+            }
+            (Calculator::WindChill, [wind_speed]) => {
+                weather::wind_chill(temperature, *wind_speed, self.to)
+            }
+            (Calculator::DewPoint, [humidity]) => {
+                weather::dew_point(temperature, *humidity, self.to)
+            }
+            (Calculator::ApparentTemperature, [humidity, wind_speed]) => {
+                weather::apparent_temperature(temperature, *humidity, *wind_speed, self.to)
+            }
+            // Still typing the other inputs
+            (Calculator::ApparentTemperature, [] | [_]) | (_, []) => return String::new(),
+            _ => return locale.message(Message::InvalidValue),
+        };
+        match result {
+            Ok(result) => locale.format_weather(calculator, temperature, result, PRECISION),
+            Err(error) => locale.message(Message::Weather(&error)),
+        }
+    }
+}
+
+/// Cut a line to the width of the terminal
+fn fit(line: &str, width: usize) -> String {
+    line.chars().take(width).collect()
+}
+
+/// Returns true when both stdin and stdout are terminals, so the interface can be drawn
+pub fn is_supported() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Translate a key event of the terminal, ignoring the keys the interface does not use
+fn key_from_event(event: KeyEvent) -> Option<Key> {
+    if event.kind == KeyEventKind::Release {
+        return None;
+    }
+    if event.modifiers.contains(KeyModifiers::CONTROL) {
+        return (event.code == KeyCode::Char('c')).then_some(Key::Quit);
+    }
+    match event.code {
+        KeyCode::Up => Some(Key::Up),
+        KeyCode::Down => Some(Key::Down),
+        KeyCode::Left => Some(Key::Left),
+        KeyCode::Right => Some(Key::Right),
+        KeyCode::Tab | KeyCode::BackTab => Some(Key::Tab),
+        KeyCode::Backspace => Some(Key::Backspace),
+        KeyCode::Esc => Some(Key::Quit),
+        KeyCode::Char(c) => Some(Key::Char(c)),
+        _ => None,
+    }
+}
+
+/// Struct to represent the terminal in raw mode on the alternate screen
+///
+/// Dropping it restores the terminal, also when the interface stops with an error.
+struct RawScreen;
+
+impl RawScreen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let screen = RawScreen;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(screen)
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        // Nothing more can be done if the terminal refuses to be restored
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Run the full-screen interface until the user quits
+///
+/// # Arguments
+///
+/// * `locale` - Language of the labels and of the typed decimals
+///
+/// # Returns
+///
+/// * `Ok(())` after Esc, `q` or Ctrl-C, or the terminal error that stopped the interface
+pub fn run(locale: Locale) -> io::Result<()> {
+    let _screen = RawScreen::enter()?;
+    let mut state = TuiState::new(locale);
+    let mut stdout = io::stdout();
+    loop {
+        let (width, height) = terminal::size()?;
+        queue!(stdout, terminal::Clear(terminal::ClearType::All))?;
+        for (row, line) in state.render(width, height).iter().enumerate() {
+            queue!(stdout, cursor::MoveTo(0, row as u16), style::Print(line))?;
+        }
+        stdout.flush()?;
+        // A resize is an event too, so the next pass draws the new layout
+        if let Event::Key(event) = event::read()?
+            && let Some(key) = key_from_event(event)
+            && !state.handle_key(key)
+        {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Module with temperatures whose scale is part of the type.
+//! `Celsius`, `Fahrenheit` and `Kelvin` are plain `f64` wrappers, so mixing them up is a
+//! compile error instead of a wrong number, and they cost nothing at runtime.
+//!
+//! ```
+//! use temperature::typed::{Celsius, Fahrenheit, Kelvin};
+//!
+//! let body = Celsius::new(37.0);
+//! let in_fahrenheit = Fahrenheit::from(body);
+//! assert_eq!(format!("{in_fahrenheit:.1}"), "98.6 °F");
+//! assert!(Kelvin::new(373.15) > body);
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Sub};
+
+use crate::delta::TemperatureDelta;
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Trait implemented by the marker types that name a scale
+pub trait Scale: Copy + fmt::Debug {
+    /// The runtime unit matching this scale
+    const UNIT: ConversionUnit;
+}
+
+/// Marker type for the Celsius scale
+#[derive(Debug, Clone, Copy)]
+pub struct CelsiusScale;
+
+/// Marker type for the Fahrenheit scale
+#[derive(Debug, Clone, Copy)]
+pub struct FahrenheitScale;
+
+/// Marker type for the Kelvin scale
+#[derive(Debug, Clone, Copy)]
+pub struct KelvinScale;
+
+impl Scale for CelsiusScale {
+    const UNIT: ConversionUnit = ConversionUnit::ToCelsius;
+}
+
+impl Scale for FahrenheitScale {
+    const UNIT: ConversionUnit = ConversionUnit::ToFahrenheit;
+}
+
+impl Scale for KelvinScale {
+    const UNIT: ConversionUnit = ConversionUnit::ToKelvin;
+}
+
+/// Struct to represent a temperature in the scale `S`
+#[derive(Debug, Clone, Copy)]
+#[repr(transparent)]
+pub struct Temperature<S: Scale> {
+    value: f64,
+    scale: PhantomData<S>,
+}
+
+/// A temperature in degrees Celsius
+pub type Celsius = Temperature<CelsiusScale>;
+/// A temperature in degrees Fahrenheit
+pub type Fahrenheit = Temperature<FahrenheitScale>;
+/// A temperature in kelvins
+pub type Kelvin = Temperature<KelvinScale>;
+
+impl<S: Scale> Temperature<S> {
+    /// Constructor for Temperature
+    ///
+    /// The value is not checked; use `try_new` for values typed by users.
+    pub const fn new(value: f64) -> Self {
+        Temperature {
+            value,
+            scale: PhantomData,
+        }
+    }
+
+    /// Validated constructor for Temperature
+    ///
+    /// # Returns
+    ///
+    /// * The temperature, or an error if the value is not finite or below absolute zero
+    pub fn try_new(value: f64) -> Result<Self, TemperatureError> {
+        TemperatureUnit::try_new(S::UNIT, value)?;
+        Ok(Temperature::new(value))
+    }
+
+    /// Returns the numeric value without its unit
+    pub const fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns the runtime unit of this temperature
+    pub fn unit(&self) -> ConversionUnit {
+        S::UNIT
+    }
+
+    /// Converts this temperature into any other scale
+    ///
+    /// # Returns
+    ///
+    /// * The same temperature in the scale `T`
+    pub fn convert<T: Scale>(self) -> Temperature<T> {
+        Temperature::new(TemperatureUnit::from(self).convert_to(T::UNIT).value())
+    }
+}
+
+/// Typed temperatures can always go back to the runtime enum
+impl<S: Scale> From<Temperature<S>> for TemperatureUnit {
+    fn from(temperature: Temperature<S>) -> Self {
+        TemperatureUnit::new(S::UNIT, temperature.value)
+    }
+}
+
+impl From<Celsius> for Fahrenheit {
+    fn from(celsius: Celsius) -> Self {
+        celsius.convert()
+    }
+}
+
+impl From<Celsius> for Kelvin {
+    fn from(celsius: Celsius) -> Self {
+        celsius.convert()
+    }
+}
+
+impl From<Fahrenheit> for Celsius {
+    fn from(fahrenheit: Fahrenheit) -> Self {
+        fahrenheit.convert()
+    }
+}
+
+impl From<Fahrenheit> for Kelvin {
+    fn from(fahrenheit: Fahrenheit) -> Self {
+        fahrenheit.convert()
+    }
+}
+
+impl From<Kelvin> for Celsius {
+    fn from(kelvin: Kelvin) -> Self {
+        kelvin.convert()
+    }
+}
+
+impl From<Kelvin> for Fahrenheit {
+    fn from(kelvin: Kelvin) -> Self {
+        kelvin.convert()
+    }
+}
+
+/// Temperatures are equal when they are the same physical temperature, whatever the scale
+impl<A: Scale, B: Scale> PartialEq<Temperature<B>> for Temperature<A> {
+    fn eq(&self, other: &Temperature<B>) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// Temperatures are ordered by how hot they are, whatever the scale
+impl<A: Scale, B: Scale> PartialOrd<Temperature<B>> for Temperature<A> {
+    fn partial_cmp(&self, other: &Temperature<B>) -> Option<Ordering> {
+        // Same scale: compare the raw values so no rounding creeps in
+        if A::UNIT == B::UNIT {
+            return self.value.partial_cmp(&other.value);
+        }
+        let this = TemperatureUnit::from(*self).to_kelvin();
+        let other = TemperatureUnit::from(*other).to_kelvin();
+        this.partial_cmp(&other)
+    }
+}
+
+/// `absolute + delta = absolute`
+impl<S: Scale> Add<TemperatureDelta> for Temperature<S> {
+    type Output = Temperature<S>;
+
+    fn add(self, rhs: TemperatureDelta) -> Temperature<S> {
+        Temperature::new(self.value + rhs.convert_to(S::UNIT).value())
+    }
+}
+
+/// `absolute - delta = absolute`
+impl<S: Scale> Sub<TemperatureDelta> for Temperature<S> {
+    type Output = Temperature<S>;
+
+    fn sub(self, rhs: TemperatureDelta) -> Temperature<S> {
+        Temperature::new(self.value - rhs.convert_to(S::UNIT).value())
+    }
+}
+
+/// `absolute - absolute = delta`, measured in the scale of the left-hand side
+impl<A: Scale, B: Scale> Sub<Temperature<B>> for Temperature<A> {
+    type Output = TemperatureDelta;
+
+    fn sub(self, rhs: Temperature<B>) -> TemperatureDelta {
+        TemperatureUnit::from(self) - TemperatureUnit::from(rhs)
+    }
+}
+
+/// Prints the value with its symbol, e.g. `21.5 °C`; a precision such as `{:.2}` applies to the value
+impl<S: Scale> fmt::Display for Temperature<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&TemperatureUnit::from(*self), f)
+    }
+}
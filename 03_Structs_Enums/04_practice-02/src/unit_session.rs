@@ -0,0 +1,264 @@
+//! Module with the interactive menu of the unit registry.
+//! Nothing in the menu is written by hand: the quantities are the dimensions that have
+//! units in the registry and the unit lists are its units, so a newly registered unit
+//! shows up without touching this module.
+//!
+//! ```
+//! use temperature::unit_session::UnitSession;
+//!
+//! let mut session = UnitSession::new("2\n2\n5\n5\n0\n".as_bytes(), Vec::new());
+//! session.run().unwrap();
+//! let transcript = String::from_utf8(session.into_output()).unwrap();
+//! assert!(transcript.contains("5.00 km is 3.11 mi"));
+//! ```
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+
+use crate::cli::DEFAULT_PRECISION;
+use crate::locale::{Locale, Message};
+use crate::output::{self, OutputFormat};
+use crate::registry::{Dimension, Quantity, Registry, Unit};
+use crate::session::{CLEAR_SCREEN, SessionError};
+
+/// Struct to represent an interactive unit conversion session over a reader and a writer
+pub struct UnitSession<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    /// Where the menu goes in machine formats; `None` leaves it out
+    menu_output: Option<Box<dyn Write>>,
+    format: OutputFormat,
+    locale: Locale,
+    /// Shared so the units can be borrowed while the session writes
+    registry: Rc<Registry>,
+}
+
+/// Implement methods for UnitSession
+impl<R: BufRead, W: Write> UnitSession<R, W> {
+    /// Constructor for UnitSession with the built-in units, printing results as text
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where the user's answers are read from
+    /// * `output` - Where the menu and the results are written
+    pub fn new(input: R, output: W) -> Self {
+        UnitSession {
+            input,
+            output,
+            menu_output: None,
+            format: OutputFormat::Text,
+            locale: Locale::En,
+            registry: Rc::new(Registry::builtin()),
+        }
+    }
+
+    /// Print the results in another format, like `Session::with_format`
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Send the menu and prompts to a separate writer in machine formats, e.g. stderr
+    pub fn with_menu_output(mut self, menu_output: Box<dyn Write>) -> Self {
+        self.menu_output = Some(menu_output);
+        self
+    }
+
+    /// Show the menu in another language, with its decimal separator
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Offer the units of another registry instead of the built-in ones
+    pub fn with_registry(mut self, registry: Registry) -> Self {
+        self.registry = Rc::new(registry);
+        self
+    }
+
+    /// Consume the session and return its writer, e.g. to inspect a transcript
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Runs the menu until the user chooses Exit or the input ends
+    ///
+    /// # Returns
+    ///
+    /// * An error if reading the input or writing the output failed
+    pub fn run(&mut self) -> Result<(), SessionError> {
+        self.clear_console()?;
+        loop {
+            let dimensions = self.registry.dimensions();
+            self.display_menu(&dimensions)?;
+            let Some(input) = self.read_input()? else {
+                break;
+            };
+            match input.parse::<usize>() {
+                Ok(0) => break,
+                Ok(number) => match number.checked_sub(1).and_then(|i| dimensions.get(i)) {
+                    Some(dimension) => self.convert_in(*dimension)?,
+                    None => self.show_error(&self.locale.message(Message::InvalidChoice))?,
+                },
+                // Not a menu number, so it may be a whole conversion
+                Err(_) => {
+                    let normalized = self.locale.normalize_decimals(&input);
+                    let registry = Rc::clone(&self.registry);
+                    match registry.parse_conversion(&normalized) {
+                        Ok((from, to)) => self.show_result(&from, &to)?,
+                        Err(error) => {
+                            self.show_error(&self.locale.message(Message::Unit(&error)))?
+                        }
+                    }
+                }
+            }
+        }
+        self.say(Message::Goodbye)?;
+        Ok(self.output.flush()?)
+    }
+
+    /// Let the user pick two units of one dimension and a value, then convert it
+    ///
+    /// The value may carry its own unit, e.g. `3 mi`, which is checked against the
+    /// dimension like any other conversion.
+    fn convert_in(&mut self, dimension: Dimension) -> Result<(), SessionError> {
+        let registry = Rc::clone(&self.registry);
+        let units: Vec<&Unit> = registry.units_of(dimension).collect();
+        self.say(Message::ConvertFrom)?;
+        let Some(from) = self.pick_unit(&units)? else {
+            return Ok(());
+        };
+        self.say(Message::ConvertUnitTo(&from.name))?;
+        let Some(to) = self.pick_unit(&units)? else {
+            return Ok(());
+        };
+        self.say(Message::EnterQuantity)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(());
+        };
+        let quantity = match self.locale.parse_number(&input) {
+            Some(value) => Ok(Quantity { value, unit: from }),
+            None => registry.parse_quantity(&self.locale.normalize_decimals(&input)),
+        };
+        match quantity.and_then(|quantity| Ok((quantity, quantity.convert_to(to)?))) {
+            Ok((from, to)) => self.show_result(&from, &to),
+            Err(error) => {
+                self.say(Message::InvalidQuantity)?;
+                self.say(Message::Unit(&error))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// List the units and read the number of one of them
+    ///
+    /// # Returns
+    ///
+    /// * The chosen unit, or `None` if the input ended or the choice was invalid
+    fn pick_unit<'a>(&mut self, units: &[&'a Unit]) -> Result<Option<&'a Unit>, SessionError> {
+        for (index, unit) in units.iter().enumerate() {
+            self.menu_line(format_args!(
+                "{}. {} ({})",
+                index + 1,
+                unit.name,
+                unit.symbol
+            ))?;
+        }
+        self.say(Message::EnterChoice)?;
+        let Some(input) = self.read_input()? else {
+            return Ok(None);
+        };
+        let unit = input
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| units.get(index));
+        match unit {
+            Some(unit) => Ok(Some(*unit)),
+            None => {
+                self.show_error(&self.locale.message(Message::InvalidChoice))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Function to display the quantities of the registry
+    fn display_menu(&mut self, dimensions: &[Dimension]) -> io::Result<()> {
+        self.say(Message::UnitMenuTitle)?;
+        self.say(Message::ChooseQuantity)?;
+        for (index, dimension) in dimensions.iter().enumerate() {
+            let name = self.locale.message(Message::Dimension(*dimension));
+            self.menu_line(format_args!("{}. {name}", index + 1))?;
+        }
+        self.say(Message::Exit)?;
+        self.say(Message::UnitExpressionHint)?;
+        self.menu_line(format_args!("==================================="))?;
+        self.say(Message::EnterChoice)
+    }
+
+    /// Write one line of menu text, to the menu writer in machine formats
+    fn menu_line(&mut self, line: fmt::Arguments<'_>) -> io::Result<()> {
+        if !self.format.is_machine() {
+            return writeln!(self.output, "{line}");
+        }
+        match &mut self.menu_output {
+            Some(menu_output) => writeln!(menu_output, "{line}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Write one line of the message catalog in the session's language
+    fn say(&mut self, message: Message<'_>) -> io::Result<()> {
+        let text = self.locale.message(message);
+        self.menu_line(format_args!("{text}"))
+    }
+
+    /// Clear the screen and explain what went wrong
+    fn show_error(&mut self, message: &str) -> io::Result<()> {
+        self.clear_console()?;
+        self.menu_line(format_args!("{message}"))
+    }
+
+    /// Function to clear the console screen, except in machine formats
+    fn clear_console(&mut self) -> io::Result<()> {
+        if self.format.is_machine() {
+            return Ok(());
+        }
+        write!(self.output, "{CLEAR_SCREEN}")?;
+        self.output.flush()
+    }
+
+    /// Function to read one line of user input
+    ///
+    /// # Returns
+    ///
+    /// * The line without surrounding whitespace, or `None` once the input has ended
+    fn read_input(&mut self) -> Result<Option<String>, SessionError> {
+        let mut input = String::new();
+        loop {
+            match self.input.read_line(&mut input) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(input.trim().to_string())),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(SessionError::Input(error)),
+            }
+        }
+    }
+
+    /// Function to print the result of a conversion in a banner, or as JSON
+    fn show_result(&mut self, from: &Quantity<'_>, to: &Quantity<'_>) -> Result<(), SessionError> {
+        if self.format.is_machine() {
+            let json = output::format_quantity_json(from, to, DEFAULT_PRECISION);
+            writeln!(self.output, "{json}")?;
+            return Ok(self.output.flush()?);
+        }
+        self.clear_console()?;
+        let title = self.locale.message(Message::ResultTitle);
+        let text = self
+            .locale
+            .format_quantity_conversion(from, to, DEFAULT_PRECISION);
+        writeln!(self.output, "{title}\n{text}")?;
+        Ok(writeln!(self.output, "===============================")?)
+    }
+}
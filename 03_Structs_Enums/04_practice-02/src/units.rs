@@ -0,0 +1,375 @@
+//! Module with the temperature unit types.
+//! `TemperatureUnit` carries a value in a given scale and `ConversionUnit` names a target scale.
+//! Every conversion goes through Kelvin, so adding a scale only needs two formulas.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::formulas::{
+    DELISLE_FACTOR, NEWTON_FACTOR, REAUMUR_FACTOR, ROMER_FACTOR, SCALE_FACTOR, celsius_to_kelvin,
+    delisle_to_kelvin, fahrenheit_to_kelvin, kelvin_to_celsius, kelvin_to_delisle,
+    kelvin_to_fahrenheit, kelvin_to_newton, kelvin_to_rankine, kelvin_to_reaumur, kelvin_to_romer,
+    newton_to_kelvin, rankine_to_kelvin, reaumur_to_kelvin, romer_to_kelvin,
+};
+
+/// Kelvin readings this far below zero are still accepted, to absorb floating point
+/// noise such as `-459.67 °F` landing a hair under 0 K after conversion.
+const ABSOLUTE_ZERO_TOLERANCE: f64 = 1e-9;
+
+/// Enum to represent different temperature units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemperatureUnit {
+    Celsius(f64),
+    Fahrenheit(f64),
+    Kelvin(f64),
+    Rankine(f64),
+    Reaumur(f64),
+    Delisle(f64),
+    Newton(f64),
+    Romer(f64),
+}
+
+impl TemperatureUnit {
+    /// Constructor for TemperatureUnit
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The scale the value is expressed in
+    /// * `value` - The temperature value
+    ///
+    /// # Returns
+    ///
+    /// * A TemperatureUnit holding `value` in the `unit` scale
+    ///
+    /// The value is not checked; use `try_new` for values typed by users.
+    pub fn new(unit: ConversionUnit, value: f64) -> Self {
+        match unit {
+            ConversionUnit::ToCelsius => TemperatureUnit::Celsius(value),
+            ConversionUnit::ToFahrenheit => TemperatureUnit::Fahrenheit(value),
+            ConversionUnit::ToKelvin => TemperatureUnit::Kelvin(value),
+            ConversionUnit::ToRankine => TemperatureUnit::Rankine(value),
+            ConversionUnit::ToReaumur => TemperatureUnit::Reaumur(value),
+            ConversionUnit::ToDelisle => TemperatureUnit::Delisle(value),
+            ConversionUnit::ToNewton => TemperatureUnit::Newton(value),
+            ConversionUnit::ToRomer => TemperatureUnit::Romer(value),
+        }
+    }
+
+    /// Validated constructor for TemperatureUnit
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - The scale the value is expressed in
+    /// * `value` - The temperature value
+    ///
+    /// # Returns
+    ///
+    /// * The temperature, or an error if the value is not finite or below absolute zero
+    pub fn try_new(unit: ConversionUnit, value: f64) -> Result<Self, TemperatureError> {
+        let temperature = TemperatureUnit::new(unit, value);
+        temperature.validate()?;
+        Ok(temperature)
+    }
+
+    /// Checks that this temperature can physically exist
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())`, or the reason the temperature is impossible
+    pub fn validate(&self) -> Result<(), TemperatureError> {
+        let (value, unit) = (self.value(), self.unit());
+        if !value.is_finite() {
+            return Err(TemperatureError::NotFinite { value, unit });
+        }
+        if self.to_kelvin() < -ABSOLUTE_ZERO_TOLERANCE {
+            return Err(TemperatureError::BelowAbsoluteZero { value, unit });
+        }
+        Ok(())
+    }
+
+    /// Returns the numeric value without its unit
+    pub fn value(&self) -> f64 {
+        match self {
+            TemperatureUnit::Celsius(v)
+            | TemperatureUnit::Fahrenheit(v)
+            | TemperatureUnit::Kelvin(v)
+            | TemperatureUnit::Rankine(v)
+            | TemperatureUnit::Reaumur(v)
+            | TemperatureUnit::Delisle(v)
+            | TemperatureUnit::Newton(v)
+            | TemperatureUnit::Romer(v) => *v,
+        }
+    }
+
+    /// Returns the scale this temperature is expressed in
+    pub fn unit(&self) -> ConversionUnit {
+        match self {
+            TemperatureUnit::Celsius(_) => ConversionUnit::ToCelsius,
+            TemperatureUnit::Fahrenheit(_) => ConversionUnit::ToFahrenheit,
+            TemperatureUnit::Kelvin(_) => ConversionUnit::ToKelvin,
+            TemperatureUnit::Rankine(_) => ConversionUnit::ToRankine,
+            TemperatureUnit::Reaumur(_) => ConversionUnit::ToReaumur,
+            TemperatureUnit::Delisle(_) => ConversionUnit::ToDelisle,
+            TemperatureUnit::Newton(_) => ConversionUnit::ToNewton,
+            TemperatureUnit::Romer(_) => ConversionUnit::ToRomer,
+        }
+    }
+
+    /// Returns this temperature expressed in Kelvin, the pivot scale
+    pub fn to_kelvin(self) -> f64 {
+        self.unit().scale_to_kelvin(self.value())
+    }
+
+    /// Converts this temperature into another scale
+    ///
+    /// Converting into the same scale returns the temperature unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The target scale
+    ///
+    /// # Returns
+    ///
+    /// * A new TemperatureUnit expressed in the `to` scale
+    pub fn convert_to(&self, to: ConversionUnit) -> TemperatureUnit {
+        // Same scale on both sides, skip the round trip so the value stays exact
+        if self.unit() == to {
+            return *self;
+        }
+        TemperatureUnit::new(to, to.kelvin_to_scale(self.to_kelvin()))
+    }
+}
+
+/// Prints the value with its symbol, e.g. `21.5 °C`; a precision such as `{:.2}` applies to the value
+impl fmt::Display for TemperatureUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} {}", precision, self.value(), self.unit().symbol()),
+            None => write!(f, "{} {}", self.value(), self.unit().symbol()),
+        }
+    }
+}
+
+/// Enum to represent conversion target units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionUnit {
+    ToCelsius,
+    ToFahrenheit,
+    ToKelvin,
+    ToRankine,
+    ToReaumur,
+    ToDelisle,
+    ToNewton,
+    ToRomer,
+}
+
+impl ConversionUnit {
+    /// Every supported scale, in the order they are shown to users
+    pub const ALL: [ConversionUnit; 8] = [
+        ConversionUnit::ToCelsius,
+        ConversionUnit::ToFahrenheit,
+        ConversionUnit::ToKelvin,
+        ConversionUnit::ToRankine,
+        ConversionUnit::ToReaumur,
+        ConversionUnit::ToDelisle,
+        ConversionUnit::ToNewton,
+        ConversionUnit::ToRomer,
+    ];
+
+    /// Returns the symbol used to print values in this scale, e.g. `°C`
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ConversionUnit::ToCelsius => "°C",
+            ConversionUnit::ToFahrenheit => "°F",
+            ConversionUnit::ToKelvin => "K",
+            ConversionUnit::ToRankine => "°R",
+            ConversionUnit::ToReaumur => "°Ré",
+            ConversionUnit::ToDelisle => "°De",
+            ConversionUnit::ToNewton => "°N",
+            ConversionUnit::ToRomer => "°Rø",
+        }
+    }
+
+    /// Returns a short ASCII code for this scale, e.g. `C` or `Re`
+    ///
+    /// Codes are meant for machine-readable output and parse back with `FromStr`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConversionUnit::ToCelsius => "C",
+            ConversionUnit::ToFahrenheit => "F",
+            ConversionUnit::ToKelvin => "K",
+            ConversionUnit::ToRankine => "R",
+            ConversionUnit::ToReaumur => "Re",
+            ConversionUnit::ToDelisle => "De",
+            ConversionUnit::ToNewton => "N",
+            ConversionUnit::ToRomer => "Ro",
+        }
+    }
+
+    /// Returns the human readable name of this scale, e.g. `Celsius`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConversionUnit::ToCelsius => "Celsius",
+            ConversionUnit::ToFahrenheit => "Fahrenheit",
+            ConversionUnit::ToKelvin => "Kelvin",
+            ConversionUnit::ToRankine => "Rankine",
+            ConversionUnit::ToReaumur => "Réaumur",
+            ConversionUnit::ToDelisle => "Delisle",
+            ConversionUnit::ToNewton => "Newton",
+            ConversionUnit::ToRomer => "Rømer",
+        }
+    }
+
+    /// Returns how many degrees of this scale fit in one kelvin
+    ///
+    /// This is the only factor that applies to temperature differences.
+    /// It is negative for Delisle, which counts downwards.
+    pub fn degrees_per_kelvin(&self) -> f64 {
+        match self {
+            ConversionUnit::ToCelsius | ConversionUnit::ToKelvin => 1.0,
+            ConversionUnit::ToFahrenheit | ConversionUnit::ToRankine => SCALE_FACTOR,
+            ConversionUnit::ToReaumur => REAUMUR_FACTOR,
+            ConversionUnit::ToDelisle => -DELISLE_FACTOR,
+            ConversionUnit::ToNewton => NEWTON_FACTOR,
+            ConversionUnit::ToRomer => ROMER_FACTOR,
+        }
+    }
+
+    /// Returns absolute zero (0 K) expressed in this scale
+    pub fn absolute_zero(&self) -> f64 {
+        self.kelvin_to_scale(0.0)
+    }
+
+    /// Returns the lowercase names and symbols accepted when parsing this scale
+    pub fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            ConversionUnit::ToCelsius => &["c", "°c", "degc", "celsius", "centigrade"],
+            ConversionUnit::ToFahrenheit => &["f", "°f", "degf", "fahrenheit"],
+            ConversionUnit::ToKelvin => &["k", "kelvin", "kelvins"],
+            ConversionUnit::ToRankine => &["r", "°r", "rankine"],
+            ConversionUnit::ToReaumur => &["re", "ré", "°ré", "reaumur", "réaumur"],
+            ConversionUnit::ToDelisle => &["de", "°de", "delisle"],
+            ConversionUnit::ToNewton => &["n", "°n", "newton"],
+            ConversionUnit::ToRomer => &["ro", "rø", "°rø", "romer", "rømer"],
+        }
+    }
+
+    /// Converts a value expressed in this scale into Kelvin
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Temperature in this scale
+    ///
+    /// # Returns
+    ///
+    /// * Temperature in Kelvin
+    pub fn scale_to_kelvin(&self, value: f64) -> f64 {
+        match self {
+            ConversionUnit::ToCelsius => celsius_to_kelvin(value),
+            ConversionUnit::ToFahrenheit => fahrenheit_to_kelvin(value),
+            ConversionUnit::ToKelvin => value,
+            ConversionUnit::ToRankine => rankine_to_kelvin(value),
+            ConversionUnit::ToReaumur => reaumur_to_kelvin(value),
+            ConversionUnit::ToDelisle => delisle_to_kelvin(value),
+            ConversionUnit::ToNewton => newton_to_kelvin(value),
+            ConversionUnit::ToRomer => romer_to_kelvin(value),
+        }
+    }
+
+    /// Converts a value expressed in Kelvin into this scale
+    ///
+    /// # Arguments
+    ///
+    /// * `kelvin` - Temperature in Kelvin
+    ///
+    /// # Returns
+    ///
+    /// * Temperature in this scale
+    pub fn kelvin_to_scale(&self, kelvin: f64) -> f64 {
+        match self {
+            ConversionUnit::ToCelsius => kelvin_to_celsius(kelvin),
+            ConversionUnit::ToFahrenheit => kelvin_to_fahrenheit(kelvin),
+            ConversionUnit::ToKelvin => kelvin,
+            ConversionUnit::ToRankine => kelvin_to_rankine(kelvin),
+            ConversionUnit::ToReaumur => kelvin_to_reaumur(kelvin),
+            ConversionUnit::ToDelisle => kelvin_to_delisle(kelvin),
+            ConversionUnit::ToNewton => kelvin_to_newton(kelvin),
+            ConversionUnit::ToRomer => kelvin_to_romer(kelvin),
+        }
+    }
+}
+
+/// Parse a scale from its name or symbol, ignoring case (e.g. `C`, `°F`, `℃`, `kelvin`)
+impl FromStr for ConversionUnit {
+    type Err = ParseUnitError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // Fold the look-alike degree signs and the single character ℃/℉ symbols into `°`
+        let wanted = input
+            .trim()
+            .to_lowercase()
+            .replace('º', "°")
+            .replace('℃', "°c")
+            .replace('℉', "°f");
+        ConversionUnit::ALL
+            .into_iter()
+            .find(|unit| unit.aliases().contains(&wanted.as_str()))
+            .ok_or_else(|| ParseUnitError {
+                input: input.to_string(),
+            })
+    }
+}
+
+/// Error returned when a string does not name any supported scale
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitError {
+    input: String,
+}
+
+impl fmt::Display for ParseUnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown temperature unit `{}`", self.input)
+    }
+}
+
+impl std::error::Error for ParseUnitError {}
+
+/// Enum to represent temperatures that cannot physically exist
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TemperatureError {
+    /// The value is colder than 0 K
+    BelowAbsoluteZero { value: f64, unit: ConversionUnit },
+    /// The value is NaN or infinite
+    NotFinite { value: f64, unit: ConversionUnit },
+    /// The value is finite but its conversion into `to` is not, e.g. `1.7e308 K` in °F
+    TooLarge {
+        value: f64,
+        unit: ConversionUnit,
+        to: ConversionUnit,
+    },
+}
+
+impl fmt::Display for TemperatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemperatureError::BelowAbsoluteZero { value, unit } => write!(
+                f,
+                "{value} {} is below absolute zero ({:.2} {})",
+                unit.symbol(),
+                unit.absolute_zero(),
+                unit.symbol()
+            ),
+            TemperatureError::NotFinite { value, unit } => {
+                write!(f, "{value} {} is not a finite temperature", unit.symbol())
+            }
+            TemperatureError::TooLarge { value, unit, to } => write!(
+                f,
+                "{value:e} {} is too large to convert into {}",
+                unit.symbol(),
+                to.symbol()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemperatureError {}
@@ -0,0 +1,396 @@
+//! Module with the weather calculators of the converter.
+//! Heat index, wind chill, dew point and apparent temperature are computed from a
+//! temperature in any scale and returned in the scale asked for. Each formula only
+//! holds over the range it was fitted on, e.g. the heat index from 80 °F, so inputs
+//! outside it are rejected instead of giving a number that means nothing.
+//!
+//! Humidities are relative, in percent, and wind speeds are in km/h at 10 m above
+//! the ground, as weather reports give them:
+//!
+//! ```
+//! use temperature::weather::{self, Calculator, WeatherError};
+//! use temperature::{ConversionUnit, TemperatureUnit};
+//!
+//! let chill = weather::wind_chill(TemperatureUnit::Celsius(-10.0), 20.0, ConversionUnit::ToCelsius);
+//! assert_eq!(chill.unwrap().value().round(), -18.0);
+//!
+//! let error = weather::heat_index(TemperatureUnit::Celsius(20.0), 50.0, ConversionUnit::ToCelsius);
+//! assert!(matches!(
+//!     error,
+//!     Err(WeatherError::TooCold { calculator: Calculator::HeatIndex, .. })
+//! ));
+//! ```
+
+use std::fmt;
+
+use crate::units::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Range of air temperatures, in °F, of the NWS heat index chart the regression was fitted on
+pub const HEAT_INDEX_RANGE_F: (f64, f64) = (80.0, 110.0);
+/// Range of air temperatures, in °C, of the Environment Canada wind chill chart
+pub const WIND_CHILL_RANGE_C: (f64, f64) = (-50.0, 10.0);
+/// Slowest wind, in km/h, the wind chill formula applies to
+pub const WIND_CHILL_MINIMUM_KMH: f64 = 4.8;
+/// Fastest wind, in km/h, of the Environment Canada wind chill chart
+pub const WIND_CHILL_MAXIMUM_KMH: f64 = 80.0;
+/// Range of air temperatures, in °C, of the Magnus coefficients used for the dew point
+pub const DEW_POINT_RANGE_C: (f64, f64) = (-45.0, 60.0);
+/// Range of air temperatures, in °C, of the apparent temperature; its vapour pressure
+/// comes from a Magnus fit like the dew point
+pub const APPARENT_TEMPERATURE_RANGE_C: (f64, f64) = (-45.0, 60.0);
+/// Fastest wind, in km/h, the apparent temperature is given for (hurricane force)
+pub const APPARENT_TEMPERATURE_MAXIMUM_KMH: f64 = 120.0;
+
+/// Magnus coefficients over water (Sonntag 1990)
+const MAGNUS_B: f64 = 17.62;
+const MAGNUS_C: f64 = 243.12;
+
+/// Enum to represent the calculators offered next to the converter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Calculator {
+    HeatIndex,
+    WindChill,
+    DewPoint,
+    ApparentTemperature,
+}
+
+impl Calculator {
+    /// Every calculator, in the order they are shown to users
+    pub const ALL: [Calculator; 4] = [
+        Calculator::HeatIndex,
+        Calculator::WindChill,
+        Calculator::DewPoint,
+        Calculator::ApparentTemperature,
+    ];
+
+    /// Returns the English name of this calculator, e.g. `Heat index`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Calculator::HeatIndex => "Heat index",
+            Calculator::WindChill => "Wind chill",
+            Calculator::DewPoint => "Dew point",
+            Calculator::ApparentTemperature => "Apparent temperature",
+        }
+    }
+
+    /// Returns the key of this calculator in the JSON output
+    pub fn key(&self) -> &'static str {
+        match self {
+            Calculator::HeatIndex => "heat_index",
+            Calculator::WindChill => "wind_chill",
+            Calculator::DewPoint => "dew_point",
+            Calculator::ApparentTemperature => "apparent_temperature",
+        }
+    }
+}
+
+/// Enum to represent inputs a calculator cannot work with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeatherError {
+    /// The air temperature cannot physically exist
+    Temperature(TemperatureError),
+    /// The humidity is not above 0 and up to 100 %
+    Humidity(f64),
+    /// The wind speed is negative or not finite
+    WindSpeed(f64),
+    /// The air is colder than the formula applies to; the limit is in the scale of the input
+    TooCold {
+        calculator: Calculator,
+        minimum: TemperatureUnit,
+    },
+    /// The air is warmer than the formula applies to; the limit is in the scale of the input
+    TooWarm {
+        calculator: Calculator,
+        maximum: TemperatureUnit,
+    },
+    /// The wind is too slow for the wind chill, given with the slowest wind in km/h
+    TooCalm { minimum: f64 },
+    /// The wind is faster than the formula applies to, given with the fastest wind in km/h
+    TooWindy {
+        calculator: Calculator,
+        maximum: f64,
+    },
+}
+
+impl fmt::Display for WeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherError::Temperature(error) => write!(f, "{error}"),
+            WeatherError::Humidity(value) => write!(
+                f,
+                "{value} % is not a relative humidity, use a value above 0 and up to 100"
+            ),
+            WeatherError::WindSpeed(value) => write!(f, "{value} km/h is not a wind speed"),
+            WeatherError::TooCold {
+                calculator,
+                minimum,
+            } => write!(
+                f,
+                "the {} only applies from {minimum:.2}",
+                calculator.name().to_lowercase()
+            ),
+            WeatherError::TooWarm {
+                calculator,
+                maximum,
+            } => write!(
+                f,
+                "the {} only applies up to {maximum:.2}",
+                calculator.name().to_lowercase()
+            ),
+            WeatherError::TooCalm { minimum } => write!(
+                f,
+                "the wind chill only applies to winds of at least {minimum} km/h"
+            ),
+            WeatherError::TooWindy {
+                calculator,
+                maximum,
+            } => write!(
+                f,
+                "the {} only applies to winds of up to {maximum} km/h",
+                calculator.name().to_lowercase()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WeatherError {}
+
+/// Heat index of the US National Weather Service, i.e. how hot humid air feels
+///
+/// Uses the Rothfusz regression with the NWS adjustments for very dry and very humid air.
+///
+/// # Arguments
+///
+/// * `temperature` - The air temperature, from 80 °F to 110 °F (26.67 °C to 43.33 °C)
+/// * `humidity` - The relative humidity in percent
+/// * `to` - The scale of the result
+///
+/// # Returns
+///
+/// * The heat index in the `to` scale, or why the inputs are out of range
+pub fn heat_index(
+    temperature: TemperatureUnit,
+    humidity: f64,
+    to: ConversionUnit,
+) -> Result<TemperatureUnit, WeatherError> {
+    check_temperature(temperature)?;
+    check_humidity(humidity)?;
+    let t = check_range(
+        temperature,
+        Calculator::HeatIndex,
+        ConversionUnit::ToFahrenheit,
+        HEAT_INDEX_RANGE_F,
+    )?;
+    let r = humidity;
+    let mut index = -42.379 + 2.049_015_23 * t + 10.143_331_27 * r
+        - 0.224_755_41 * t * r
+        - 0.006_837_83 * t * t
+        - 0.054_817_17 * r * r
+        + 0.001_228_74 * t * t * r
+        + 0.000_852_82 * t * r * r
+        - 0.000_001_99 * t * t * r * r;
+    if r < 13.0 && t <= 112.0 {
+        index -= (13.0 - r) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if r > 85.0 && t <= 87.0 {
+        index += (r - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+    }
+    in_scale(ConversionUnit::ToFahrenheit, index, to)
+}
+
+/// Wind chill of Environment Canada and the US National Weather Service
+///
+/// # Arguments
+///
+/// * `temperature` - The air temperature, from -50 °C to 10 °C (50 °F)
+/// * `wind_speed` - The wind speed in km/h, from 4.8 km/h (3 mph) to 80 km/h
+/// * `to` - The scale of the result
+///
+/// # Returns
+///
+/// * How cold the wind makes the air feel in the `to` scale, or why the inputs are out of range
+pub fn wind_chill(
+    temperature: TemperatureUnit,
+    wind_speed: f64,
+    to: ConversionUnit,
+) -> Result<TemperatureUnit, WeatherError> {
+    check_temperature(temperature)?;
+    check_wind_speed(wind_speed)?;
+    let t = check_range(
+        temperature,
+        Calculator::WindChill,
+        ConversionUnit::ToCelsius,
+        WIND_CHILL_RANGE_C,
+    )?;
+    if wind_speed < WIND_CHILL_MINIMUM_KMH {
+        return Err(WeatherError::TooCalm {
+            minimum: WIND_CHILL_MINIMUM_KMH,
+        });
+    }
+    check_wind_maximum(wind_speed, Calculator::WindChill, WIND_CHILL_MAXIMUM_KMH)?;
+    let v = wind_speed.powf(0.16);
+    let chill = 13.12 + 0.6215 * t - 11.37 * v + 0.3965 * t * v;
+    in_scale(ConversionUnit::ToCelsius, chill, to)
+}
+
+/// Dew point, the temperature at which the air would be saturated, from the Magnus formula
+///
+/// # Arguments
+///
+/// * `temperature` - The air temperature, from -45 °C to 60 °C
+/// * `humidity` - The relative humidity in percent
+/// * `to` - The scale of the result
+///
+/// # Returns
+///
+/// * The dew point in the `to` scale, or why the inputs are out of range
+pub fn dew_point(
+    temperature: TemperatureUnit,
+    humidity: f64,
+    to: ConversionUnit,
+) -> Result<TemperatureUnit, WeatherError> {
+    check_temperature(temperature)?;
+    check_humidity(humidity)?;
+    let t = check_range(
+        temperature,
+        Calculator::DewPoint,
+        ConversionUnit::ToCelsius,
+        DEW_POINT_RANGE_C,
+    )?;
+    let gamma = (humidity / 100.0).ln() + MAGNUS_B * t / (MAGNUS_C + t);
+    let dew_point = MAGNUS_C * gamma / (MAGNUS_B - gamma);
+    in_scale(ConversionUnit::ToCelsius, dew_point, to)
+}
+
+/// Apparent temperature of the Australian Bureau of Meteorology (Steadman 1994)
+///
+/// Unlike the heat index and the wind chill it holds in any weather, taking both the
+/// humidity and the wind into account. Sunshine is left out.
+///
+/// # Arguments
+///
+/// * `temperature` - The air temperature, from -45 °C to 60 °C
+/// * `humidity` - The relative humidity in percent
+/// * `wind_speed` - The wind speed in km/h, up to 120 km/h
+/// * `to` - The scale of the result
+///
+/// # Returns
+///
+/// * How warm the air feels in the `to` scale, or why the inputs are out of range
+pub fn apparent_temperature(
+    temperature: TemperatureUnit,
+    humidity: f64,
+    wind_speed: f64,
+    to: ConversionUnit,
+) -> Result<TemperatureUnit, WeatherError> {
+    check_temperature(temperature)?;
+    check_humidity(humidity)?;
+    check_wind_speed(wind_speed)?;
+    let t = check_range(
+        temperature,
+        Calculator::ApparentTemperature,
+        ConversionUnit::ToCelsius,
+        APPARENT_TEMPERATURE_RANGE_C,
+    )?;
+    check_wind_maximum(
+        wind_speed,
+        Calculator::ApparentTemperature,
+        APPARENT_TEMPERATURE_MAXIMUM_KMH,
+    )?;
+    // Water vapour pressure in hPa
+    let vapour = humidity / 100.0 * 6.105 * (17.27 * t / (237.7 + t)).exp();
+    let apparent = t + 0.33 * vapour - 0.70 * (wind_speed / 3.6) - 4.00;
+    in_scale(ConversionUnit::ToCelsius, apparent, to)
+}
+
+/// Rejects air outside the range of a formula
+///
+/// # Arguments
+///
+/// * `temperature` - The air temperature, already checked to exist
+/// * `calculator` - The calculator named in the error
+/// * `scale` - The scale the formula works in
+/// * `range` - The coldest and warmest air in `scale`
+///
+/// # Returns
+///
+/// * The air temperature in `scale`, or the limit it crossed in the scale of the input
+fn check_range(
+    temperature: TemperatureUnit,
+    calculator: Calculator,
+    scale: ConversionUnit,
+    (minimum, maximum): (f64, f64),
+) -> Result<f64, WeatherError> {
+    let t = temperature.convert_to(scale).value();
+    if t < minimum {
+        return Err(WeatherError::TooCold {
+            calculator,
+            minimum: TemperatureUnit::new(scale, minimum).convert_to(temperature.unit()),
+        });
+    }
+    if t > maximum {
+        return Err(WeatherError::TooWarm {
+            calculator,
+            maximum: TemperatureUnit::new(scale, maximum).convert_to(temperature.unit()),
+        });
+    }
+    Ok(t)
+}
+
+/// Rejects winds faster than a formula applies to
+fn check_wind_maximum(
+    wind_speed: f64,
+    calculator: Calculator,
+    maximum: f64,
+) -> Result<(), WeatherError> {
+    if wind_speed > maximum {
+        return Err(WeatherError::TooWindy {
+            calculator,
+            maximum,
+        });
+    }
+    Ok(())
+}
+
+/// Checks the result of a formula, which may leave the range it was fitted on, then converts it
+///
+/// # Arguments
+///
+/// * `unit` - The scale the formula works in
+/// * `value` - What the formula gave
+/// * `to` - The scale of the result
+///
+/// # Returns
+///
+/// * The result in the `to` scale, or why it cannot physically exist
+fn in_scale(
+    unit: ConversionUnit,
+    value: f64,
+    to: ConversionUnit,
+) -> Result<TemperatureUnit, WeatherError> {
+    TemperatureUnit::try_new(unit, value)
+        .map(|result| result.convert_to(to))
+        .map_err(WeatherError::Temperature)
+}
+
+/// Rejects temperatures below absolute zero or not finite
+fn check_temperature(temperature: TemperatureUnit) -> Result<(), WeatherError> {
+    temperature.validate().map_err(WeatherError::Temperature)
+}
+
+/// Rejects humidities that are not above 0 and up to 100 %
+fn check_humidity(humidity: f64) -> Result<(), WeatherError> {
+    if humidity > 0.0 && humidity <= 100.0 {
+        Ok(())
+    } else {
+        Err(WeatherError::Humidity(humidity))
+    }
+}
+
+/// Rejects negative or non-finite wind speeds
+fn check_wind_speed(wind_speed: f64) -> Result<(), WeatherError> {
+    if wind_speed.is_finite() && wind_speed >= 0.0 {
+        Ok(())
+    } else {
+        Err(WeatherError::WindSpeed(wind_speed))
+    }
+}
@@ -0,0 +1,118 @@
+//! Tests for the threshold alerts, on their own and on top of the batch and watch modes.
+
+use temperature::alert::{AlertEventKind, AlertMonitor, AlertRule, AlertRuleErrorKind, Direction};
+use temperature::batch::{BatchOptions, ColumnSelector, convert_delimited_with_alerts};
+use temperature::follow::{FollowOptions, follow_with_alerts};
+use temperature::output::OutputFormat;
+use temperature::{ConversionUnit, TemperatureUnit};
+
+/// Feeds Celsius readings to a rule and returns the line and kind of every event
+fn events(rule: &str, celsius: &[f64]) -> Vec<(usize, AlertEventKind)> {
+    let mut monitor = AlertMonitor::new(vec![rule.parse().unwrap()], Box::new(Vec::new()));
+    let mut events = Vec::new();
+    for (index, value) in celsius.iter().enumerate() {
+        let reading = TemperatureUnit::Celsius(*value);
+        for event in monitor.observe(index + 1, None, reading).unwrap() {
+            events.push((event.line, event.kind));
+        }
+    }
+    events
+}
+
+#[test]
+fn rules_are_parsed_in_any_scale() {
+    let rule: AlertRule = "Above 100 °F for 3 readings hysteresis 0.5K"
+        .parse()
+        .unwrap();
+    assert_eq!(rule.direction, Direction::Above);
+    assert_eq!(rule.threshold, TemperatureUnit::Fahrenheit(100.0));
+    assert_eq!(rule.readings, 3);
+    assert_eq!(rule.hysteresis.to_kelvin(), 0.5);
+    assert_eq!(rule.to_string(), "above 100 °F for 3 readings");
+
+    let rule: AlertRule = "<0C".parse().unwrap();
+    assert_eq!(rule.direction, Direction::Below);
+    assert_eq!(rule.readings, 1);
+    assert_eq!(rule.hysteresis.to_kelvin(), 1.0);
+
+    let kinds = [
+        "100F",
+        "above 100",
+        "above 100F for 0",
+        "above 100F hysteresis x",
+    ]
+    .map(|input| input.parse::<AlertRule>().unwrap_err().kind);
+    assert_eq!(kinds[0], AlertRuleErrorKind::MissingDirection);
+    assert!(matches!(kinds[1], AlertRuleErrorKind::Threshold(_)));
+    assert_eq!(kinds[2], AlertRuleErrorKind::InvalidCount("0".to_string()));
+    assert!(matches!(kinds[3], AlertRuleErrorKind::Hysteresis(_)));
+}
+
+#[test]
+fn consecutive_readings_raise_and_hysteresis_clears() {
+    // Broken streaks never raise
+    assert!(events("above 30C for 3", &[31.0, 32.0, 29.0, 31.0, 31.0, 20.0]).is_empty());
+    // Flapping just below the threshold keeps the alert up
+    assert_eq!(
+        events(
+            "above 30C for 2",
+            &[31.0, 31.0, 29.5, 30.5, 29.5, 28.5, 28.0]
+        ),
+        [(2, AlertEventKind::Raised), (7, AlertEventKind::Cleared)]
+    );
+    // A rule in Fahrenheit on Celsius readings, compared in kelvins
+    assert_eq!(
+        events("below 32F hysteresis 1.8F", &[-0.5, 0.5, 1.5, -2.0]),
+        [
+            (1, AlertEventKind::Raised),
+            (3, AlertEventKind::Cleared),
+            (4, AlertEventKind::Raised)
+        ]
+    );
+}
+
+#[test]
+fn events_are_logged_from_the_watch_and_batch_modes() {
+    let rules = vec!["above 100F".parse().unwrap()];
+
+    let mut log = Vec::new();
+    let mut monitor = AlertMonitor::new(rules.clone(), Box::new(&mut log))
+        .with_format(OutputFormat::JsonLines)
+        .with_precision(1);
+    let options = FollowOptions::new(Some(ConversionUnit::ToCelsius), ConversionUnit::ToKelvin);
+    let input = "12:00 20\n12:01 40\n";
+    follow_with_alerts(
+        input.as_bytes(),
+        Vec::new(),
+        Vec::new(),
+        &options,
+        Some(&mut monitor),
+    )
+    .unwrap();
+    drop(monitor);
+    assert_eq!(
+        String::from_utf8(log).unwrap(),
+        concat!(
+            r#"{"event":"raised","rule":"above 100 °F","line":2,"timestamp":"12:01","#,
+            r#""reading":{"value":104.0,"unit":"F"}}"#,
+            "\n"
+        )
+    );
+
+    let mut log = Vec::new();
+    let mut monitor = AlertMonitor::new(rules, Box::new(&mut log));
+    let options = BatchOptions::new(
+        ColumnSelector::Index(1),
+        ConversionUnit::ToCelsius,
+        ConversionUnit::ToFahrenheit,
+    );
+    let input = "temp\n40\n20\n";
+    convert_delimited_with_alerts(input.as_bytes(), Vec::new(), &options, Some(&mut monitor))
+        .unwrap();
+    drop(monitor);
+    assert_eq!(
+        String::from_utf8(log).unwrap(),
+        "alert raised at line 2: 104.00 °F, above 100 °F\n\
+         alert cleared at line 3: 68.00 °F, above 100 °F\n"
+    );
+}
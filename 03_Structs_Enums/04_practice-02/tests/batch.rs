@@ -0,0 +1,97 @@
+//! Tests for the batch conversion of CSV/TSV input.
+
+use temperature::ConversionUnit;
+use temperature::batch::{
+    BatchError, BatchOptions, ColumnMode, ColumnSelector, RowError, RowErrorKind,
+    convert_delimited, join_record, split_record,
+};
+
+/// Runs a batch conversion over `input` and returns the output and the skipped rows
+fn run(input: &str, options: &BatchOptions) -> (String, Vec<RowError>) {
+    let mut output = Vec::new();
+    let report = convert_delimited(input.as_bytes(), &mut output, options).unwrap();
+    (String::from_utf8(output).unwrap(), report.errors)
+}
+
+#[test]
+fn named_column_is_appended_in_the_target_unit() {
+    let options = BatchOptions::new(
+        ColumnSelector::parse("temp"),
+        ConversionUnit::ToCelsius,
+        ConversionUnit::ToFahrenheit,
+    );
+    let (output, errors) = run("sensor,temp\na,0\nb,100\n", &options);
+    assert_eq!(output, "sensor,temp,temp (°F)\na,0,32.00\nb,100,212.00\n");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn indexed_column_is_replaced_in_tab_separated_input() {
+    let mut options = BatchOptions::new(
+        ColumnSelector::parse("2"),
+        ConversionUnit::ToKelvin,
+        ConversionUnit::ToCelsius,
+    );
+    options.delimiter = '\t';
+    options.has_header = false;
+    options.mode = ColumnMode::Replace;
+    let (output, _) = run("a\t273.15\nb\t373.15\n", &options);
+    assert_eq!(output, "a\t0.00\nb\t100.00\n");
+}
+
+#[test]
+fn bad_rows_are_reported_with_line_numbers_and_kept() {
+    let options = BatchOptions::new(
+        ColumnSelector::Index(2),
+        ConversionUnit::ToCelsius,
+        ConversionUnit::ToKelvin,
+    );
+    let (output, errors) = run("id,temp\n1,oops\n2\n3,0\n", &options);
+    assert_eq!(output, "id,temp,temp (K)\n1,oops,\n2,,\n3,0,273.15\n");
+    assert_eq!(
+        errors,
+        vec![
+            RowError {
+                line: 2,
+                kind: RowErrorKind::InvalidValue("oops".to_string()),
+            },
+            RowError {
+                line: 3,
+                kind: RowErrorKind::MissingColumn,
+            },
+        ]
+    );
+}
+
+#[test]
+fn short_rows_are_padded_to_the_header_before_appending() {
+    let options = BatchOptions::new(
+        ColumnSelector::parse("temp"),
+        ConversionUnit::ToCelsius,
+        ConversionUnit::ToFahrenheit,
+    );
+    let (output, errors) = run("a,temp,b\n1,20\n2,hot\n3,0,x\n", &options);
+    assert_eq!(
+        output,
+        "a,temp,b,temp (°F)\n1,20,,68.00\n2,hot,,\n3,0,x,32.00\n"
+    );
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn unknown_column_names_stop_the_batch() {
+    let options = BatchOptions::new(
+        ColumnSelector::parse("missing"),
+        ConversionUnit::ToCelsius,
+        ConversionUnit::ToKelvin,
+    );
+    let result = convert_delimited("id,temp\n".as_bytes(), Vec::new(), &options);
+    assert!(matches!(result, Err(BatchError::ColumnNotFound(name)) if name == "missing"));
+}
+
+#[test]
+fn quoted_fields_survive_a_round_trip() {
+    let fields = split_record(r#"1,"Lab, room ""A""",20"#, ',');
+    assert_eq!(fields, vec!["1", r#"Lab, room "A""#, "20"]);
+    assert_eq!(join_record(&fields, ','), r#"1,"Lab, room ""A""",20"#);
+}
@@ -0,0 +1,64 @@
+/* Links against libtemperature.a and checks the C ABI; run by `tests/ffi.rs`. */
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "temperature.h"
+
+static int failures = 0;
+
+static void check(int condition, const char *what) {
+    if (!condition) {
+        fprintf(stderr, "failed: %s\n", what);
+        failures++;
+    }
+}
+
+int main(void) {
+    double result = 0.0;
+
+    check(temperature_convert(100.0, TEMPERATURE_SCALE_CELSIUS, TEMPERATURE_SCALE_FAHRENHEIT,
+                              &result) == TEMPERATURE_OK,
+          "100 C converts");
+    check(fabs(result - 212.0) < 1e-9, "100 C is 212 F");
+
+    check(temperature_convert(0.0, TEMPERATURE_SCALE_KELVIN, TEMPERATURE_SCALE_RANKINE,
+                              &result) == TEMPERATURE_OK,
+          "0 K converts");
+    check(fabs(result) < 1e-9, "0 K is 0 R");
+
+    result = 42.0;
+    check(temperature_convert(-300.0, TEMPERATURE_SCALE_CELSIUS, TEMPERATURE_SCALE_KELVIN,
+                              &result) == TEMPERATURE_BELOW_ABSOLUTE_ZERO,
+          "-300 C is rejected");
+    check(result == 42.0, "a rejected conversion leaves the result alone");
+    check(temperature_convert(NAN, TEMPERATURE_SCALE_CELSIUS, TEMPERATURE_SCALE_KELVIN,
+                              &result) == TEMPERATURE_NOT_FINITE,
+          "NaN is rejected");
+    check(temperature_convert(1.7e308, TEMPERATURE_SCALE_KELVIN, TEMPERATURE_SCALE_FAHRENHEIT,
+                              &result) == TEMPERATURE_NOT_FINITE,
+          "a result too large for a double is rejected");
+    check(result == 42.0, "an overflowing conversion leaves the result alone");
+    check(temperature_convert(1.0, 99, TEMPERATURE_SCALE_KELVIN, &result) ==
+              TEMPERATURE_UNKNOWN_SCALE,
+          "scale 99 is unknown");
+    check(temperature_convert(1.0, TEMPERATURE_SCALE_CELSIUS, TEMPERATURE_SCALE_KELVIN, NULL) ==
+              TEMPERATURE_NULL_POINTER,
+          "a NULL result is rejected");
+
+    check(temperature_absolute_zero(TEMPERATURE_SCALE_FAHRENHEIT, &result) == TEMPERATURE_OK,
+          "Fahrenheit has an absolute zero");
+    check(fabs(result + 459.67) < 1e-9, "absolute zero is -459.67 F");
+
+    check(strcmp(temperature_scale_code(TEMPERATURE_SCALE_REAUMUR), "Re") == 0,
+          "the code of Reaumur is Re");
+    check(temperature_scale_code(-1) == NULL, "scale -1 has no code");
+    check(strcmp(temperature_status_message(TEMPERATURE_NOT_FINITE),
+                 "the value or its result is not a finite number") == 0,
+          "statuses have messages");
+
+    if (failures == 0) {
+        puts("ok");
+    }
+    return failures == 0 ? 0 : 1;
+}
@@ -0,0 +1,363 @@
+//! Tests for the command line argument parser of the converter.
+
+use std::path::PathBuf;
+
+use temperature::ConversionUnit;
+use temperature::cli::{CliError, Command, MenuArgs, parse_args, parse_args_with_registry};
+use temperature::follow::MalformedLines;
+use temperature::locale::Locale;
+use temperature::output::OutputFormat;
+use temperature::registry::Registry;
+
+/// Parses the arguments and unwraps a conversion command
+fn parse_convert(args: &[&str]) -> (ConversionUnit, ConversionUnit, Vec<f64>) {
+    match parse_args(args) {
+        Ok(Command::Convert(cli_args)) => {
+            (cli_args.from(), cli_args.to(), cli_args.values().to_vec())
+        }
+        other => panic!("expected a conversion, got {other:?}"),
+    }
+}
+
+#[test]
+fn unit_after_the_value_is_the_source_unit() {
+    let (from, to, values) = parse_convert(&["98.6", "F", "--to", "C"]);
+    assert_eq!(from, ConversionUnit::ToFahrenheit);
+    assert_eq!(to, ConversionUnit::ToCelsius);
+    assert_eq!(values, vec![98.6]);
+}
+
+#[test]
+fn flags_accept_several_values_including_negative_ones() {
+    let (from, to, values) = parse_convert(&["--from", "K", "--to", "F", "0", "100", "-1.5"]);
+    assert_eq!(from, ConversionUnit::ToKelvin);
+    assert_eq!(to, ConversionUnit::ToFahrenheit);
+    assert_eq!(values, vec![0.0, 100.0, -1.5]);
+}
+
+#[test]
+fn units_can_be_written_as_names_or_symbols() {
+    let (from, to, _) = parse_convert(&["-f", "celsius", "-t", "°Rø", "20"]);
+    assert_eq!(from, ConversionUnit::ToCelsius);
+    assert_eq!(to, ConversionUnit::ToRomer);
+}
+
+#[test]
+fn help_flag_wins_over_everything_else() {
+    assert_eq!(parse_args(&["1", "--help"]), Ok(Command::Help));
+}
+
+#[test]
+fn invalid_arguments_are_reported() {
+    assert_eq!(parse_args(&["10", "--to", "C"]), Err(CliError::MissingFrom));
+    assert_eq!(parse_args(&["--from", "F", "10"]), Err(CliError::MissingTo));
+    assert_eq!(
+        parse_args(&["F", "--to", "C"]),
+        Err(CliError::MissingValues)
+    );
+    assert_eq!(
+        parse_args(&["10", "F", "--to"]),
+        Err(CliError::MissingFlagValue("--to".to_string()))
+    );
+    assert_eq!(
+        parse_args(&["10", "F", "C", "--to", "K"]),
+        Err(CliError::DuplicateFrom)
+    );
+    assert_eq!(
+        parse_args(&["10", "--verbose"]),
+        Err(CliError::UnknownFlag("--verbose".to_string()))
+    );
+    assert!(matches!(
+        parse_args(&["10", "X", "--to", "C"]),
+        Err(CliError::UnknownUnit(_))
+    ));
+}
+
+#[test]
+fn precision_is_capped_in_every_mode() {
+    let too_large = Err(CliError::PrecisionTooLarge(100_000));
+    assert_eq!(
+        parse_args(&["1", "C", "-t", "F", "-p", "100000"]),
+        too_large
+    );
+    let table = [
+        "--table", "C", "-t", "F", "--start", "0", "--end", "1", "--step", "1",
+    ];
+    assert_eq!(
+        parse_args(&[&table[..], &["--precision", "100000"]].concat()),
+        too_large
+    );
+    assert_eq!(
+        parse_args_with_registry(
+            &["1", "km", "-t", "mi", "-p", "100000"],
+            Locale::En,
+            &Registry::builtin()
+        ),
+        too_large
+    );
+    assert!(matches!(
+        parse_args(&["1", "C", "-t", "F", "-p", "17"]),
+        Ok(Command::Convert(cli_args)) if cli_args.precision() == 17
+    ));
+}
+
+#[test]
+fn expressions_are_accepted_as_arguments() {
+    let (from, to, values) = parse_convert(&["300", "kelvin", "in", "fahrenheit"]);
+    assert_eq!(from, ConversionUnit::ToKelvin);
+    assert_eq!(to, ConversionUnit::ToFahrenheit);
+    assert_eq!(values, vec![300.0]);
+    assert!(matches!(
+        parse_args(&["98.6Q in C"]),
+        Err(CliError::Expression { .. })
+    ));
+}
+
+#[test]
+fn expressions_take_the_target_and_output_flags() {
+    let (from, to, values) = parse_convert(&["25c"]);
+    assert_eq!(from, ConversionUnit::ToCelsius);
+    assert_eq!(to, ConversionUnit::ToFahrenheit);
+    assert_eq!(values, vec![25.0]);
+    assert_eq!(parse_convert(&["300K"]).1, ConversionUnit::ToCelsius);
+    assert_eq!(
+        parse_convert(&["25c", "--to", "K"]).1,
+        ConversionUnit::ToKelvin
+    );
+
+    let args = ["98,6F in C", "-o", "json", "-p", "1", "--lang", "es"];
+    let Ok(Command::Convert(cli_args)) = parse_args(&args) else {
+        panic!("expected a conversion");
+    };
+    assert_eq!(cli_args.from(), ConversionUnit::ToFahrenheit);
+    assert_eq!(cli_args.values(), [98.6]);
+    assert_eq!(cli_args.output(), OutputFormat::Json);
+    assert_eq!(cli_args.precision(), 1);
+    assert_eq!(cli_args.locale(), Locale::Es);
+    assert_eq!(
+        parse_args(&["98.6F in C", "--to", "K"]),
+        Err(CliError::DuplicateTo)
+    );
+}
+
+#[test]
+fn table_flags_build_table_options() {
+    let Ok(Command::Table(options)) = parse_args(&[
+        "--table", "--from", "F", "--start", "-40", "--end", "212", "--step", "20", "--format",
+        "md", "-p", "1",
+    ]) else {
+        panic!("expected a table");
+    };
+    assert_eq!(options.from, ConversionUnit::ToFahrenheit);
+    assert_eq!(
+        (options.start, options.end, options.step),
+        (-40.0, 212.0, 20.0)
+    );
+    assert_eq!(options.targets.len(), ConversionUnit::ALL.len() - 1);
+    assert_eq!(options.precision, 1);
+    assert_eq!(
+        parse_args(&["--table", "-f", "C", "--start", "0", "--end", "1"]),
+        Err(CliError::MissingTableValue("--step"))
+    );
+    assert_eq!(
+        parse_args(&["10", "C", "--to", "F", "--step", "1"]),
+        Err(CliError::TableOnly("--step".to_string()))
+    );
+}
+
+#[test]
+fn output_flag_selects_the_format() {
+    let Ok(Command::Convert(cli_args)) = parse_args(&["0", "C", "--to", "F", "-o", "jsonl"]) else {
+        panic!("expected a conversion");
+    };
+    assert_eq!(cli_args.output(), OutputFormat::JsonLines);
+    assert_eq!(
+        parse_args(&["--output", "json"]),
+        Ok(Command::Menu(MenuArgs {
+            output: OutputFormat::Json,
+            ..MenuArgs::default()
+        }))
+    );
+    assert!(matches!(
+        parse_args(&["--output", "yaml"]),
+        Err(CliError::UnknownOutput(_))
+    ));
+    assert_eq!(
+        parse_args(&[
+            "-b", "in.csv", "-c", "1", "-f", "C", "-t", "F", "-o", "json"
+        ]),
+        Err(CliError::OutputWithMode("--batch"))
+    );
+}
+
+#[test]
+fn history_flags_open_the_menu() {
+    let Ok(Command::Menu(menu_args)) = parse_args(&["--keep-history", "--history", "out.json"])
+    else {
+        panic!("expected the menu");
+    };
+    assert!(menu_args.keep_history);
+    assert_eq!(menu_args.history, Some(PathBuf::from("out.json")));
+    assert_eq!(
+        parse_args(&["10", "C", "-t", "F", "--keep-history"]),
+        Err(CliError::MenuOnly("--keep-history".to_string()))
+    );
+}
+
+#[test]
+fn units_flag_opens_the_unit_menu_without_history() {
+    let Ok(Command::Menu(menu_args)) = parse_args(&["--units", "-o", "jsonl"]) else {
+        panic!("expected the menu");
+    };
+    assert!(menu_args.units);
+    assert_eq!(
+        parse_args(&["--units", "--keep-history"]),
+        Err(CliError::HistoryWithUnits("--keep-history".to_string()))
+    );
+}
+
+#[test]
+fn line_flag_keeps_the_line_menu() {
+    assert_eq!(
+        parse_args(&["--line", "--lang", "es"]),
+        Ok(Command::Menu(MenuArgs {
+            line: true,
+            locale: Locale::Es,
+            ..MenuArgs::default()
+        }))
+    );
+    assert_eq!(
+        parse_args(&["10", "C", "-t", "F", "--line"]),
+        Err(CliError::MenuOnly("--line".to_string()))
+    );
+}
+
+#[test]
+fn registry_units_are_converted_when_temperatures_do_not_match() {
+    let registry = Registry::builtin();
+    let Ok(Command::ConvertUnits(unit_args)) =
+        parse_args_with_registry(&["3", "ft", "--to", "m", "6"], Locale::En, &registry)
+    else {
+        panic!("expected a unit conversion");
+    };
+    assert_eq!(
+        (unit_args.from.as_str(), unit_args.to.as_str()),
+        ("ft", "m")
+    );
+    assert_eq!(unit_args.values, vec![3.0, 6.0]);
+    assert!(matches!(
+        parse_args_with_registry(&["1", "kg", "to", "m"], Locale::En, &registry),
+        Err(CliError::UnitConversion(_))
+    ));
+    // Temperatures keep their own parser and errors
+    assert!(matches!(
+        parse_args_with_registry(&["10", "C", "-t", "F"], Locale::En, &registry),
+        Ok(Command::Convert(_))
+    ));
+    assert!(matches!(
+        parse_args_with_registry(&["10", "parsec", "-t", "F"], Locale::En, &registry),
+        Err(CliError::UnknownUnit(_))
+    ));
+}
+
+#[test]
+fn stats_flags_build_stats_arguments() {
+    let Ok(Command::Stats(stats_args)) = parse_args(&["--stats", "-f", "C", "--input", "-"]) else {
+        panic!("expected statistics");
+    };
+    assert_eq!(stats_args.to, ConversionUnit::ToCelsius);
+    assert_eq!(stats_args.input, None);
+    assert!(stats_args.values.is_empty());
+    assert_eq!(
+        parse_args(&["--stats", "--table", "-f", "C"]),
+        Err(CliError::ConflictingModes("--stats", "--table"))
+    );
+    assert_eq!(
+        parse_args(&["--input", "a.txt", "-f", "C", "-t", "F", "1"]),
+        Err(CliError::StatsOnly("--input".to_string()))
+    );
+}
+
+#[test]
+fn follow_flags_build_the_watch_mode() {
+    let args = ["--follow", "-", "--to", "F", "--on-malformed", "stop"];
+    let Ok(Command::Follow(follow_args)) = parse_args(&args) else {
+        panic!("expected the watch mode");
+    };
+    assert_eq!(follow_args.input, None);
+    assert_eq!(follow_args.options.from, None);
+    assert_eq!(follow_args.options.on_malformed, MalformedLines::Stop);
+    assert_eq!(
+        parse_args(&["10", "C", "-t", "F", "--on-malformed", "skip"]),
+        Err(CliError::FollowOnly("--on-malformed".to_string()))
+    );
+    assert_eq!(
+        parse_args(&["--follow", "log.txt", "--stats", "-f", "C"]),
+        Err(CliError::ConflictingModes("--follow", "--stats"))
+    );
+    assert_eq!(
+        parse_args(&["--follow", "log.txt", "-o", "jsonl"]),
+        Err(CliError::MissingTo)
+    );
+}
+
+#[test]
+fn alert_flags_go_with_the_watch_and_batch_modes() {
+    let args = [
+        "--follow",
+        "-",
+        "-t",
+        "F",
+        "--lang",
+        "es",
+        "--alert",
+        "above 37,5C for 2",
+        "--alert",
+        "below 0C",
+        "--alert-log",
+        "alerts.jsonl",
+    ];
+    let Ok(Command::Follow(follow_args)) = parse_args(&args) else {
+        panic!("expected the watch mode");
+    };
+    assert_eq!(follow_args.alerts.rules.len(), 2);
+    assert_eq!(follow_args.alerts.rules[0].readings, 2);
+    assert_eq!(follow_args.alerts.log, Some(PathBuf::from("alerts.jsonl")));
+    assert_eq!(
+        parse_args(&["10", "C", "-t", "F", "--alert", "above 5C"]),
+        Err(CliError::AlertOnly("--alert".to_string()))
+    );
+    assert!(matches!(
+        parse_args(&[
+            "-b", "in.csv", "-c", "1", "-f", "C", "-t", "F", "--alert", "hot"
+        ]),
+        Err(CliError::InvalidAlert(_))
+    ));
+}
+
+#[test]
+fn exact_flag_keeps_values_as_typed() {
+    let args = ["--exact", "0,1", "1/3", "C", "--to", "F", "--lang", "es"];
+    let Ok(Command::ConvertExact(exact_args)) = parse_args(&args) else {
+        panic!("expected an exact conversion");
+    };
+    let values: Vec<String> = exact_args.values.iter().map(|v| v.to_string()).collect();
+    assert_eq!(values, ["0.1", "1/3"]);
+    let conversions = exact_args.conversions();
+    assert_eq!(
+        conversions[0].as_ref().unwrap().1.value().to_string(),
+        "32.18"
+    );
+    assert_eq!(
+        parse_args(&["1/3", "C", "--to", "F"]),
+        Err(CliError::ExactOnly("1/3".to_string()))
+    );
+    assert_eq!(
+        parse_args(&["--exact", "--stats", "-f", "C", "1"]),
+        Err(CliError::ConflictingModes("--exact", "--stats"))
+    );
+    assert!(matches!(
+        parse_args(&["--exact", "nan", "C", "--to", "F"]),
+        Err(CliError::InvalidExactNumber(_))
+    ));
+}
@@ -0,0 +1,154 @@
+//! Tests for every pair of temperature scales in the `temperature` library.
+
+use temperature::formulas::{
+    celsius_to_fahrenheit, celsius_to_kelvin, fahrenheit_to_celsius, fahrenheit_to_kelvin,
+    kelvin_to_celsius, kelvin_to_fahrenheit,
+};
+use temperature::{ConversionRequest, ConversionUnit, TemperatureUnit};
+
+/// Tolerance used when comparing floating point results
+const EPSILON: f64 = 1e-9;
+
+/// Asserts that two temperatures share a unit and have (almost) the same value
+fn assert_temperature_eq(actual: TemperatureUnit, expected: TemperatureUnit) {
+    assert_eq!(actual.unit(), expected.unit());
+    assert!(
+        (actual.value() - expected.value()).abs() < EPSILON,
+        "expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[test]
+fn celsius_to_fahrenheit_converts_reference_points() {
+    assert!((celsius_to_fahrenheit(0.0) - 32.0).abs() < EPSILON);
+    assert!((celsius_to_fahrenheit(100.0) - 212.0).abs() < EPSILON);
+    assert!((celsius_to_fahrenheit(-40.0) - -40.0).abs() < EPSILON);
+    assert_temperature_eq(
+        TemperatureUnit::Celsius(37.0).convert_to(ConversionUnit::ToFahrenheit),
+        TemperatureUnit::Fahrenheit(98.6),
+    );
+}
+
+#[test]
+fn celsius_to_kelvin_converts_reference_points() {
+    assert!((celsius_to_kelvin(0.0) - 273.15).abs() < EPSILON);
+    assert!((celsius_to_kelvin(-273.15) - 0.0).abs() < EPSILON);
+    assert_temperature_eq(
+        TemperatureUnit::Celsius(100.0).convert_to(ConversionUnit::ToKelvin),
+        TemperatureUnit::Kelvin(373.15),
+    );
+}
+
+#[test]
+fn fahrenheit_to_celsius_converts_reference_points() {
+    assert!((fahrenheit_to_celsius(32.0) - 0.0).abs() < EPSILON);
+    assert!((fahrenheit_to_celsius(212.0) - 100.0).abs() < EPSILON);
+    assert_temperature_eq(
+        TemperatureUnit::Fahrenheit(-40.0).convert_to(ConversionUnit::ToCelsius),
+        TemperatureUnit::Celsius(-40.0),
+    );
+}
+
+#[test]
+fn fahrenheit_to_kelvin_converts_reference_points() {
+    assert!((fahrenheit_to_kelvin(32.0) - 273.15).abs() < EPSILON);
+    assert!((fahrenheit_to_kelvin(-459.67) - 0.0).abs() < EPSILON);
+    assert_temperature_eq(
+        TemperatureUnit::Fahrenheit(212.0).convert_to(ConversionUnit::ToKelvin),
+        TemperatureUnit::Kelvin(373.15),
+    );
+}
+
+#[test]
+fn kelvin_to_celsius_converts_reference_points() {
+    assert!((kelvin_to_celsius(273.15) - 0.0).abs() < EPSILON);
+    assert!((kelvin_to_celsius(0.0) - -273.15).abs() < EPSILON);
+    assert_temperature_eq(
+        TemperatureUnit::Kelvin(373.15).convert_to(ConversionUnit::ToCelsius),
+        TemperatureUnit::Celsius(100.0),
+    );
+}
+
+#[test]
+fn kelvin_to_fahrenheit_converts_reference_points() {
+    assert!((kelvin_to_fahrenheit(273.15) - 32.0).abs() < EPSILON);
+    assert!((kelvin_to_fahrenheit(0.0) - -459.67).abs() < EPSILON);
+    assert_temperature_eq(
+        TemperatureUnit::Kelvin(373.15).convert_to(ConversionUnit::ToFahrenheit),
+        TemperatureUnit::Fahrenheit(212.0),
+    );
+}
+
+#[test]
+fn converting_to_the_same_scale_keeps_the_value() {
+    for temperature in [
+        TemperatureUnit::Celsius(21.5),
+        TemperatureUnit::Fahrenheit(70.7),
+        TemperatureUnit::Kelvin(294.65),
+    ] {
+        assert_eq!(temperature.convert_to(temperature.unit()), temperature);
+    }
+}
+
+#[test]
+fn new_value_and_unit_round_trip() {
+    let temperature = TemperatureUnit::new(ConversionUnit::ToKelvin, 300.0);
+    assert_eq!(temperature, TemperatureUnit::Kelvin(300.0));
+    assert_eq!(temperature.value(), 300.0);
+    assert_eq!(temperature.unit(), ConversionUnit::ToKelvin);
+}
+
+#[test]
+fn conversion_request_returns_the_converted_value() {
+    let request = ConversionRequest::new(
+        TemperatureUnit::Celsius(100.0),
+        ConversionUnit::ToFahrenheit,
+    );
+    assert!((request.convert() - 212.0).abs() < EPSILON);
+    assert_eq!(request.from(), TemperatureUnit::Celsius(100.0));
+    assert_eq!(request.to(), ConversionUnit::ToFahrenheit);
+}
+
+#[test]
+fn historic_scales_match_reference_points() {
+    // Freezing and boiling points of water in every scale
+    let freezing = TemperatureUnit::Celsius(0.0);
+    let boiling = TemperatureUnit::Celsius(100.0);
+    let expected = [
+        (ConversionUnit::ToRankine, 491.67, 671.67),
+        (ConversionUnit::ToReaumur, 0.0, 80.0),
+        (ConversionUnit::ToDelisle, 150.0, 0.0),
+        (ConversionUnit::ToNewton, 0.0, 33.0),
+        (ConversionUnit::ToRomer, 7.5, 60.0),
+    ];
+    for (unit, at_freezing, at_boiling) in expected {
+        assert_temperature_eq(
+            freezing.convert_to(unit),
+            TemperatureUnit::new(unit, at_freezing),
+        );
+        assert_temperature_eq(
+            boiling.convert_to(unit),
+            TemperatureUnit::new(unit, at_boiling),
+        );
+    }
+}
+
+#[test]
+fn every_scale_pair_round_trips_through_kelvin() {
+    for from in ConversionUnit::ALL {
+        for to in ConversionUnit::ALL {
+            let original = TemperatureUnit::new(from, 42.0);
+            let back = original.convert_to(to).convert_to(from);
+            assert_temperature_eq(back, original);
+        }
+    }
+}
+
+#[test]
+fn every_scale_agrees_on_absolute_zero() {
+    let absolute_zero = TemperatureUnit::Kelvin(0.0);
+    for unit in ConversionUnit::ALL {
+        let converted = absolute_zero.convert_to(unit);
+        assert!(converted.to_kelvin().abs() < EPSILON, "{converted:?}");
+    }
+}
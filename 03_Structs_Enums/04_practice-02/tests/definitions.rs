@@ -0,0 +1,114 @@
+//! Tests for user-defined units read from a definitions file.
+
+use temperature::cli::{Command, MenuArgs, parse_args};
+use temperature::definitions::{self, DefinitionError, DefinitionErrorKind};
+use temperature::registry::{Dimension, Registry, RegistryError};
+use temperature::unit_session::UnitSession;
+
+/// A temperature scale written with the converter's constants, and a length
+const UNITS: &str = r#"
+# Gas mark 1 is 275 °F and every mark adds 25 °F
+[gas mark]
+symbol = "GM"
+aliases = ["gasmark", "mark"]
+factor = 25 / SCALE_FACTOR
+offset = 250 / SCALE_FACTOR + KELVIN_OFFSET - FAHRENHEIT_OFFSET / SCALE_FACTOR
+
+[fathom]
+dimension = length ; INI comments work too
+symbol = ftm
+factor = 1.8288
+"#;
+
+/// Parses the text and registers it over the built-in units
+fn load(text: &str) -> Result<Registry, DefinitionError> {
+    let mut registry = Registry::builtin();
+    definitions::register(&mut registry, &definitions::parse(text)?)?;
+    Ok(registry)
+}
+
+#[test]
+fn definitions_are_registered_next_to_the_built_in_units() {
+    let registry = load(UNITS).unwrap();
+    assert!((registry.convert(1.0, "mark", "F").unwrap() - 275.0).abs() < 1e-9);
+    assert!((registry.convert(4.0, "GM", "C").unwrap() - 176.666_666_666).abs() < 1e-6);
+    assert!((registry.convert(1.0, "fathom", "ft").unwrap() - 6.0).abs() < 1e-9);
+    let gas_mark = registry.find("gasmark").unwrap();
+    assert_eq!(gas_mark.name, "gas mark");
+    assert_eq!(gas_mark.dimension, Dimension::Temperature);
+}
+
+#[test]
+fn custom_scales_are_listed_in_the_menu_of_the_definitions() {
+    // On its own, `--definitions` opens the menu that is built from the registry
+    assert_eq!(
+        parse_args(&["--definitions", "units.ini"]),
+        Ok(Command::Menu(MenuArgs {
+            units: true,
+            ..MenuArgs::default()
+        }))
+    );
+    let input = "1\n9\n1\n4\n0\n";
+    let mut session =
+        UnitSession::new(input.as_bytes(), Vec::new()).with_registry(load(UNITS).unwrap());
+    session.run().unwrap();
+    let transcript = String::from_utf8(session.into_output()).unwrap();
+    assert!(transcript.contains("Convert from:\n1. Celsius (°C)\n"));
+    assert!(transcript.contains("8. Rømer (°Rø)\n9. gas mark (GM)\n"));
+    assert!(transcript.contains("4.00 GM is 176.67 °C\n"));
+}
+
+#[test]
+fn numbers_accept_signs_exponents_and_constants() {
+    let text =
+        "[cold]\nsymbol = cold\nfactor = 2 * -0.5 + 3e-1 - -1.7\noffset = KELVIN_BOILING_POINT\n";
+    let unit = &definitions::parse(text).unwrap()[0].unit;
+    assert!((unit.factor - 1.0).abs() < 1e-12);
+    assert_eq!(unit.offset, 373.15);
+}
+
+#[test]
+fn mistakes_are_reported_with_their_line() {
+    let error = |text: &str| definitions::parse(text).unwrap_err();
+    assert_eq!(
+        error("symbol = X\n"),
+        DefinitionError {
+            line: 1,
+            kind: DefinitionErrorKind::OutsideSection
+        }
+    );
+    assert_eq!(
+        error("[x]\nsymbol = X\nfactor = fast\n").kind,
+        DefinitionErrorKind::InvalidNumber("fast".to_string())
+    );
+    assert_eq!(
+        error("[x]\nsymbol = X\n\n[y]\n").kind,
+        DefinitionErrorKind::MissingKey("factor")
+    );
+    assert_eq!(
+        error("[x]\nsymbol = X\nsymbol = Y\n").kind,
+        DefinitionErrorKind::DuplicateKey("symbol".to_string())
+    );
+    assert_eq!(error("[x]\nsymbol = X\ndimension = time\n").line, 3);
+}
+
+#[test]
+fn zero_factors_and_taken_symbols_are_rejected() {
+    assert!(matches!(
+        load("[flat]\nsymbol = flat\nfactor = 0\n"),
+        Err(DefinitionError {
+            line: 1,
+            kind: DefinitionErrorKind::Registry(RegistryError::InvalidFactor { .. })
+        })
+    ));
+    let error = load("[a]\nsymbol = A\nfactor = 1\n\n[b]\nsymbol = B\naliases = a\nfactor = 1\n")
+        .unwrap_err();
+    assert_eq!(error.line, 5);
+    assert_eq!(
+        error.kind,
+        DefinitionErrorKind::Registry(RegistryError::DuplicateName {
+            name: "a".to_string(),
+            existing: "A".to_string(),
+        })
+    );
+}
@@ -0,0 +1,80 @@
+//! Tests for temperature differences and their arithmetic.
+
+use temperature::{ConversionUnit, TemperatureDelta, TemperatureUnit};
+
+/// Tolerance used when comparing floating point results
+const EPSILON: f64 = 1e-9;
+
+/// Asserts that a delta has the expected unit and (almost) the expected value
+fn assert_delta_eq(actual: TemperatureDelta, unit: ConversionUnit, value: f64) {
+    assert_eq!(actual.unit(), unit);
+    assert!(
+        (actual.value() - value).abs() < EPSILON,
+        "expected {value}, got {actual:?}"
+    );
+}
+
+#[test]
+fn deltas_only_apply_the_degree_size() {
+    let ten_celsius = TemperatureDelta::new(ConversionUnit::ToCelsius, 10.0);
+    assert_delta_eq(
+        ten_celsius.convert_to(ConversionUnit::ToFahrenheit),
+        ConversionUnit::ToFahrenheit,
+        18.0,
+    );
+    assert_delta_eq(
+        ten_celsius.convert_to(ConversionUnit::ToKelvin),
+        ConversionUnit::ToKelvin,
+        10.0,
+    );
+    assert_delta_eq(
+        ten_celsius.convert_to(ConversionUnit::ToReaumur),
+        ConversionUnit::ToReaumur,
+        8.0,
+    );
+    // Delisle counts downwards, so a rise is a negative number of degrees
+    assert_delta_eq(
+        ten_celsius.convert_to(ConversionUnit::ToDelisle),
+        ConversionUnit::ToDelisle,
+        -15.0,
+    );
+}
+
+#[test]
+fn absolute_minus_absolute_is_a_delta() {
+    let rise = TemperatureUnit::Fahrenheit(68.0) - TemperatureUnit::Celsius(10.0);
+    assert_delta_eq(rise, ConversionUnit::ToFahrenheit, 18.0);
+}
+
+#[test]
+fn absolute_plus_or_minus_delta_is_absolute() {
+    let set_point = TemperatureUnit::Celsius(21.0);
+    let swing = TemperatureDelta::new(ConversionUnit::ToFahrenheit, 9.0);
+    let upper = set_point + swing;
+    let lower = set_point - swing;
+    assert_eq!(upper.unit(), ConversionUnit::ToCelsius);
+    assert!((upper.value() - 26.0).abs() < EPSILON);
+    assert!((lower.value() - 16.0).abs() < EPSILON);
+}
+
+#[test]
+fn deltas_combine_and_scale() {
+    let a = TemperatureDelta::new(ConversionUnit::ToCelsius, 2.0);
+    let b = TemperatureDelta::new(ConversionUnit::ToFahrenheit, 1.8);
+    assert_delta_eq(a + b, ConversionUnit::ToCelsius, 3.0);
+    assert_delta_eq(a - b, ConversionUnit::ToCelsius, 1.0);
+    assert_delta_eq(-a, ConversionUnit::ToCelsius, -2.0);
+    assert_delta_eq(a * 3.0, ConversionUnit::ToCelsius, 6.0);
+    assert_delta_eq(a / 4.0, ConversionUnit::ToCelsius, 0.5);
+}
+
+#[test]
+fn every_scale_round_trips_a_delta() {
+    for from in ConversionUnit::ALL {
+        for to in ConversionUnit::ALL {
+            let original = TemperatureDelta::new(from, 12.5);
+            let back = original.convert_to(to).convert_to(from);
+            assert_delta_eq(back, from, 12.5);
+        }
+    }
+}
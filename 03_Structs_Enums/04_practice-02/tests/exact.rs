@@ -0,0 +1,139 @@
+//! Tests for the exact conversion mode, with property tests over every pair of scales.
+
+use proptest::prelude::*;
+use temperature::exact::{self, ExactError, ExactTemperature, ParseRationalError, Rational};
+use temperature::{ConversionUnit, TemperatureUnit, formulas};
+
+/// Any scale
+fn any_unit() -> impl Strategy<Value = ConversionUnit> {
+    (0..ConversionUnit::ALL.len()).prop_map(|index| ConversionUnit::ALL[index])
+}
+
+/// Decimal Kelvin readings from 0 K to 10 000 K with up to four decimals
+fn any_kelvin() -> impl Strategy<Value = Rational> {
+    (0_i128..100_000_000)
+        .prop_map(|ten_thousandths| Rational::new(ten_thousandths, 10_000).unwrap())
+}
+
+#[test]
+fn constants_match_the_floating_point_formulas() {
+    let pairs = [
+        (exact::SCALE_FACTOR, formulas::SCALE_FACTOR),
+        (exact::FAHRENHEIT_OFFSET, formulas::FAHRENHEIT_OFFSET),
+        (exact::KELVIN_OFFSET, formulas::KELVIN_OFFSET),
+        (exact::KELVIN_BOILING_POINT, formulas::KELVIN_BOILING_POINT),
+        (exact::REAUMUR_FACTOR, formulas::REAUMUR_FACTOR),
+        (exact::DELISLE_FACTOR, formulas::DELISLE_FACTOR),
+        (exact::NEWTON_FACTOR, formulas::NEWTON_FACTOR),
+        (exact::ROMER_FACTOR, formulas::ROMER_FACTOR),
+        (exact::ROMER_OFFSET, formulas::ROMER_OFFSET),
+    ];
+    for (fraction, float) in pairs {
+        assert_eq!(fraction.to_f64(), float, "{fraction}");
+    }
+}
+
+#[test]
+fn rationals_parse_and_print_exactly() {
+    let cases = [
+        ("0.1", "0.1"),
+        ("-12.50", "-12.5"),
+        ("1e-3", "0.001"),
+        ("2.5E2", "250"),
+        ("1/3", "1/3"),
+        ("-6/4", "-1.5"),
+        ("0.5/3", "1/6"),
+    ];
+    for (input, printed) in cases {
+        let value: Rational = input.parse().unwrap();
+        assert_eq!(value.to_string(), printed, "{input}");
+    }
+    for input in ["", "-", "nan", "inf", "1/0", "1.2.3", "1e", "0x10"] {
+        assert!(
+            matches!(
+                input.parse::<Rational>(),
+                Err(ParseRationalError::Invalid { .. })
+            ),
+            "{input}"
+        );
+    }
+    // Decimals too long for 128 bits are valid input, only out of range
+    for input in [
+        "1e-400",
+        "1e39",
+        "1/1e99999999999",
+        "123456789012345678901234567890123456789012",
+    ] {
+        assert!(
+            matches!(
+                input.parse::<Rational>(),
+                Err(ParseRationalError::OutOfRange { .. })
+            ),
+            "{input}"
+        );
+    }
+    assert_eq!(
+        "1e-400".parse::<Rational>().unwrap_err().to_string(),
+        "`1e-400` is out of range, exact numbers fit in 128 bits: up to 38 digits and powers of \
+         ten from 1e-38 to 1e38"
+    );
+}
+
+#[test]
+fn absolute_zero_and_overflow_are_errors() {
+    let below = Rational::new(-1, 10).unwrap();
+    assert!(matches!(
+        ExactTemperature::try_new(ConversionUnit::ToKelvin, below),
+        Err(ExactError::BelowAbsoluteZero { .. })
+    ));
+    let zero = ExactTemperature::try_new(ConversionUnit::ToCelsius, "-273.15".parse().unwrap());
+    assert_eq!(zero.unwrap().to_kelvin(), Ok(Rational::ZERO));
+
+    let huge = ExactTemperature::new(ConversionUnit::ToKelvin, Rational::integer(i128::MAX / 2));
+    assert_eq!(
+        huge.convert_to(ConversionUnit::ToRankine),
+        Err(ExactError::Overflow)
+    );
+}
+
+proptest! {
+    #[test]
+    fn every_pair_of_scales_round_trips_exactly(
+        kelvin in any_kelvin(),
+        from in any_unit(),
+        to in any_unit(),
+    ) {
+        let start = ExactTemperature::new(ConversionUnit::ToKelvin, kelvin)
+            .convert_to(from)
+            .unwrap();
+        let there = start.convert_to(to).unwrap();
+        prop_assert_eq!(there.convert_to(from).unwrap(), start);
+        prop_assert_eq!(there.to_kelvin().unwrap(), kelvin);
+    }
+
+    #[test]
+    fn celsius_decimals_round_trip_through_fahrenheit(hundredths in -27_315_i128..1_000_000) {
+        let celsius = Rational::new(hundredths, 100).unwrap();
+        let start = ExactTemperature::try_new(ConversionUnit::ToCelsius, celsius).unwrap();
+        let fahrenheit = start.convert_to(ConversionUnit::ToFahrenheit).unwrap();
+        // Celsius hundredths are always finite decimals in Fahrenheit and Kelvin
+        prop_assert!(fahrenheit.value().is_decimal());
+        prop_assert_eq!(fahrenheit.convert_to(ConversionUnit::ToCelsius).unwrap(), start);
+        let kelvin = start.convert_to(ConversionUnit::ToKelvin).unwrap();
+        prop_assert_eq!(kelvin.convert_to(ConversionUnit::ToCelsius).unwrap(), start);
+    }
+
+    #[test]
+    fn exact_results_agree_with_floating_point(
+        kelvin in any_kelvin(),
+        from in any_unit(),
+        to in any_unit(),
+    ) {
+        let start = ExactTemperature::new(ConversionUnit::ToKelvin, kelvin)
+            .convert_to(from)
+            .unwrap();
+        let exact = start.convert_to(to).unwrap().value().to_f64();
+        let float = TemperatureUnit::new(from, start.value().to_f64()).convert_to(to).value();
+        prop_assert!((exact - float).abs() <= 1e-9 * exact.abs().max(1.0), "{exact} != {float}");
+    }
+}
@@ -0,0 +1,110 @@
+//! Tests for the conversion expression parser.
+
+use temperature::expression::{
+    ExpressionError, ExpressionErrorKind, parse_expression, parse_temperature,
+};
+use temperature::{ConversionUnit, TemperatureUnit};
+
+#[test]
+fn accepts_symbols_names_and_arrows() {
+    let cases = [
+        (
+            "98.6 °F to C",
+            TemperatureUnit::Fahrenheit(98.6),
+            ConversionUnit::ToCelsius,
+        ),
+        (
+            "-40F->C",
+            TemperatureUnit::Fahrenheit(-40.0),
+            ConversionUnit::ToCelsius,
+        ),
+        (
+            "300 kelvin in fahrenheit",
+            TemperatureUnit::Kelvin(300.0),
+            ConversionUnit::ToFahrenheit,
+        ),
+        (
+            "  20℃ => Rø ",
+            TemperatureUnit::Celsius(20.0),
+            ConversionUnit::ToRomer,
+        ),
+        (
+            "1e2 C as K",
+            TemperatureUnit::Celsius(100.0),
+            ConversionUnit::ToKelvin,
+        ),
+    ];
+    for (input, from, to) in cases {
+        let request = parse_expression(input).unwrap();
+        assert_eq!(request.from(), from, "{input}");
+        assert_eq!(request.to(), to, "{input}");
+    }
+}
+
+#[test]
+fn temperatures_without_target_are_accepted_on_their_own() {
+    assert_eq!(parse_temperature("25c"), Ok(TemperatureUnit::Celsius(25.0)));
+    assert_eq!(
+        parse_temperature("-3.5 ºF"),
+        Ok(TemperatureUnit::Fahrenheit(-3.5))
+    );
+    assert_eq!(parse_temperature("7 Ré"), Ok(TemperatureUnit::Reaumur(7.0)));
+}
+
+#[test]
+fn errors_point_at_the_offending_column() {
+    let error = |input: &str| parse_expression(input).unwrap_err();
+    assert_eq!(
+        error("98.6 X to C"),
+        ExpressionError {
+            column: 6,
+            kind: ExpressionErrorKind::UnknownUnit("X".to_string()),
+        }
+    );
+    assert_eq!(
+        error("to C"),
+        ExpressionError {
+            column: 1,
+            kind: ExpressionErrorKind::MissingValue,
+        }
+    );
+    assert_eq!(
+        error("98.6 to C"),
+        ExpressionError {
+            column: 6,
+            kind: ExpressionErrorKind::MissingUnit,
+        }
+    );
+    // A full expression names its target; the command line falls back to `--to` instead
+    assert_eq!(
+        error("25c"),
+        ExpressionError {
+            column: 4,
+            kind: ExpressionErrorKind::MissingTarget,
+        }
+    );
+    assert_eq!(
+        error("25c to K now"),
+        ExpressionError {
+            column: 10,
+            kind: ExpressionErrorKind::UnexpectedInput("now".to_string()),
+        }
+    );
+    assert_eq!(
+        error("1.2.3C to F"),
+        ExpressionError {
+            column: 1,
+            kind: ExpressionErrorKind::InvalidNumber("1.2.3".to_string()),
+        }
+    );
+}
+
+#[test]
+fn highlight_puts_a_caret_under_the_error() {
+    let input = "98.6 X to C";
+    let error = parse_expression(input).unwrap_err();
+    assert_eq!(
+        error.highlight(input),
+        "98.6 X to C\n     ^ unknown unit `X` (column 6)"
+    );
+}
@@ -0,0 +1,89 @@
+//! Tests for the C ABI, from Rust and from a C program linked against the static library.
+
+use std::env;
+use std::ffi::CStr;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use temperature::ConversionUnit;
+use temperature::ffi::{self, Status};
+
+#[test]
+fn convert_returns_status_codes() {
+    let mut result = 0.0;
+    let status = unsafe { ffi::temperature_convert(100.0, 0, 1, &mut result) };
+    assert_eq!(status, Status::Ok);
+    assert!((result - 212.0).abs() < 1e-9);
+
+    result = 7.0;
+    let rejected = [
+        (-1.0, 2, 0, Status::BelowAbsoluteZero),
+        (f64::INFINITY, 0, 2, Status::NotFinite),
+        (1.7e308, 2, 1, Status::NotFinite),
+        (1.0, 0, 8, Status::UnknownScale),
+        (1.0, -1, 0, Status::UnknownScale),
+    ];
+    for (value, from, to, expected) in rejected {
+        let status = unsafe { ffi::temperature_convert(value, from, to, &mut result) };
+        assert_eq!(status, expected, "{value} from {from} to {to}");
+    }
+    assert_eq!(result, 7.0);
+    let status = unsafe { ffi::temperature_convert(1.0, 0, 2, std::ptr::null_mut()) };
+    assert_eq!(status, Status::NullPointer);
+}
+
+#[test]
+fn scale_codes_match_the_library() {
+    for (number, unit) in ConversionUnit::ALL.iter().enumerate() {
+        let code = unsafe { CStr::from_ptr(ffi::temperature_scale_code(number as i32)) };
+        assert_eq!(code.to_str(), Ok(unit.code()));
+    }
+    assert!(ffi::temperature_scale_code(8).is_null());
+    assert!(ffi::temperature_status_message(5).is_null());
+}
+
+/// Set `UPDATE_HEADER=1` to rewrite the header after changing the C ABI
+#[test]
+fn header_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("include/temperature.h");
+    if env::var_os("UPDATE_HEADER").is_some() {
+        fs::write(&path, ffi::header()).unwrap();
+    }
+    let on_disk = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        on_disk,
+        ffi::header(),
+        "include/temperature.h is stale, run the tests with UPDATE_HEADER=1"
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn c_program_links_against_the_static_library() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // Cargo builds the static library next to this test in `target/<profile>/deps`
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("convert_c");
+    let compiled = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg("-I")
+        .arg(manifest.join("include"))
+        .arg(manifest.join("tests/c/convert.c"))
+        .arg(deps.join("libtemperature.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status();
+    let Ok(compiled) = compiled else {
+        eprintln!("skipping the C test: no C compiler found");
+        return;
+    };
+    assert!(compiled.success(), "the C test program did not compile");
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
@@ -0,0 +1,122 @@
+//! Tests for the watch mode, over in-memory streams and a file that grows while it is read.
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use temperature::ConversionUnit;
+use temperature::follow::{
+    FollowError, FollowOptions, LineErrorKind, MalformedLines, Tail, follow, parse_reading,
+};
+use temperature::locale::Locale;
+use temperature::output::OutputFormat;
+
+/// Follows `input` to its end and returns the output, the warnings and the report
+fn run(input: &str, options: &FollowOptions) -> (String, String, usize) {
+    let (mut output, mut warnings) = (Vec::new(), Vec::new());
+    let report = follow(input.as_bytes(), &mut output, &mut warnings, options).unwrap();
+    assert_eq!(
+        report.converted,
+        output.iter().filter(|b| **b == b'\n').count()
+    );
+    (
+        String::from_utf8(output).unwrap(),
+        String::from_utf8(warnings).unwrap(),
+        report.skipped,
+    )
+}
+
+#[test]
+fn readings_keep_their_timestamp_and_may_carry_a_unit() {
+    let mut options = FollowOptions::new(None, ConversionUnit::ToCelsius);
+    options.locale = Locale::Es;
+    let reading = parse_reading("2024-05-01 12:00:00  98,6 °F", &options).unwrap();
+    assert_eq!(reading.timestamp.as_deref(), Some("2024-05-01 12:00:00"));
+    assert_eq!(reading.request.from().value(), 98.6);
+    assert_eq!(reading.request.from().unit(), ConversionUnit::ToFahrenheit);
+
+    assert_eq!(
+        parse_reading("12:00 21.5", &options),
+        Err(LineErrorKind::MissingUnit)
+    );
+    assert!(matches!(
+        parse_reading("21.5 parsec", &options),
+        Err(LineErrorKind::UnknownUnit(_))
+    ));
+    assert!(matches!(
+        parse_reading("-5 K", &options),
+        Err(LineErrorKind::InvalidTemperature(_))
+    ));
+    assert!(matches!(
+        parse_reading("sensor offline", &options),
+        Err(LineErrorKind::InvalidValue(_))
+    ));
+}
+
+#[test]
+fn malformed_lines_are_skipped_reported_or_stop_the_stream() {
+    let input = "t1 0\noffline\n\nt3 100\n";
+    let mut options = FollowOptions::new(Some(ConversionUnit::ToCelsius), ConversionUnit::ToKelvin);
+
+    let (output, warnings, skipped) = run(input, &options);
+    assert_eq!(output, "t1 273.15 K\nt3 373.15 K\n");
+    assert_eq!(
+        warnings,
+        "warning: line 2: `offline` is not a temperature\n"
+    );
+    assert_eq!(skipped, 1);
+
+    options.on_malformed = MalformedLines::Skip;
+    let (_, warnings, skipped) = run(input, &options);
+    assert_eq!((warnings.as_str(), skipped), ("", 1));
+
+    options.on_malformed = MalformedLines::Stop;
+    let mut output = Vec::new();
+    let result = follow(input.as_bytes(), &mut output, Vec::new(), &options);
+    assert!(matches!(result, Err(FollowError::Malformed(error)) if error.line == 2));
+    assert_eq!(output, b"t1 273.15 K\n");
+}
+
+#[test]
+fn json_output_writes_one_object_per_reading() {
+    let mut options = FollowOptions::new(None, ConversionUnit::ToFahrenheit);
+    options.output = OutputFormat::Json;
+    options.precision = 1;
+    let (output, _, _) = run("2024-05-01T12:00:00Z 100C\n0 C\n", &options);
+    assert_eq!(
+        output,
+        concat!(
+            r#"{"timestamp":"2024-05-01T12:00:00Z","from":{"value":100.0,"unit":"C"},"#,
+            r#""to":{"value":212.0,"unit":"F"}}"#,
+            "\n",
+            r#"{"from":{"value":0.0,"unit":"C"},"to":{"value":32.0,"unit":"F"}}"#,
+            "\n"
+        )
+    );
+}
+
+#[test]
+fn tail_waits_for_lines_appended_later() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("follow_tail.log");
+    fs::write(&path, "1 C\n").unwrap();
+    let tail = Tail::open(&path)
+        .unwrap()
+        .with_poll_interval(Duration::from_millis(10));
+    let mut lines = BufReader::new(tail).lines();
+    assert_eq!(lines.next().unwrap().unwrap(), "1 C");
+
+    let writer_path = path.clone();
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        let mut file = OpenOptions::new().append(true).open(writer_path).unwrap();
+        file.write_all(b"2 C\n").unwrap();
+    });
+    assert_eq!(lines.next().unwrap().unwrap(), "2 C");
+    writer.join().unwrap();
+
+    // A truncated file is read again from the start
+    fs::write(&path, "3\n").unwrap();
+    assert_eq!(lines.next().unwrap().unwrap(), "3");
+}
@@ -0,0 +1,75 @@
+//! Tests for the conversion history and its CSV/JSON files.
+
+use std::env;
+use std::fs;
+
+use temperature::history::History;
+use temperature::{ConversionRequest, ConversionUnit, TemperatureUnit};
+
+/// A history with two conversions
+fn sample() -> History {
+    let mut history = History::default();
+    history.push(ConversionRequest::new(
+        TemperatureUnit::Celsius(37.0),
+        ConversionUnit::ToFahrenheit,
+    ));
+    history.push(ConversionRequest::new(
+        TemperatureUnit::Kelvin(0.0),
+        ConversionUnit::ToRankine,
+    ));
+    history
+}
+
+#[test]
+fn csv_keeps_every_digit_and_reads_back() {
+    let mut csv = Vec::new();
+    sample().write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.starts_with("from_value,from_unit,to_value,to_unit\n37,C,98.6"));
+    assert_eq!(History::read_csv(csv.as_bytes()).unwrap(), sample());
+}
+
+#[test]
+fn json_lists_every_entry() {
+    let mut json = Vec::new();
+    sample().write_json(&mut json).unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap(),
+        concat!(
+            r#"[{"from":{"value":37,"unit":"C"},"to":{"value":98.60000000000001,"unit":"F"}},"#,
+            r#"{"from":{"value":0,"unit":"K"},"to":{"value":0,"unit":"R"}}]"#,
+            "\n"
+        )
+    );
+
+    // A result too large for an `f64` still gives valid JSON
+    let mut history = History::default();
+    history.push(ConversionRequest::new(
+        TemperatureUnit::Kelvin(1.7e308),
+        ConversionUnit::ToFahrenheit,
+    ));
+    let mut json = Vec::new();
+    history.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.ends_with("\"to\":{\"value\":null,\"unit\":\"F\"}}]\n"));
+}
+
+#[test]
+fn broken_rows_are_rejected() {
+    let csv = "from_value,from_unit,to_value,to_unit\n12,C,53.6,F\n-500,C,0,F\n";
+    let error = History::read_csv(csv.as_bytes()).unwrap_err();
+    assert_eq!(error.to_string(), "line 3: not a history entry");
+}
+
+#[test]
+fn export_picks_the_format_from_the_extension() {
+    let dir = env::temp_dir().join(format!("converter-history-{}", std::process::id()));
+    let json = dir.join("nested").join("history.json");
+    let csv = dir.join("history.csv");
+    sample().export(&json).unwrap();
+    sample().export(&csv).unwrap();
+    assert!(fs::read_to_string(&json).unwrap().starts_with("[{"));
+    assert_eq!(History::load(&csv).unwrap(), sample());
+    assert!(History::load(&dir.join("missing.csv")).unwrap().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,79 @@
+//! Tests for the English and Spanish message catalog.
+
+use temperature::cli::{Command, parse_args, parse_args_with_locale};
+use temperature::locale::{Locale, Message};
+use temperature::{ConversionUnit, TemperatureUnit};
+
+#[test]
+fn language_tags_are_recognised() {
+    assert_eq!("es_MX.UTF-8".parse(), Ok(Locale::Es));
+    assert_eq!("EN-gb".parse(), Ok(Locale::En));
+    assert_eq!("C".parse(), Ok(Locale::En));
+    assert!("fr_FR".parse::<Locale>().is_err());
+}
+
+#[test]
+fn numbers_use_the_decimal_separator_of_the_language() {
+    assert_eq!(Locale::Es.parse_number("-36,6"), Some(-36.6));
+    assert_eq!(Locale::Es.parse_number("36.6"), Some(36.6));
+    assert_eq!(Locale::En.parse_number("36,6"), None);
+    assert_eq!(Locale::Es.format_number(1234.5, 2), "1234,50");
+    assert_eq!(Locale::Es.normalize_decimals("36,6C, 1,5"), "36.6C, 1.5");
+    assert_eq!(
+        Locale::En.format_temperature(TemperatureUnit::Kelvin(0.5), 1),
+        "0.5 K"
+    );
+}
+
+#[test]
+fn every_message_is_translated() {
+    let messages = [
+        Message::MenuTitle,
+        Message::ConvertFrom,
+        Message::Exit,
+        Message::EnterChoice,
+        Message::ConvertTo(ConversionUnit::ToKelvin),
+        Message::EnterValue,
+        Message::InvalidChoice,
+        Message::InvalidValue,
+        Message::Goodbye,
+        Message::ResultTitle,
+        Message::HistoryTitle,
+        Message::NoConversions,
+        Message::HistoryEmpty,
+        Message::NoHistoryEntry(3),
+    ];
+    for message in messages {
+        assert_ne!(Locale::En.message(message), Locale::Es.message(message));
+    }
+}
+
+#[test]
+fn command_line_values_follow_the_language() {
+    let Ok(Command::Convert(cli_args)) = parse_args(&["--lang", "es", "36,6", "C", "-t", "F"])
+    else {
+        panic!("expected a conversion");
+    };
+    assert_eq!(cli_args.values(), [36.6]);
+    assert_eq!(cli_args.locale(), Locale::Es);
+
+    let Ok(Command::Convert(cli_args)) = parse_args_with_locale(&["36,6C", "in", "F"], Locale::Es)
+    else {
+        panic!("expected an expression");
+    };
+    assert_eq!(cli_args.values(), [36.6]);
+    // In English a comma is not a decimal separator
+    assert!(parse_args(&["36,6", "C", "-t", "F"]).is_err());
+
+    let table = [
+        "--table", "C", "-t", "F", "--start", "36,6", "--end", "37,5", "--step", "0,1",
+    ];
+    let Ok(Command::Table(options)) = parse_args(&[&table[..], &["--lang", "es"]].concat()) else {
+        panic!("expected a table");
+    };
+    assert_eq!(
+        (options.start, options.end, options.step),
+        (36.6, 37.5, 0.1)
+    );
+    assert!(parse_args(&table).is_err());
+}
@@ -0,0 +1,64 @@
+//! Tests for the text, JSON and JSON Lines output of conversions.
+
+use temperature::locale::Locale;
+use temperature::output::{OutputFormat, format_conversions, format_json, format_text};
+use temperature::{ConversionRequest, ConversionUnit, TemperatureUnit};
+
+/// Two conversions into Fahrenheit used by most tests
+fn requests() -> Vec<ConversionRequest> {
+    vec![
+        ConversionRequest::new(TemperatureUnit::Celsius(0.0), ConversionUnit::ToFahrenheit),
+        ConversionRequest::new(TemperatureUnit::Celsius(37.0), ConversionUnit::ToFahrenheit),
+    ]
+}
+
+#[test]
+fn json_object_uses_unit_codes() {
+    let request = ConversionRequest::new(TemperatureUnit::Reaumur(80.0), ConversionUnit::ToRomer);
+    assert_eq!(
+        format_json(&request, 2),
+        r#"{"from":{"value":80.00,"unit":"Re"},"to":{"value":60.00,"unit":"Ro"}}"#
+    );
+    assert_eq!(format_text(&request, 0), "80 °Ré is 60 °Rø");
+}
+
+#[test]
+fn each_format_prints_every_conversion() {
+    assert_eq!(
+        format_conversions(&requests(), OutputFormat::Text, 1, Locale::En),
+        "0.0 °C is 32.0 °F\n37.0 °C is 98.6 °F\n"
+    );
+    assert_eq!(
+        format_conversions(&requests(), OutputFormat::Json, 1, Locale::En),
+        concat!(
+            r#"[{"from":{"value":0.0,"unit":"C"},"to":{"value":32.0,"unit":"F"}},"#,
+            r#"{"from":{"value":37.0,"unit":"C"},"to":{"value":98.6,"unit":"F"}}]"#,
+            "\n"
+        )
+    );
+    let lines = format_conversions(&requests(), OutputFormat::JsonLines, 1, Locale::Es);
+    assert_eq!(lines.lines().count(), 2);
+    assert!(
+        lines
+            .lines()
+            .all(|line| line.starts_with('{') && line.ends_with('}'))
+    );
+    assert_eq!(
+        format_conversions(&[], OutputFormat::Json, 1, Locale::En),
+        "[]\n"
+    );
+    // JSON numbers keep their dot whatever the language
+    assert!(lines.contains(r#""value":98.6,"#));
+    assert_eq!(
+        format_conversions(&requests()[1..], OutputFormat::Text, 1, Locale::Es),
+        "37,0 °C equivalen a 98,6 °F\n"
+    );
+}
+
+#[test]
+fn format_names_are_parsed() {
+    assert_eq!("JSON".parse(), Ok(OutputFormat::Json));
+    assert_eq!("ndjson".parse(), Ok(OutputFormat::JsonLines));
+    assert!("xml".parse::<OutputFormat>().is_err());
+    assert!(!OutputFormat::Text.is_machine());
+}
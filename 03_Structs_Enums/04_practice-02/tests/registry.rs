@@ -0,0 +1,149 @@
+//! Tests for the dimension-aware unit registry and its menu.
+
+use temperature::output::format_quantity_json;
+use temperature::registry::{ConversionError, Dimension, Quantity, Registry, RegistryError, Unit};
+use temperature::unit_session::UnitSession;
+use temperature::{ConversionUnit, TemperatureUnit};
+
+#[test]
+fn temperature_scales_agree_with_the_temperature_converter() {
+    let registry = Registry::builtin();
+    for from in ConversionUnit::ALL {
+        for to in ConversionUnit::ALL {
+            let expected = TemperatureUnit::try_new(from, 36.6)
+                .unwrap()
+                .convert_to(to)
+                .value();
+            let converted = registry.convert(36.6, from.code(), to.code()).unwrap();
+            assert!(
+                (converted - expected).abs() < 1e-9,
+                "{from:?} -> {to:?}: {converted} != {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn converts_within_a_dimension_and_rejects_mixed_ones() {
+    let registry = Registry::builtin();
+    assert!((registry.convert(1.0, "mile", "km").unwrap() - 1.609_344).abs() < 1e-12);
+    assert!((registry.convert(100.0, "km/h", "m/s").unwrap() - 27.777_777).abs() < 1e-6);
+    assert_eq!(
+        registry.convert(1.0, "gal", "kg"),
+        Err(ConversionError::IncompatibleDimensions {
+            from: Dimension::Volume,
+            to: Dimension::Mass,
+        })
+    );
+    assert_eq!(
+        registry.convert(-1.0, "K", "C"),
+        Err(ConversionError::BelowMinimum {
+            value: -1.0,
+            symbol: "K".to_string(),
+        })
+    );
+    assert_eq!(
+        registry.convert(1.0, "parsec", "m"),
+        Err(ConversionError::UnknownUnit("parsec".to_string()))
+    );
+}
+
+#[test]
+fn parses_conversions_with_inches() {
+    let registry = Registry::builtin();
+    let (from, to) = registry.parse_conversion("3 in in cm").unwrap();
+    assert_eq!(from.unit.symbol, "in");
+    assert!((to.value - 7.62).abs() < 1e-12);
+    let (_, to) = registry.parse_conversion("2nmi -> km").unwrap();
+    assert!((to.value - 3.704).abs() < 1e-12);
+    assert!(matches!(
+        registry.parse_conversion("five km to mi"),
+        Err(ConversionError::InvalidExpression(_))
+    ));
+}
+
+#[test]
+fn numbers_may_use_exponents_like_temperatures() {
+    let registry = Registry::builtin();
+    let (from, to) = registry.parse_conversion("1e3 m to km").unwrap();
+    assert_eq!(from.value, 1000.0);
+    assert!((to.value - 1.0).abs() < 1e-12);
+    let quantity = registry.parse_quantity("2.5E-3km").unwrap();
+    assert_eq!(
+        (quantity.value, quantity.unit.symbol.as_str()),
+        (0.0025, "km")
+    );
+    assert_eq!(registry.parse_quantity("-1.5e+2 m").unwrap().value, -150.0);
+    // Without digits after it, the `e` is not an exponent
+    assert!(matches!(
+        registry.parse_quantity("5e m"),
+        Err(ConversionError::UnknownUnit(unit)) if unit == "e m"
+    ));
+}
+
+#[test]
+fn json_stays_valid_and_keeps_the_temperature_codes() {
+    let registry = Registry::builtin();
+    assert_eq!(
+        registry.convert(1e308, "km", "mm"),
+        Err(ConversionError::NotFinite(f64::INFINITY))
+    );
+    let quantity = |value: f64, unit: &str| Quantity {
+        value,
+        unit: registry.find(unit).unwrap(),
+    };
+    assert_eq!(
+        format_quantity_json(&quantity(1.0, "°C"), &quantity(33.8, "°F"), 1),
+        r#"{"from":{"value":1.0,"unit":"C"},"to":{"value":33.8,"unit":"F"}}"#
+    );
+    let odd = Unit::new("\"q\u{1}", "odd", Dimension::Length, 1.0);
+    let odd = Quantity {
+        value: 2.0,
+        unit: &odd,
+    };
+    assert_eq!(
+        format_quantity_json(&odd, &quantity(2.0, "m"), 0),
+        r#"{"from":{"value":2,"unit":"\"q\u0001"},"to":{"value":2,"unit":"m"}}"#
+    );
+}
+
+#[test]
+fn registering_checks_factors_and_names() {
+    let mut registry = Registry::builtin();
+    assert!(matches!(
+        registry.register(Unit::new("x", "broken", Dimension::Length, 0.0)),
+        Err(RegistryError::InvalidFactor { .. })
+    ));
+    assert_eq!(
+        registry.register(Unit::new("M", "mega", Dimension::Length, 1e6)),
+        Err(RegistryError::DuplicateName {
+            name: "M".to_string(),
+            existing: "m".to_string(),
+        })
+    );
+    registry
+        .register(Unit::new("fur", "furlong", Dimension::Length, 201.168))
+        .unwrap();
+    assert!((registry.convert(8.0, "furlong", "mi").unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn menu_is_built_from_the_registry() {
+    let mut registry = Registry::new();
+    registry
+        .register(Unit::new("kg", "kilogram", Dimension::Mass, 1.0))
+        .unwrap();
+    registry
+        .register(Unit::new("lb", "pound", Dimension::Mass, 0.453_592_37))
+        .unwrap();
+    let input = "1\n2\n1\n10\n1\n1\n1\n3 m\n0\n";
+    let mut session = UnitSession::new(input.as_bytes(), Vec::new()).with_registry(registry);
+    session.run().unwrap();
+    let transcript = String::from_utf8(session.into_output()).unwrap();
+    // Only the dimension with units is offered
+    assert!(transcript.contains("Choose a quantity:\n1. Mass\n0. Exit\n"));
+    assert!(transcript.contains("Convert pound to:\n1. kilogram (kg)\n2. pound (lb)\n"));
+    assert!(transcript.contains("10.00 lb is 4.54 kg"));
+    assert!(transcript.contains("Invalid value, please try again.\nunknown unit `m`\n"));
+    assert!(transcript.ends_with("Exiting the program. Goodbye!\n"));
+}
@@ -0,0 +1,196 @@
+//! Tests for the local HTTP API and its JSON reader.
+
+use std::io::{self, Cursor, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use temperature::json::{self, JsonValue};
+use temperature::server;
+
+/// Struct to represent a client connection held in memory
+struct Connection<R> {
+    input: R,
+    output: Vec<u8>,
+}
+
+impl<R: Read> Read for Connection<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl<R> Write for Connection<R> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Struct to represent a client that sends part of its request and then goes silent
+struct Stalled(Cursor<&'static [u8]>);
+
+impl Read for Stalled {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.0.read(buf)? {
+            0 => Err(io::ErrorKind::TimedOut.into()),
+            count => Ok(count),
+        }
+    }
+}
+
+/// Answers a request held in memory and returns its status line
+fn status_line<R: Read>(input: R) -> String {
+    let mut connection = Connection {
+        input,
+        output: Vec::new(),
+    };
+    server::handle_connection(&mut connection).unwrap();
+    let response = String::from_utf8(connection.output).unwrap();
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[test]
+fn get_convert_answers_json_or_bad_request() {
+    let response = server::route("GET", "/convert?value=0&from=C&to=K", "");
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        response.body,
+        r#"{"from":{"value":0,"unit":"C"},"to":{"value":273.15,"unit":"K"}}"#
+    );
+    let bad = [
+        "/convert?value=1&from=X&to=F",
+        "/convert?value=NaN&from=C&to=F",
+        "/convert?value=inf&from=C&to=F",
+        "/convert?value=-1&from=K&to=C",
+        "/convert?value=hot&from=C&to=F",
+        "/convert?from=C&to=F",
+        // The value is fine but the result does not fit in an `f64`
+        "/convert?value=1.7e308&from=K&to=F",
+    ];
+    for target in bad {
+        let response = server::route("GET", target, "");
+        assert_eq!(response.status, 400, "{target}");
+        assert!(response.body.starts_with(r#"{"error":""#), "{target}");
+    }
+}
+
+#[test]
+fn batch_converts_every_value_or_names_the_bad_one() {
+    let body = r#"{"from": "F", "to": "C", "values": [32, 212]}"#;
+    let response = server::route("POST", "/convert/batch", body);
+    assert_eq!(response.status, 200);
+    let document = json::parse(&response.body).unwrap();
+    let results = document
+        .get("results")
+        .and_then(JsonValue::as_array)
+        .unwrap();
+    let celsius: Vec<f64> = results
+        .iter()
+        .filter_map(|result| result.get("to")?.get("value")?.as_f64())
+        .collect();
+    assert_eq!(celsius, vec![0.0, 100.0]);
+
+    let body = r#"{"from": "C", "to": "F", "values": [20, -300]}"#;
+    let response = server::route("POST", "/convert/batch", body);
+    assert_eq!(response.status, 400);
+    let document = json::parse(&response.body).unwrap();
+    assert_eq!(document.get("index").and_then(JsonValue::as_f64), Some(1.0));
+    assert_eq!(server::route("POST", "/convert/batch", "{").status, 400);
+}
+
+#[test]
+fn results_too_large_for_json_are_bad_requests() {
+    let body = r#"{"from": "K", "to": "F", "values": [1, 1.7e308]}"#;
+    let response = server::route("POST", "/convert/batch", body);
+    assert_eq!(response.status, 400);
+    let document = json::parse(&response.body).unwrap();
+    assert_eq!(document.get("index"), Some(&JsonValue::Number(1.0)));
+    assert!(!response.body.contains("inf"));
+}
+
+#[test]
+fn deeply_nested_bodies_are_rejected_without_overflowing() {
+    let response = server::route("POST", "/convert/batch", &"[".repeat(500_000));
+    assert_eq!(response.status, 400);
+    assert!(response.body.contains("nested more than 64 levels"));
+
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(json::parse(&nested(json::MAX_DEPTH)).is_ok());
+    assert_eq!(
+        json::parse(&nested(json::MAX_DEPTH + 1))
+            .unwrap_err()
+            .offset,
+        json::MAX_DEPTH
+    );
+}
+
+#[test]
+fn oversized_or_stalled_requests_get_an_error_status() {
+    let long_header = format!(
+        "GET /convert?value=0&from=C&to=K HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+        "a".repeat(server::MAX_HEAD as usize)
+    );
+    assert_eq!(
+        status_line(Cursor::new(long_header)),
+        "HTTP/1.1 431 Request Header Fields Too Large"
+    );
+    let long_target = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(20 * 1024));
+    assert_eq!(
+        status_line(Cursor::new(long_target)),
+        "HTTP/1.1 414 URI Too Long"
+    );
+    let stalled = Stalled(Cursor::new(b"GET /convert HTTP/1.1\r\nHost: loc"));
+    assert_eq!(status_line(stalled), "HTTP/1.1 408 Request Timeout");
+    // Headers up to the limit are still read
+    let request = "GET /convert?value=0&from=C&to=K HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    assert_eq!(status_line(Cursor::new(request)), "HTTP/1.1 200 OK");
+}
+
+#[test]
+fn unknown_paths_and_methods() {
+    assert_eq!(server::route("GET", "/", "").status, 404);
+    let response = server::route("POST", "/convert", "");
+    assert_eq!(response.status, 405);
+    assert_eq!(response.allow, Some("GET"));
+    assert_eq!(
+        server::route("GET", "/convert/batch", "").allow,
+        Some("POST")
+    );
+}
+
+#[test]
+fn answers_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    thread::spawn(move || server::serve(listener));
+
+    let body = r#"{"from":"C","to":"F","values":[100]}"#;
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "POST /convert/batch HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    assert!(response.contains("Content-Type: application/json\r\n"));
+    assert!(response.ends_with(
+        r#"{"results":[{"from":{"value":100,"unit":"C"},"to":{"value":212,"unit":"F"}}]}"#
+    ));
+}
+
+#[test]
+fn json_reader_and_escaping() {
+    let value = json::parse(r#" {"a": [true, null, "x\"é"], "b": -1.5e2} "#).unwrap();
+    assert_eq!(value.get("b").and_then(JsonValue::as_f64), Some(-150.0));
+    let items = value.get("a").and_then(JsonValue::as_array).unwrap();
+    assert_eq!(items[0], JsonValue::Bool(true));
+    assert_eq!(items[2].as_str(), Some("x\"é"));
+    assert_eq!(json::parse("[1,]").unwrap_err().offset, 3);
+    assert_eq!(json::escape("a\"b\\\n"), r#"a\"b\\\n"#);
+}
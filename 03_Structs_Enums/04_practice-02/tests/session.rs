@@ -0,0 +1,207 @@
+//! End-to-end tests for the interactive menu, fed with scripted input.
+
+use std::io::{self, BufReader, Read, Write};
+use std::thread;
+use std::time::Duration;
+use temperature::output::OutputFormat;
+
+use temperature::input::ThreadedInput;
+use temperature::locale::Locale;
+use temperature::session::{CLEAR_SCREEN, Session, SessionError};
+
+/// The main menu as printed before every choice
+const MENU: &str = "\
+====== Temperature Converter ======
+Convert from:
+1. Celsius (°C)
+2. Fahrenheit (°F)
+3. Kelvin (K)
+4. Rankine (°R)
+5. Réaumur (°Ré)
+6. Delisle (°De)
+7. Newton (°N)
+8. Rømer (°Rø)
+Weather:
+9. Heat index
+10. Wind chill
+11. Dew point
+12. Apparent temperature
+0. Exit
+Or type an expression such as `98.6F in C`
+Type `history`, `!N` to repeat entry N or `swap` to convert the last result back
+===================================
+Enter your choice: 
+";
+
+/// The list of target scales shown after picking a source scale
+const SCALES: &str = "\
+1. Celsius (°C)
+2. Fahrenheit (°F)
+3. Kelvin (K)
+4. Rankine (°R)
+5. Réaumur (°Ré)
+6. Delisle (°De)
+7. Newton (°N)
+8. Rømer (°Rø)
+Enter your choice: 
+";
+
+/// Message printed when the session ends
+const GOODBYE: &str = "Exiting the program. Goodbye!";
+
+/// Message printed when the menu input is not understood
+const INVALID_CHOICE: &str = "Invalid choice, please try again.";
+
+/// Runs a session over the given input and returns everything it wrote
+fn transcript(input: &str, format: OutputFormat) -> String {
+    let mut session = Session::new(input.as_bytes(), Vec::new()).with_format(format);
+    session.run().unwrap();
+    String::from_utf8(session.into_output()).unwrap()
+}
+
+#[test]
+fn menu_conversion_then_exit() {
+    let expected = format!(
+        "{CLEAR_SCREEN}{MENU}Convert Celsius to:\n{SCALES}Enter the temperature value: \n\
+         {CLEAR_SCREEN}====== Conversion Result ======\n100.00 °C is 212.00 °F\n\
+         ===============================\n{MENU}{GOODBYE}\n"
+    );
+    assert_eq!(transcript("1\n2\n100\n0\n", OutputFormat::Text), expected);
+}
+
+#[test]
+fn expressions_and_garbage_at_the_prompt() {
+    let expected = format!(
+        "{CLEAR_SCREEN}{MENU}{CLEAR_SCREEN}{INVALID_CHOICE}\n\
+         {MENU}{CLEAR_SCREEN}====== Conversion Result ======\n37.00 °C is 98.60 °F\n\
+         ===============================\n{MENU}{GOODBYE}\n"
+    );
+    assert_eq!(
+        transcript("42\n37 C to F\n0\n", OutputFormat::Text),
+        expected
+    );
+}
+
+#[test]
+fn invalid_temperatures_are_reported() {
+    let expected = format!(
+        "{CLEAR_SCREEN}{MENU}Convert Kelvin to:\n{SCALES}Enter the temperature value: \n\
+         Invalid temperature value, please try again.\n\
+         -5 K is below absolute zero (0.00 K)\n{MENU}{GOODBYE}\n"
+    );
+    assert_eq!(transcript("3\n1\n-5\n0\n", OutputFormat::Text), expected);
+}
+
+#[test]
+fn end_of_input_ends_the_session() {
+    assert_eq!(
+        transcript("", OutputFormat::Text),
+        format!("{CLEAR_SCREEN}{MENU}{GOODBYE}\n")
+    );
+    // Input that stops in the middle of a conversion must not loop forever
+    let partial = transcript("1\n2\n", OutputFormat::Text);
+    assert!(partial.ends_with(&format!("Enter the temperature value: \n{MENU}{GOODBYE}\n")));
+}
+
+#[test]
+fn machine_formats_only_write_results() {
+    assert_eq!(
+        transcript("1\n2\n100\n-40F->C\n0\n", OutputFormat::JsonLines),
+        "{\"from\":{\"value\":100.00,\"unit\":\"C\"},\"to\":{\"value\":212.00,\"unit\":\"F\"}}\n\
+         {\"from\":{\"value\":-40.00,\"unit\":\"F\"},\"to\":{\"value\":-40.00,\"unit\":\"C\"}}\n"
+    );
+}
+
+/// A reader whose every read fails, like a terminal that went away
+struct BrokenInput;
+
+impl Read for BrokenInput {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::other("device lost"))
+    }
+}
+
+#[test]
+fn read_failures_end_the_session_with_an_error() {
+    let mut session = Session::new(BufReader::new(BrokenInput), Vec::new());
+    assert!(matches!(session.run(), Err(SessionError::Input(_))));
+
+    // Input that is not UTF-8 cannot be read as a line either
+    let mut session = Session::new(&[0xff, 0xfe, b'\n'][..], Vec::new());
+    let error = session.run().unwrap_err();
+    assert!(error.to_string().starts_with("cannot read the input"));
+}
+
+#[test]
+fn interrupting_the_input_ends_the_session_like_its_end() {
+    // A pipe whose writer stays open, like a terminal where nobody types
+    let (reader, mut writer) = io::pipe().unwrap();
+    writer.write_all(b"1\n2\n100\n").unwrap();
+    let (input, interrupter) = ThreadedInput::spawn(BufReader::new(reader));
+    let handler = interrupter.clone();
+    let ctrl_c = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        handler.interrupt();
+    });
+    let mut session = Session::new(input, Vec::new());
+    session.run().unwrap();
+    ctrl_c.join().unwrap();
+    assert!(interrupter.is_interrupted());
+    // The conversion made before Ctrl-C is still there to be saved
+    assert_eq!(session.history().len(), 1);
+    let transcript = String::from_utf8(session.into_output()).unwrap();
+    assert!(transcript.ends_with(&format!("{MENU}{GOODBYE}\n")));
+    drop(writer);
+}
+
+#[test]
+fn history_can_be_listed_repeated_and_swapped() {
+    let result = |text: &str| {
+        format!(
+            "{CLEAR_SCREEN}====== Conversion Result ======\n{text}\n===============================\n"
+        )
+    };
+    let expected = format!(
+        "{CLEAR_SCREEN}{MENU}{}{MENU}{}{MENU}{}{MENU}\
+         {CLEAR_SCREEN}====== Conversion History ======\n\
+         1. 100.00 °C is 212.00 °F\n2. 212.00 °F is 100.00 °C\n3. 100.00 °C is 212.00 °F\n\
+         ================================\n{MENU}\
+         {CLEAR_SCREEN}There is no conversion number 9 in the history.\n{MENU}{GOODBYE}\n",
+        result("100.00 °C is 212.00 °F"),
+        result("212.00 °F is 100.00 °C"),
+        result("100.00 °C is 212.00 °F"),
+    );
+    let mut session = Session::new(
+        "100c to f\nswap\n!1\nhistory\n!9\n0\n".as_bytes(),
+        Vec::new(),
+    );
+    session.run().unwrap();
+    assert_eq!(session.history().len(), 3);
+    assert_eq!(String::from_utf8(session.into_output()).unwrap(), expected);
+}
+
+#[test]
+fn history_commands_on_an_empty_history() {
+    let expected = format!(
+        "{CLEAR_SCREEN}{MENU}{CLEAR_SCREEN}The history is empty.\n{MENU}\
+         {CLEAR_SCREEN}`x` is not a history entry number.\n{MENU}{GOODBYE}\n"
+    );
+    assert_eq!(transcript("swap\n!x\n0\n", OutputFormat::Text), expected);
+}
+
+#[test]
+fn spanish_session_uses_decimal_commas() {
+    let mut session =
+        Session::new("1\n2\n36,6\n-300 C\n0\n".as_bytes(), Vec::new()).with_locale(Locale::Es);
+    session.run().unwrap();
+    let transcript = String::from_utf8(session.into_output()).unwrap();
+    assert!(transcript.contains("Convertir Celsius a:\n"));
+    assert!(
+        transcript
+            .contains("====== Resultado de la conversión ======\n36,60 °C equivalen a 97,88 °F\n")
+    );
+    assert!(transcript.contains(
+        "-300 C\n^ -300 °C está por debajo del cero absoluto (-273,15 °C) (columna 1)\n"
+    ));
+    assert!(transcript.ends_with("Saliendo del programa. ¡Adiós!\n"));
+}
@@ -0,0 +1,84 @@
+//! Tests for the summary statistics of a series of readings.
+
+use temperature::locale::Locale;
+use temperature::output::OutputFormat;
+use temperature::stats::{self, Summary};
+use temperature::{ConversionUnit, TemperatureUnit};
+
+/// Readings in Celsius with an even count, so the median is an average
+fn readings() -> Vec<TemperatureUnit> {
+    [18.0, 21.0, 20.0, 25.0]
+        .map(TemperatureUnit::Celsius)
+        .to_vec()
+}
+
+/// Asserts that every value of two summaries matches
+fn assert_close(left: &Summary, right: &Summary) {
+    let pairs = [
+        (left.min.value(), right.min.value()),
+        (left.max.value(), right.max.value()),
+        (left.mean.value(), right.mean.value()),
+        (left.median.value(), right.median.value()),
+        (left.range.value(), right.range.value()),
+        (left.std_dev.value(), right.std_dev.value()),
+    ];
+    for (left_value, right_value) in pairs {
+        assert!(
+            (left_value - right_value).abs() < 1e-9,
+            "{left:?} != {right:?}"
+        );
+    }
+}
+
+#[test]
+fn summary_of_celsius_readings() {
+    let summary = stats::summarize(&readings(), ConversionUnit::ToCelsius).unwrap();
+    assert_eq!(summary.count, 4);
+    assert_eq!(summary.min, TemperatureUnit::Celsius(18.0));
+    assert_eq!(summary.max, TemperatureUnit::Celsius(25.0));
+    assert_eq!(summary.median, TemperatureUnit::Celsius(20.5));
+    assert!((summary.mean.value() - 21.0).abs() < 1e-12);
+    assert!((summary.range.value() - 7.0).abs() < 1e-12);
+    assert!((summary.std_dev.value() - (26.0_f64 / 3.0).sqrt()).abs() < 1e-12);
+    assert!(stats::summarize(&[], ConversionUnit::ToCelsius).is_none());
+}
+
+#[test]
+fn spreads_convert_as_differences_and_readings_as_temperatures() {
+    let celsius = stats::summarize(&readings(), ConversionUnit::ToCelsius).unwrap();
+    let fahrenheit = stats::summarize(&readings(), ConversionUnit::ToFahrenheit).unwrap();
+    assert_close(
+        &celsius.convert_to(ConversionUnit::ToFahrenheit),
+        &fahrenheit,
+    );
+    // 7 °C of range is 12.6 °F, not 7 °C read as a temperature (44.6 °F)
+    assert!((fahrenheit.range.value() - 12.6).abs() < 1e-9);
+    assert!((fahrenheit.min.value() - 64.4).abs() < 1e-9);
+}
+
+#[test]
+fn delisle_keeps_the_minimum_below_the_maximum() {
+    let delisle = stats::summarize(&readings(), ConversionUnit::ToDelisle).unwrap();
+    assert!(delisle.min.value() < delisle.max.value());
+    assert!(delisle.range.value() > 0.0 && delisle.std_dev.value() > 0.0);
+    let celsius = stats::summarize(&readings(), ConversionUnit::ToCelsius).unwrap();
+    assert_close(&celsius.convert_to(ConversionUnit::ToDelisle), &delisle);
+}
+
+#[test]
+fn readings_files_and_rendering() {
+    let input = "# morning\n18,0 21\n\n20 25\n";
+    let values = stats::read_readings(input.as_bytes(), Locale::Es).unwrap();
+    assert_eq!(values, vec![18.0, 21.0, 20.0, 25.0]);
+    let error = stats::read_readings("1\n2 hot\n".as_bytes(), Locale::En).unwrap_err();
+    assert_eq!(error.to_string(), "line 2: `hot` is not a number");
+
+    let summary = stats::summarize(&readings(), ConversionUnit::ToCelsius).unwrap();
+    let text = stats::render_summary(&summary, OutputFormat::Text, 1, Locale::En);
+    assert!(text.starts_with("Readings:           4\nMinimum:            18.0 °C\n"));
+    assert_eq!(
+        stats::render_summary(&summary, OutputFormat::JsonLines, 1, Locale::En),
+        "{\"unit\":\"C\",\"count\":4,\"min\":18.0,\"max\":25.0,\"mean\":21.0,\
+         \"median\":20.5,\"range\":7.0,\"stddev\":2.9}\n"
+    );
+}
@@ -0,0 +1,80 @@
+//! Tests for the conversion table generator.
+
+use temperature::ConversionUnit;
+use temperature::table::{TableError, TableFormat, TableOptions, render_table, table_rows};
+
+/// A small Celsius to Fahrenheit table used by most tests
+fn celsius_to_fahrenheit(format: TableFormat) -> TableOptions {
+    let mut options = TableOptions::new(ConversionUnit::ToCelsius, 0.0, 100.0, 50.0);
+    options.targets = vec![ConversionUnit::ToFahrenheit];
+    options.precision = 1;
+    options.format = format;
+    options
+}
+
+#[test]
+fn rows_cover_the_range_with_every_target_by_default() {
+    let options = TableOptions::new(ConversionUnit::ToCelsius, 0.0, 1.0, 0.1);
+    let rows = table_rows(&options).unwrap();
+    assert_eq!(rows.len(), 11);
+    assert_eq!(rows[0].len(), ConversionUnit::ALL.len());
+    assert!((rows[10][0] - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn descending_ranges_use_a_negative_step() {
+    let options = TableOptions::new(ConversionUnit::ToKelvin, 10.0, 0.0, -5.0);
+    let values: Vec<f64> = table_rows(&options)
+        .unwrap()
+        .iter()
+        .map(|row| row[0])
+        .collect();
+    assert_eq!(values, vec![10.0, 5.0, 0.0]);
+}
+
+#[test]
+fn text_table_is_aligned() {
+    let table = render_table(&celsius_to_fahrenheit(TableFormat::Text)).unwrap();
+    assert_eq!(
+        table,
+        "Celsius (°C)  Fahrenheit (°F)\n\
+         ------------  ---------------\n\
+         \x20        0.0             32.0\n\
+         \x20       50.0            122.0\n\
+         \x20      100.0            212.0\n"
+    );
+}
+
+#[test]
+fn markdown_csv_and_html_tables() {
+    let markdown = render_table(&celsius_to_fahrenheit(TableFormat::Markdown)).unwrap();
+    assert!(
+        markdown.starts_with("| Celsius (°C) | Fahrenheit (°F) |\n|---:|---:|\n| 0.0 | 32.0 |\n")
+    );
+
+    let csv = render_table(&celsius_to_fahrenheit(TableFormat::Csv)).unwrap();
+    assert_eq!(
+        csv,
+        "Celsius (°C),Fahrenheit (°F)\n0.0,32.0\n50.0,122.0\n100.0,212.0\n"
+    );
+
+    let html = render_table(&celsius_to_fahrenheit(TableFormat::Html)).unwrap();
+    assert!(html.contains("<tr><th>Celsius (°C)</th><th>Fahrenheit (°F)</th></tr>"));
+    assert!(html.contains("<tr><td>100.0</td><td>212.0</td></tr>"));
+}
+
+#[test]
+fn invalid_ranges_are_rejected() {
+    let wrong_way = TableOptions::new(ConversionUnit::ToCelsius, 0.0, 10.0, -1.0);
+    assert_eq!(table_rows(&wrong_way), Err(TableError::InvalidStep(-1.0)));
+    let zero = TableOptions::new(ConversionUnit::ToCelsius, 0.0, 10.0, 0.0);
+    assert_eq!(table_rows(&zero), Err(TableError::InvalidStep(0.0)));
+    let huge = TableOptions::new(ConversionUnit::ToCelsius, 0.0, 1e9, 1.0);
+    assert_eq!(table_rows(&huge), Err(TableError::TooManyRows));
+    let too_cold = TableOptions::new(ConversionUnit::ToCelsius, -300.0, 0.0, 10.0);
+    assert!(matches!(
+        table_rows(&too_cold),
+        Err(TableError::InvalidTemperature(_))
+    ));
+    assert!("pdf".parse::<TableFormat>().is_err());
+}
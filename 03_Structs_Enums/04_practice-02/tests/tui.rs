@@ -0,0 +1,114 @@
+//! Tests for the full-screen interface, driven with keys and rendered without a terminal.
+
+use temperature::ConversionUnit;
+use temperature::locale::Locale;
+use temperature::tui::{Focus, Key, MIN_HEIGHT, MIN_WIDTH, TuiState};
+use temperature::weather::Calculator;
+
+/// Types every character of `text`
+fn type_text(state: &mut TuiState, text: &str) {
+    for c in text.chars() {
+        assert!(state.handle_key(Key::Char(c)));
+    }
+}
+
+#[test]
+fn arrow_keys_pick_the_scales_and_wrap_around() {
+    let mut state = TuiState::new(Locale::En);
+    state.handle_key(Key::Up);
+    assert_eq!(state.from(), ConversionUnit::ToRomer);
+    state.handle_key(Key::Down);
+    state.handle_key(Key::Down);
+    assert_eq!(state.from(), ConversionUnit::ToFahrenheit);
+
+    state.handle_key(Key::Right);
+    assert_eq!(state.focus(), Focus::To);
+    state.handle_key(Key::Down);
+    assert_eq!(state.to(), ConversionUnit::ToKelvin);
+
+    state.handle_key(Key::Char('s'));
+    assert_eq!(
+        (state.from(), state.to()),
+        (ConversionUnit::ToKelvin, ConversionUnit::ToFahrenheit)
+    );
+    assert!(!state.handle_key(Key::Char('q')));
+    assert!(!state.handle_key(Key::Quit));
+}
+
+#[test]
+fn the_result_follows_the_typed_value() {
+    let mut state = TuiState::new(Locale::Es);
+    type_text(&mut state, "37x,5");
+    assert_eq!(state.input(), "37,5");
+    let screen = state.render(80, 24).join("\n");
+    assert!(screen.contains("37,50 °C equivalen a 99,50 °F"), "{screen}");
+    assert!(screen.contains("310,65 K"), "{screen}");
+
+    while !state.input().is_empty() {
+        state.handle_key(Key::Backspace);
+    }
+    assert!(state.temperature().is_none());
+    type_text(&mut state, "-300");
+    assert!(state.temperature().unwrap().is_err());
+    let screen = state.render(80, 24).join("\n");
+    assert!(screen.contains("cero absoluto"), "{screen}");
+}
+
+#[test]
+fn the_layout_follows_the_terminal_size() {
+    let mut state = TuiState::new(Locale::En);
+    type_text(&mut state, "0");
+    let fits = |lines: &[String], width: u16, height: u16| {
+        lines.len() <= height as usize
+            && lines
+                .iter()
+                .all(|line| line.chars().count() <= width as usize)
+    };
+
+    // Wide: the panel sits beside the lists
+    let wide = state.render(100, MIN_HEIGHT);
+    assert!(fits(&wide, 100, MIN_HEIGHT));
+    assert!(wide.iter().any(|line| line.contains("All scales")));
+
+    // Narrow and tall: the panel moves below; narrow and short: it is left out
+    let tall = state.render(MIN_WIDTH, 40);
+    assert!(fits(&tall, MIN_WIDTH, 40));
+    assert!(tall.iter().any(|line| line.starts_with("All scales")));
+    let short = state.render(MIN_WIDTH, MIN_HEIGHT);
+    assert!(fits(&short, MIN_WIDTH, MIN_HEIGHT));
+    assert!(!short.iter().any(|line| line.contains("All scales")));
+
+    let tiny = state.render(MIN_WIDTH - 1, 40);
+    assert_eq!(tiny.len(), 1);
+    assert!(tiny[0].starts_with("The terminal is too small"));
+}
+
+#[test]
+fn w_picks_a_weather_calculator_for_the_typed_values() {
+    let mut state = TuiState::new(Locale::En);
+    state.handle_key(Key::Char('w'));
+    state.handle_key(Key::Char('w'));
+    assert_eq!(state.calculator(), Some(Calculator::WindChill));
+    type_text(&mut state, "-10");
+    let screen = state.render(80, 24).join("\n");
+    assert!(
+        screen.contains("Wind chill: type the value, then wind km/h"),
+        "{screen}"
+    );
+    assert!(!screen.contains("Wind chill at"), "{screen}");
+
+    type_text(&mut state, " 20");
+    assert_eq!(state.input(), "-10 20");
+    let screen = state.render(80, 24).join("\n");
+    assert!(screen.contains("Wind chill at -10.00 °C"), "{screen}");
+    assert!(screen.contains("°F"), "{screen}");
+
+    type_text(&mut state, " 5");
+    let screen = state.render(80, 24).join("\n");
+    assert!(screen.contains("Invalid temperature value"), "{screen}");
+
+    for _ in 0..3 {
+        state.handle_key(Key::Char('w'));
+    }
+    assert_eq!(state.calculator(), None);
+}
@@ -0,0 +1,55 @@
+//! Tests for the compile-time tagged temperatures.
+
+use temperature::typed::{Celsius, Fahrenheit, Kelvin, Temperature};
+use temperature::{ConversionUnit, TemperatureDelta, TemperatureUnit};
+
+/// Tolerance used when comparing floating point results
+const EPSILON: f64 = 1e-9;
+
+#[test]
+fn from_converts_between_every_pair() {
+    assert!((Fahrenheit::from(Celsius::new(100.0)).value() - 212.0).abs() < EPSILON);
+    assert!((Kelvin::from(Celsius::new(0.0)).value() - 273.15).abs() < EPSILON);
+    assert!((Celsius::from(Fahrenheit::new(-40.0)).value() - -40.0).abs() < EPSILON);
+    assert!((Kelvin::from(Fahrenheit::new(32.0)).value() - 273.15).abs() < EPSILON);
+    assert!((Celsius::from(Kelvin::new(373.15)).value() - 100.0).abs() < EPSILON);
+    assert!((Fahrenheit::from(Kelvin::new(0.0)).value() - -459.67).abs() < EPSILON);
+}
+
+#[test]
+fn comparisons_work_across_scales() {
+    assert!(Celsius::new(0.0) == Fahrenheit::new(32.0));
+    assert!(Kelvin::new(300.0) > Celsius::new(20.0));
+    assert!(Fahrenheit::new(0.0) < Celsius::new(0.0));
+    assert!(Celsius::new(1.0) < Celsius::new(2.0));
+}
+
+#[test]
+fn deltas_are_added_and_subtracted() {
+    let warmer = Fahrenheit::new(50.0) + TemperatureDelta::new(ConversionUnit::ToCelsius, 10.0);
+    assert!((warmer.value() - 68.0).abs() < EPSILON);
+    let colder = Kelvin::new(300.0) - TemperatureDelta::new(ConversionUnit::ToFahrenheit, 9.0);
+    assert!((colder.value() - 295.0).abs() < EPSILON);
+    let difference = Celsius::new(25.0) - Fahrenheit::new(68.0);
+    assert_eq!(difference.unit(), ConversionUnit::ToCelsius);
+    assert!((difference.value() - 5.0).abs() < EPSILON);
+}
+
+#[test]
+fn display_uses_the_scale_symbol() {
+    assert_eq!(Celsius::new(21.5).to_string(), "21.5 °C");
+    assert_eq!(format!("{:.2}", Fahrenheit::new(98.6)), "98.60 °F");
+    assert_eq!(format!("{:.1}", Kelvin::new(0.0)), "0.0 K");
+    assert_eq!(format!("{:.2}", TemperatureUnit::Romer(7.5)), "7.50 °Rø");
+}
+
+#[test]
+fn typed_and_runtime_temperatures_interoperate() {
+    let runtime = TemperatureUnit::from(Celsius::new(5.0));
+    assert_eq!(runtime, TemperatureUnit::Celsius(5.0));
+    assert!(Kelvin::try_new(-1.0).is_err());
+    let generic: Temperature<_> = Celsius::new(10.0).convert::<temperature::typed::KelvinScale>();
+    assert_eq!(generic.unit(), ConversionUnit::ToKelvin);
+    // The wrapper adds no runtime cost
+    assert_eq!(std::mem::size_of::<Celsius>(), std::mem::size_of::<f64>());
+}
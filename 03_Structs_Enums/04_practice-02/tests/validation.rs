@@ -0,0 +1,107 @@
+//! Tests for rejecting temperatures that cannot physically exist.
+
+use temperature::batch::{BatchOptions, ColumnSelector, RowErrorKind, convert_delimited};
+use temperature::cli::{Command, parse_args};
+use temperature::expression::{ExpressionErrorKind, parse_expression};
+use temperature::output;
+use temperature::{ConversionRequest, ConversionUnit, TemperatureError, TemperatureUnit};
+
+#[test]
+fn values_below_absolute_zero_are_rejected_in_every_scale() {
+    for unit in ConversionUnit::ALL {
+        // One degree colder than absolute zero, remembering Delisle counts downwards
+        let colder = if unit == ConversionUnit::ToDelisle {
+            unit.absolute_zero() + 1.0
+        } else {
+            unit.absolute_zero() - 1.0
+        };
+        assert_eq!(
+            TemperatureUnit::try_new(unit, colder),
+            Err(TemperatureError::BelowAbsoluteZero {
+                value: colder,
+                unit
+            }),
+            "{unit:?}"
+        );
+        // Absolute zero itself is fine, even after floating point noise
+        assert!(TemperatureUnit::try_new(unit, unit.absolute_zero()).is_ok());
+    }
+}
+
+#[test]
+fn non_finite_values_are_rejected() {
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let result = TemperatureUnit::try_new(ConversionUnit::ToCelsius, value);
+        assert!(matches!(result, Err(TemperatureError::NotFinite { .. })));
+    }
+}
+
+#[test]
+fn conversion_request_validates_its_source() {
+    let error =
+        ConversionRequest::try_new(TemperatureUnit::Kelvin(-50.0), ConversionUnit::ToCelsius)
+            .unwrap_err();
+    assert_eq!(error.to_string(), "-50 K is below absolute zero (0.00 K)");
+    assert!(
+        ConversionRequest::try_new(TemperatureUnit::Kelvin(50.0), ConversionUnit::ToCelsius)
+            .is_ok()
+    );
+}
+
+#[test]
+fn every_input_path_surfaces_the_error() {
+    let expression = parse_expression("-500 C to F").unwrap_err();
+    assert_eq!(expression.column, 1);
+    assert!(matches!(
+        expression.kind,
+        ExpressionErrorKind::InvalidTemperature(TemperatureError::BelowAbsoluteZero { .. })
+    ));
+
+    let Ok(Command::Convert(cli_args)) = parse_args(&["-500", "10", "C", "--to", "F"]) else {
+        panic!("expected a conversion");
+    };
+    let requests = cli_args.requests();
+    assert!(requests[0].is_err());
+    assert!(requests[1].is_ok());
+
+    let options = BatchOptions::new(
+        ColumnSelector::Index(1),
+        ConversionUnit::ToKelvin,
+        ConversionUnit::ToCelsius,
+    );
+    let mut output = Vec::new();
+    let report = convert_delimited("t\n-1\nNaN\n".as_bytes(), &mut output, &options).unwrap();
+    let kinds: Vec<_> = report.errors.iter().map(|error| &error.kind).collect();
+    assert!(matches!(
+        kinds[..],
+        [
+            RowErrorKind::InvalidTemperature(TemperatureError::BelowAbsoluteZero { .. }),
+            RowErrorKind::InvalidTemperature(TemperatureError::NotFinite { .. }),
+        ]
+    ));
+}
+
+#[test]
+fn results_too_large_for_the_target_scale_are_errors() {
+    let args = ["--from", "K", "--to", "F", "1.7e308", "1"];
+    let Ok(Command::Convert(cli_args)) = parse_args(&args) else {
+        panic!("expected a conversion");
+    };
+    let requests = cli_args.requests();
+    let error = TemperatureError::TooLarge {
+        value: 1.7e308,
+        unit: ConversionUnit::ToKelvin,
+        to: ConversionUnit::ToFahrenheit,
+    };
+    assert_eq!(requests[0], Err(error));
+    assert_eq!(
+        error.to_string(),
+        "1.7e308 K is too large to convert into °F"
+    );
+    assert!(requests[1].is_ok());
+    // JSON has no infinity, so a result that slips through is still valid JSON
+    assert_eq!(
+        output::json_temperature(TemperatureUnit::Fahrenheit(f64::INFINITY), Some(2)),
+        r#"{"value":null,"unit":"F"}"#
+    );
+}
@@ -0,0 +1,217 @@
+//! Tests for the weather calculators, on their own and from the interactive menu.
+
+use temperature::locale::Locale;
+use temperature::output::OutputFormat;
+use temperature::session::Session;
+use temperature::weather::{self, Calculator, WeatherError};
+use temperature::{ConversionUnit, TemperatureError, TemperatureUnit};
+
+/// Asserts that a result is within a hundredth of a degree of the expected value
+fn assert_close(result: Result<TemperatureUnit, WeatherError>, expected: TemperatureUnit) {
+    let result = result.unwrap();
+    assert_eq!(result.unit(), expected.unit());
+    assert!(
+        (result.value() - expected.value()).abs() < 0.01,
+        "{result} is not {expected}"
+    );
+}
+
+#[test]
+fn results_match_the_published_tables_in_any_scale() {
+    // NWS heat index chart: 90 °F at 70 % feels like 106 °F
+    let fahrenheit = weather::heat_index(
+        TemperatureUnit::Fahrenheit(90.0),
+        70.0,
+        ConversionUnit::ToFahrenheit,
+    );
+    assert_eq!(fahrenheit.unwrap().value().round(), 106.0);
+    let celsius = TemperatureUnit::Fahrenheit(90.0).convert_to(ConversionUnit::ToCelsius);
+    assert_close(
+        weather::heat_index(celsius, 70.0, ConversionUnit::ToFahrenheit),
+        fahrenheit.unwrap(),
+    );
+
+    // Environment Canada wind chill chart: -10 °C with 20 km/h feels like -18 °C
+    assert_close(
+        weather::wind_chill(
+            TemperatureUnit::Celsius(-10.0),
+            20.0,
+            ConversionUnit::ToCelsius,
+        ),
+        TemperatureUnit::Celsius(-17.87),
+    );
+    assert_close(
+        weather::dew_point(
+            TemperatureUnit::Kelvin(298.15),
+            60.0,
+            ConversionUnit::ToCelsius,
+        ),
+        TemperatureUnit::Celsius(16.69),
+    );
+    // Saturated air is at its dew point
+    assert_close(
+        weather::dew_point(
+            TemperatureUnit::Celsius(12.5),
+            100.0,
+            ConversionUnit::ToCelsius,
+        ),
+        TemperatureUnit::Celsius(12.5),
+    );
+    assert_close(
+        weather::apparent_temperature(
+            TemperatureUnit::Celsius(30.0),
+            50.0,
+            0.0,
+            ConversionUnit::ToKelvin,
+        ),
+        TemperatureUnit::Kelvin(306.13),
+    );
+}
+
+#[test]
+fn inputs_outside_the_formulas_are_rejected() {
+    let celsius = ConversionUnit::ToCelsius;
+    let Err(WeatherError::TooCold {
+        calculator: Calculator::HeatIndex,
+        minimum,
+    }) = weather::heat_index(TemperatureUnit::Celsius(20.0), 50.0, celsius)
+    else {
+        panic!("expected the heat index to be too cold");
+    };
+    assert_eq!(minimum.unit(), celsius);
+    assert!((minimum.value() - 26.67).abs() < 0.01);
+
+    let error = weather::wind_chill(TemperatureUnit::Fahrenheit(60.0), 20.0, celsius);
+    assert_eq!(
+        error,
+        Err(WeatherError::TooWarm {
+            calculator: Calculator::WindChill,
+            maximum: TemperatureUnit::Fahrenheit(50.0),
+        })
+    );
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "the wind chill only applies up to 50.00 °F"
+    );
+    assert_eq!(
+        weather::wind_chill(TemperatureUnit::Celsius(0.0), 2.0, celsius),
+        Err(WeatherError::TooCalm { minimum: 4.8 })
+    );
+    assert!(matches!(
+        weather::dew_point(TemperatureUnit::Celsius(70.0), 50.0, celsius),
+        Err(WeatherError::TooWarm {
+            calculator: Calculator::DewPoint,
+            ..
+        })
+    ));
+
+    for humidity in [0.0, 100.5, f64::NAN] {
+        assert!(matches!(
+            weather::dew_point(TemperatureUnit::Celsius(20.0), humidity, celsius),
+            Err(WeatherError::Humidity(_))
+        ));
+    }
+    assert_eq!(
+        weather::apparent_temperature(TemperatureUnit::Celsius(20.0), 50.0, -1.0, celsius),
+        Err(WeatherError::WindSpeed(-1.0))
+    );
+    assert!(matches!(
+        weather::apparent_temperature(TemperatureUnit::Kelvin(-1.0), 50.0, 0.0, celsius),
+        Err(WeatherError::Temperature(
+            TemperatureError::BelowAbsoluteZero { .. }
+        ))
+    ));
+}
+
+#[test]
+fn extreme_air_and_wind_are_rejected_before_the_formulas_break() {
+    let celsius = ConversionUnit::ToCelsius;
+    for air in [200.0, 1000.0] {
+        assert_eq!(
+            weather::heat_index(TemperatureUnit::Fahrenheit(air), 90.0, celsius),
+            Err(WeatherError::TooWarm {
+                calculator: Calculator::HeatIndex,
+                maximum: TemperatureUnit::Fahrenheit(110.0),
+            })
+        );
+    }
+    assert!(weather::heat_index(TemperatureUnit::Fahrenheit(110.0), 90.0, celsius).is_ok());
+
+    assert_eq!(
+        weather::wind_chill(TemperatureUnit::Celsius(-60.0), 20.0, celsius),
+        Err(WeatherError::TooCold {
+            calculator: Calculator::WindChill,
+            minimum: TemperatureUnit::Celsius(-50.0),
+        })
+    );
+    let error = weather::wind_chill(TemperatureUnit::Celsius(-10.0), 200.0, celsius);
+    assert_eq!(
+        error,
+        Err(WeatherError::TooWindy {
+            calculator: Calculator::WindChill,
+            maximum: 80.0,
+        })
+    );
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "the wind chill only applies to winds of up to 80 km/h"
+    );
+    // The coldest and windiest air the wind chill takes is still far above 0 K
+    let chill = weather::wind_chill(TemperatureUnit::Celsius(-50.0), 80.0, celsius).unwrap();
+    assert!(chill.value() > -100.0);
+
+    // Near absolute zero the vapour pressure is infinite, and fast winds went below 0 K
+    for (air, wind) in [(-240.0, 0.0), (-237.0, 500.0)] {
+        assert!(matches!(
+            weather::apparent_temperature(TemperatureUnit::Celsius(air), 50.0, wind, celsius),
+            Err(WeatherError::TooCold {
+                calculator: Calculator::ApparentTemperature,
+                ..
+            })
+        ));
+    }
+    assert!(matches!(
+        weather::apparent_temperature(TemperatureUnit::Celsius(70.0), 50.0, 0.0, celsius),
+        Err(WeatherError::TooWarm {
+            calculator: Calculator::ApparentTemperature,
+            ..
+        })
+    ));
+    assert_eq!(
+        weather::apparent_temperature(TemperatureUnit::Celsius(20.0), 50.0, 500.0, celsius),
+        Err(WeatherError::TooWindy {
+            calculator: Calculator::ApparentTemperature,
+            maximum: 120.0,
+        })
+    );
+}
+
+#[test]
+fn menu_entries_after_the_scales_run_the_calculators() {
+    // Heat index in Fahrenheit, then the dew point in Celsius of a temperature with its unit
+    let mut session = Session::new("9\n2\n90\n70\n11\n1\n77F\n60\n0\n".as_bytes(), Vec::new())
+        .with_format(OutputFormat::JsonLines);
+    session.run().unwrap();
+    assert!(session.history().is_empty());
+    assert_eq!(
+        String::from_utf8(session.into_output()).unwrap(),
+        concat!(
+            r#"{"calculation":"heat_index","from":{"value":90.00,"unit":"F"},"#,
+            r#""to":{"value":105.92,"unit":"F"}}"#,
+            "\n",
+            r#"{"calculation":"dew_point","from":{"value":77.00,"unit":"F"},"#,
+            r#""to":{"value":16.69,"unit":"C"}}"#,
+            "\n"
+        )
+    );
+
+    let mut session = Session::new("10\n1\n-5,5\n30\n10\n1\n20\n10\n0\n".as_bytes(), Vec::new())
+        .with_locale(Locale::Es);
+    session.run().unwrap();
+    let transcript = String::from_utf8(session.into_output()).unwrap();
+    assert!(transcript.contains("10. Sensación por viento\n"));
+    assert!(transcript.contains(
+        "====== Sensación por viento ======\nSensación por viento a -5,50 °C: -13,65 °C\n"
+    ));
+    assert!(transcript.contains("Sensación por viento: la fórmula solo vale hasta 10,00 °C\n"));
+}
@@ -0,0 +1,223 @@
+//! The temperature conversion formulas shared by the practice converters.
+//!
+//! Practice 1 started with the Celsius, Fahrenheit and Kelvin functions, and Practice 2
+//! copied them before adding the historic scales. Both now depend on this crate, so the
+//! constants and the formulas are written only once.
+//! Every function works on plain `f64` values. Celsius, Fahrenheit and Kelvin convert
+//! directly between each other; the historic scales only convert to and from Kelvin,
+//! which acts as the common pivot.
+//!
+//! ```
+//! use temperature_core::{celsius_to_fahrenheit, fahrenheit_to_celsius};
+//!
+//! assert_eq!(celsius_to_fahrenheit(100.0), 212.0);
+//! assert_eq!(fahrenheit_to_celsius(32.0), 0.0);
+//! ```
+
+/// Ratio between the size of a Fahrenheit degree and a Celsius degree.
+pub const SCALE_FACTOR: f64 = 1.8;
+/// Fahrenheit reading at the freezing point of water.
+pub const FAHRENHEIT_OFFSET: f64 = 32.0;
+/// Kelvin reading at the freezing point of water.
+pub const KELVIN_OFFSET: f64 = 273.15;
+/// Kelvin reading at the boiling point of water, the zero of the Delisle scale.
+pub const KELVIN_BOILING_POINT: f64 = 373.15;
+/// Ratio between the size of a Réaumur degree and a Celsius degree.
+pub const REAUMUR_FACTOR: f64 = 0.8;
+/// Ratio between the size of a Delisle degree and a Celsius degree.
+pub const DELISLE_FACTOR: f64 = 1.5;
+/// Ratio between the size of a Newton degree and a Celsius degree.
+pub const NEWTON_FACTOR: f64 = 0.33;
+/// Ratio between the size of a Rømer degree and a Celsius degree.
+pub const ROMER_FACTOR: f64 = 0.525;
+/// Rømer reading at the freezing point of water.
+pub const ROMER_OFFSET: f64 = 7.5;
+
+/// Converts Celsius to Fahrenheit.
+///
+/// # Arguments
+/// * `celsius` - Temperature in Celsius.
+///
+/// # Returns
+/// * Temperature in Fahrenheit.
+pub fn celsius_to_fahrenheit(celsius: f64) -> f64 {
+    // Formula to convert Celsius to Fahrenheit
+    (celsius * SCALE_FACTOR) + FAHRENHEIT_OFFSET
+}
+
+/// Converts Celsius to Kelvin.
+///
+/// # Arguments
+/// * `celsius` - Temperature in Celsius.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn celsius_to_kelvin(celsius: f64) -> f64 {
+    // Formula to convert Celsius to Kelvin
+    celsius + KELVIN_OFFSET
+}
+
+/// Converts Fahrenheit to Celsius.
+///
+/// # Arguments
+/// * `fahrenheit` - Temperature in Fahrenheit.
+///
+/// # Returns
+/// * Temperature in Celsius.
+pub fn fahrenheit_to_celsius(fahrenheit: f64) -> f64 {
+    // Formula to convert Fahrenheit to Celsius
+    (fahrenheit - FAHRENHEIT_OFFSET) / SCALE_FACTOR
+}
+
+/// Converts Fahrenheit to Kelvin.
+///
+/// # Arguments
+/// * `fahrenheit` - Temperature in Fahrenheit.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn fahrenheit_to_kelvin(fahrenheit: f64) -> f64 {
+    // First convert Fahrenheit to Celsius, then Celsius to Kelvin
+    let celsius = fahrenheit_to_celsius(fahrenheit);
+    celsius_to_kelvin(celsius)
+}
+
+/// Converts Kelvin to Celsius.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Celsius.
+pub fn kelvin_to_celsius(kelvin: f64) -> f64 {
+    // Formula to convert Kelvin to Celsius
+    kelvin - KELVIN_OFFSET
+}
+
+/// Converts Kelvin to Fahrenheit.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Fahrenheit.
+pub fn kelvin_to_fahrenheit(kelvin: f64) -> f64 {
+    // First convert Kelvin to Celsius, then Celsius to Fahrenheit
+    let celsius = kelvin_to_celsius(kelvin);
+    celsius_to_fahrenheit(celsius)
+}
+
+/// Converts Kelvin to Rankine.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Rankine.
+pub fn kelvin_to_rankine(kelvin: f64) -> f64 {
+    // Rankine is an absolute scale with Fahrenheit sized degrees
+    kelvin * SCALE_FACTOR
+}
+
+/// Converts Rankine to Kelvin.
+///
+/// # Arguments
+/// * `rankine` - Temperature in Rankine.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn rankine_to_kelvin(rankine: f64) -> f64 {
+    rankine / SCALE_FACTOR
+}
+
+/// Converts Kelvin to Réaumur.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Réaumur.
+pub fn kelvin_to_reaumur(kelvin: f64) -> f64 {
+    // Réaumur shares the Celsius zero, with 80 degrees up to boiling water
+    kelvin_to_celsius(kelvin) * REAUMUR_FACTOR
+}
+
+/// Converts Réaumur to Kelvin.
+///
+/// # Arguments
+/// * `reaumur` - Temperature in Réaumur.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn reaumur_to_kelvin(reaumur: f64) -> f64 {
+    celsius_to_kelvin(reaumur / REAUMUR_FACTOR)
+}
+
+/// Converts Kelvin to Delisle.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Delisle.
+pub fn kelvin_to_delisle(kelvin: f64) -> f64 {
+    // Delisle counts downwards from the boiling point of water
+    (KELVIN_BOILING_POINT - kelvin) * DELISLE_FACTOR
+}
+
+/// Converts Delisle to Kelvin.
+///
+/// # Arguments
+/// * `delisle` - Temperature in Delisle.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn delisle_to_kelvin(delisle: f64) -> f64 {
+    KELVIN_BOILING_POINT - (delisle / DELISLE_FACTOR)
+}
+
+/// Converts Kelvin to Newton.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Newton.
+pub fn kelvin_to_newton(kelvin: f64) -> f64 {
+    // Newton shares the Celsius zero, with 33 degrees up to boiling water
+    kelvin_to_celsius(kelvin) * NEWTON_FACTOR
+}
+
+/// Converts Newton to Kelvin.
+///
+/// # Arguments
+/// * `newton` - Temperature in Newton.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn newton_to_kelvin(newton: f64) -> f64 {
+    celsius_to_kelvin(newton / NEWTON_FACTOR)
+}
+
+/// Converts Kelvin to Rømer.
+///
+/// # Arguments
+/// * `kelvin` - Temperature in Kelvin.
+///
+/// # Returns
+/// * Temperature in Rømer.
+pub fn kelvin_to_romer(kelvin: f64) -> f64 {
+    // Rømer places water freezing at 7.5 and boiling at 60
+    (kelvin_to_celsius(kelvin) * ROMER_FACTOR) + ROMER_OFFSET
+}
+
+/// Converts Rømer to Kelvin.
+///
+/// # Arguments
+/// * `romer` - Temperature in Rømer.
+///
+/// # Returns
+/// * Temperature in Kelvin.
+pub fn romer_to_kelvin(romer: f64) -> f64 {
+    celsius_to_kelvin((romer - ROMER_OFFSET) / ROMER_FACTOR)
+}